@@ -4,13 +4,60 @@ declare module '@/public/wasm/ai_trainer' {
         angles: Record<string, number>;
         errors: string[];
         repDetected: boolean;
+        repCount: number;
+        repReport: object | null;
+        setStatus: {
+            set_number: number,
+            reps: number,
+            velocity_loss: number,
+            reps_in_reserve: number | null,
+            stop_set: boolean
+        } | null;
+        hold: {
+            holding: boolean,
+            current: number,
+            total: number,
+            longest: number,
+            breaks: number,
+            broken_by: string[]
+        } | null;
+        recognition: {
+            exercise_id: string,
+            muscle_group: string,
+            confidence: number,
+            alternatives: { exercise_id: string, score: number }[]
+        } | null;
+        stage: {
+            index: number | null,
+            name: string | null,
+            stage_count: number,
+            completed: number
+        } | null;
     }
 
     export class ExerciseValidator {
-        constructor(exerciseId: string);
-        validatePose(keypoints: Record<string, {x: number, y: number}>): ValidationResult;
+        constructor(exerciseId: string, layout?: string);
+        loadDefinitions(definitions: string | object): string[];
+        setTempo(tempo?: string): void;
+        setRecognition(enabled: boolean): void;
+        setRepDetector(detector?: 'velocity' | 'peaks'): void;
+        startSet(): number;
+        endSet(): object | undefined;
+        recountSet(): object | undefined;
+        setReference(reference?: {angles: Record<string, number[]>, tolerance?: number, minSimilarity?: number}): void;
+        recordReference(): void;
+        reference(): {angles: Record<string, number[]>, tolerance: number, minSimilarity: number | null} | undefined;
+        validatePose(keypoints: Record<string, {x: number, y: number, z?: number, score?: number}>, timestamp: number): ValidationResult;
     }
 
+    export type LogLevel = 'debug' | 'info' | 'warn' | 'error';
+
+    export function set_log_level(level: LogLevel | 'off'): void;
+    export function set_log_rate_limit(intervalMs: number): void;
+    export function set_log_callback(
+        callback?: (level: LogLevel, target: string, message: string, suppressed: number) => void
+    ): void;
+
     export default function init(): Promise<{
         ExerciseValidator: typeof ExerciseValidator,
         ValidationResult: ValidationResult
@@ -45,9 +45,9 @@ export class ExerciseValidator {
         this.validator = new wasm.instance.exports.ExerciseValidator(exerciseId);
     }
 
-    validatePose(keypoints) {
+    validatePose(keypoints, timestamp) {
         const jsonStr = JSON.stringify(keypoints);
-        const resultPtr = this.validator.validate_pose(jsonStr);
+        const resultPtr = this.validator.validatePose(jsonStr, timestamp);
         const resultJson = getStringFromWasm(resultPtr);
         return JSON.parse(resultJson);
     }
@@ -10,6 +10,7 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
   const videoRef = useRef(null);
   const canvasRef = useRef(null);
   const analyzerRef = useRef(null);
+  const overlayCanvasRef = useRef(null);
   const animationIdRef = useRef(null);
   const [isWasmReady, setWasmReady] = useState(false);
   const [cameraState, setCameraState] = useState('loading');
@@ -24,6 +25,7 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
         
         // 2. Initialize exercise analyzer
         analyzerRef.current = new ExerciseAnalyzer(exerciseId);
+        analyzerRef.current.setOverlay(true);
         
         // 3. Start camera stream
         const stream = await navigator.mediaDevices.getUserMedia({
@@ -75,8 +77,12 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
         // 1. Prepare canvas
         const canvas = canvasRef.current;
         const ctx = canvas.getContext('2d');
-        canvas.width = videoRef.current.videoWidth;
-        canvas.height = videoRef.current.videoHeight;
+        const { videoWidth, videoHeight } = videoRef.current;
+        if (canvas.width !== videoWidth || canvas.height !== videoHeight) {
+          canvas.width = videoWidth;
+          canvas.height = videoHeight;
+          analyzerRef.current.setOverlaySize(videoWidth, videoHeight);
+        }
         
         // 2. Draw video frame
         ctx.drawImage(videoRef.current, 0, 0, canvas.width, canvas.height);
@@ -86,13 +92,13 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
         const keypoints = await detectPose(imageData); // Your pose detection logic
         
         // 4. Process with WASM
-        const result = await analyzerRef.current.process_frame(
+        const result = await analyzerRef.current.processFrame(
           keypointsToFloat32Array(keypoints),
           timestamp
         );
         
         // 5. Render feedback
-        renderFeedback(ctx, result);
+        renderFeedback(ctx, result, analyzerRef.current.overlay());
         
         // 6. Pass analysis to parent
         onAnalysis({
@@ -124,21 +130,25 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
   };
 
   // Helper: Render WASM analysis results
-  const renderFeedback = (ctx, analysis) => {
+  const renderFeedback = (ctx, analysis, overlayData) => {
     // 1. Clear previous frame
     ctx.clearRect(0, 0, ctx.canvas.width, ctx.canvas.height);
     
     // 2. Draw video frame
     ctx.drawImage(videoRef.current, 0, 0);
     
-    // 3. Draw neon overlay (from WASM)
-    if (analysis.overlay_data) {
+    // 3. Draw neon overlay (RGBA from WASM), via a scratch canvas so it blends over the video
+    if (overlayData && overlayData.length === ctx.canvas.width * ctx.canvas.height * 4) {
       const imgData = new ImageData(
-        new Uint8ClampedArray(analysis.overlay_data),
+        new Uint8ClampedArray(overlayData.buffer, overlayData.byteOffset, overlayData.length),
         ctx.canvas.width,
         ctx.canvas.height
       );
-      ctx.putImageData(imgData, 0, 0);
+      const overlay = overlayCanvasRef.current || (overlayCanvasRef.current = document.createElement('canvas'));
+      overlay.width = ctx.canvas.width;
+      overlay.height = ctx.canvas.height;
+      overlay.getContext('2d').putImageData(imgData, 0, 0);
+      ctx.drawImage(overlay, 0, 0);
     }
     
     // 4. Draw form feedback
@@ -148,7 +158,29 @@ const CameraFeed = ({ exerciseId, onAnalysis, onError }) => {
       ctx.fillText(error, 20, 30 + i * 20);
     });
     
-    // 5. Draw rep counter
+    // 5. Ask for a better camera angle when joints drop out of view
+    if (analysis.occluded_landmarks && analysis.occluded_landmarks.length > 0) {
+      ctx.fillStyle = '#f39c12';
+      ctx.font = '16px Arial';
+      ctx.fillText(
+        `Adjust camera: can't see ${analysis.occluded_landmarks.join(', ').replace(/_/g, ' ')}`,
+        20,
+        ctx.canvas.height - 60
+      );
+    }
+
+    // The pose model doesn't track some landmark the exercise needs, no camera angle fixes that
+    if (analysis.unavailable_angles && analysis.unavailable_angles.length > 0) {
+      ctx.fillStyle = '#e74c3c';
+      ctx.font = '16px Arial';
+      ctx.fillText(
+        `This pose model can't measure ${analysis.unavailable_angles.join(', ').replace(/_/g, ' ')}`,
+        20,
+        ctx.canvas.height - 80
+      );
+    }
+
+    // 6. Draw rep counter
     ctx.fillStyle = '#3498db';
     ctx.font = 'bold 24px Arial';
     ctx.fillText(`Reps: ${analysis.rep_count}`, 20, ctx.canvas.height - 30);
@@ -2,12 +2,14 @@ import { useEffect, useRef, useState } from 'react';
 import { useWasm } from './WASMcontext/useWasm';
 import { useExerciseStore } from '@/state/useExerciseStore';
 import FeedbackDisplay from '@/components/Camera/FeedbackDisplay';
+import StepIndicator from '@/components/Exercises/components/StepIndicator';
 import PoseRenderer from '@/tracking/pose/PoseRenderer';
 
 export default function CameraSetup({ exerciseId }) {
   const videoRef = useRef(null);
   const canvasRef = useRef(null);
   const [pose, setPose] = useState(null);
+  const [stage, setStage] = useState(null);
   const { validator, loading, error, validatePose } = useWasm(exerciseId);
   const { startSession, updateRepCount, addFormError } = useExerciseStore();
 
@@ -27,15 +29,16 @@ export default function CameraSetup({ exerciseId }) {
         poseDetector = await initializePoseDetector();
         startSession(exerciseId);
         
-        const detectPose = async () => {
+        const detectPose = async (timestamp = performance.now()) => {
           if (videoRef.current && canvasRef.current) {
             const detectedPose = await poseDetector.estimatePose(videoRef.current);
             setPose(detectedPose);
             
             if (validator && detectedPose) {
-              const validation = await validatePose(detectedPose.keypoints);
-              if (validation.repDetected) updateRepCount();
+              const validation = await validatePose(detectedPose.keypoints, timestamp);
+              if (validation.repDetected) updateRepCount(validation.repReport);
               if (validation.errors.length) addFormError(validation.errors);
+              setStage(validation.stage ?? null);
             }
           }
           animationFrameId = requestAnimationFrame(detectPose);
@@ -69,6 +72,14 @@ export default function CameraSetup({ exerciseId }) {
       </div>
       
       <div className="feedback-panel">
+        {stage && (
+          // The engine tracks the step, the dots only show it
+          <StepIndicator
+            totalSteps={stage.stage_count}
+            currentStep={stage.index}
+            onChange={() => {}}
+          />
+        )}
         <FeedbackDisplay 
           exerciseId={exerciseId} 
           pose={pose} 
@@ -13,44 +13,34 @@ export default function SetTracker({ exerciseId }) {
     completeSet,
     startRestPeriod,
     endRestPeriod,
-    updateExerciseStats
+    updateExerciseStats,
+    repCount,
+    lastRepReport
   } = useExerciseStore();
-  const { validator, validatePose } = useWasm(exerciseId);
-  const [activeReps, setActiveReps] = useState(0);
+  const { validator } = useWasm(exerciseId);
   const [isResting, setIsResting] = useState(false);
+  const [lastSetSummary, setLastSetSummary] = useState(null);
 
-  // Handle rep validation
-  const handleValidRep = () => {
-    const newRepCount = activeReps + 1;
-    setActiveReps(newRepCount);
-    
-    if (newRepCount >= reps) {
-      completeSet();
-      startRestPeriod();
-      setIsResting(true);
-    }
-  };
+  // The engine tracks each set between rests, for velocity loss and reps in reserve
+  useEffect(() => {
+    if (validator && !isResting) validator.startSet();
+  }, [validator, isResting]);
 
-  // Connect with WASM validator
+  // Reps come from the engine, CameraSetup reports each one to the store
   useEffect(() => {
-    if (!validator) return;
-    
-    // In a real implementation, this would come from camera validation
-    // For now simulating rep completion every 3 seconds
-    const interval = setInterval(() => {
-      if (!isResting && activeReps < reps) {
-        handleValidRep();
-      }
-    }, 3000);
+    if (isResting || repCount < reps) return;
 
-    return () => clearInterval(interval);
-  }, [validator, activeReps, reps, isResting]);
+    const summary = validator?.endSet();
+    if (summary) setLastSetSummary(summary);
+    completeSet();
+    startRestPeriod();
+    setIsResting(true);
+  }, [validator, repCount, reps, isResting, completeSet, startRestPeriod]);
   
   // Handle rest period completion
   const handleRestEnd = () => {
     endRestPeriod();
     setIsResting(false);
-    setActiveReps(0);
   };
 
   // Update exercise stats when sets are completed
@@ -73,7 +63,7 @@ export default function SetTracker({ exerciseId }) {
           isResting={isResting}
         />
         <div className="rep-display">
-          {activeReps}/{reps} reps
+          {repCount}/{reps} reps
         </div>
       </div>
 
@@ -86,9 +76,16 @@ export default function SetTracker({ exerciseId }) {
 
       <div className="validation-feedback">
         {/* This would display real-time form feedback */}
-        {activeReps > 0 && (
+        {lastRepReport && (
           <div className="form-accuracy">
-            Last rep form accuracy: 92%
+            Last rep form accuracy: {Math.round(lastRepReport.quality)}%
+          </div>
+        )}
+        {isResting && lastSetSummary && (
+          <div className="set-fatigue">
+            Velocity loss: {Math.round(lastSetSummary.velocity_loss * 100)}%
+            {lastSetSummary.reps_in_reserve !== null &&
+              ` (~${Math.round(lastSetSummary.reps_in_reserve)} reps in reserve)`}
           </div>
         )}
       </div>
@@ -1,4 +1,5 @@
 import { WasmExerciseValidator } from '@/libs/wasm/adapter';
+import type { HoldReport, Recognition, RepReport, SetStatus, StageStatus } from '@/public/wasm/ai_trainer';
 
 export interface WasmState {
   validator: WasmExerciseValidator | null;
@@ -6,11 +7,16 @@ export interface WasmState {
   error: string | null;
   performance: number | null;
   initializeValidator: (exerciseId: string) => Promise<WasmExerciseValidator>;
-  validatePose: (keypoints: Record<string, any>) => Promise<{
+  validatePose: (keypoints: Record<string, any>, timestamp?: number) => Promise<{
     angles: Record<string, number>;
     errors: string[];
     repDetected: boolean;
     repCount: number;
+    repReport: RepReport | null;
+    setStatus: SetStatus | null;
+    hold: HoldReport | null;
+    recognition: Recognition | null;
+    stage: StageStatus | null;
     performance: number;
   }>;
 }
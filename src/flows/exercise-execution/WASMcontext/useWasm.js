@@ -61,14 +61,14 @@ export function useWasm(exerciseId) {
     };
   }, [exerciseId, initializeValidator]);
 
-  const validatePose = useCallback(async (keypoints) => {
+  const validatePose = useCallback(async (keypoints, timestamp = performance.now()) => {
     if (!state.validator) {
       throw new Error('WASM validator not initialized');
     }
     
     try {
       const start = performance.now();
-      const result = await state.validator.validatePose(keypoints);
+      const result = await state.validator.validatePose(keypoints, timestamp);
       const validationTime = performance.now() - start;
       
       return {
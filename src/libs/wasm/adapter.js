@@ -11,7 +11,9 @@ export async function createValidator(exerciseId, fallback = true) {
   try {
     const validator = await createWasmValidator(exerciseId);
     return {
-      validatePose: (keypoints) => validator.validatePose(keypoints),
+      validatePose: (keypoints, timestamp = performance.now()) => validator.validatePose(keypoints, timestamp),
+      startSet: () => validator.startSet(),
+      endSet: () => validator.endSet(),
       type: 'wasm'
     };
   } catch (error) {
@@ -20,7 +22,11 @@ export async function createValidator(exerciseId, fallback = true) {
       console.log('Falling back to JavaScript validator');
       const jsValidator = new JsValidator(exerciseId);
       return {
+        // The JS validator keeps no timing
         validatePose: (keypoints) => jsValidator.validatePose(keypoints),
+        // The JS validator has no notion of sets
+        startSet: () => null,
+        endSet: () => undefined,
         type: 'js'
       };
     }
@@ -3,21 +3,171 @@ declare module '@/public/wasm/ai_trainer' {
   export interface Keypoint {
     x: number;
     y: number;
-    score: number;
+    z?: number;
+    score?: number;
   }
 
+  export interface FormIssue {
+    code: string;
+    severity: 'info' | 'warning' | 'critical';
+    joint: string | null;
+    measured: number | null;
+    target: { min: number | null; max: number | null };
+    phase: 'concentric' | 'eccentric' | 'static_hold' | 'none' | null;
+    message: string;
+  }
+
+  // Times in seconds, angles in degrees of the exercise's primary joint
+  export interface RepReport {
+    number: number;
+    min_angle: number;
+    max_angle: number;
+    rom_percentage: number; // 0-1
+    duration: number;
+    concentric_time: number;
+    eccentric_time: number;
+    bottom_pause_time: number;
+    top_pause_time: number;
+    peak_velocity: number;
+    form_issues: FormIssue[];
+    quality: number; // 0-100
+    tempo_adherence: number | null; // 0-100 when a tempo is prescribed
+    reference: RepComparison | null; // When the exercise has a reference rep
+  }
+
+  // A rep aligned against the reference rep. deviation is where it strayed furthest:
+  // progress 0-1 through the reference, time in seconds into the rep
+  export interface RepComparison {
+    similarity: number; // 0-100
+    mean_deviation: number; // Degrees
+    deviation: {
+      angle: string;
+      progress: number;
+      time: number;
+      measured: number;
+      reference: number;
+    } | null;
+  }
+
+  // Same shape as "reference" in exercise.schema.json
+  export interface ReferenceRep {
+    angles: Record<string, number[]>;
+    tolerance: number;
+    minSimilarity: number | null;
+  }
+
+  // Velocity loss is 0-1, the latest rep against the fastest of the set
+  export interface SetStatus {
+    set_number: number;
+    reps: number;
+    velocity_loss: number;
+    reps_in_reserve: number | null;
+    stop_set: boolean;
+  }
+
+  export interface SetSummary {
+    set_number: number;
+    reps: RepReport[];
+    duration: number;
+    average_quality: number;
+    best_velocity: number;
+    velocity_loss: number;
+    reps_in_reserve: number | null;
+    stopped_for_fatigue: boolean;
+  }
+
+  // Isometric exercises, times in seconds. The clock runs only while the hold's angles
+  // are in range and no warning-level issue is raised
+  export interface HoldReport {
+    holding: boolean;
+    current: number;
+    total: number;
+    longest: number;
+    breaks: number;
+    broken_by: string[]; // Angle keys and issue codes pausing the clock
+  }
+
+  // Exercises with stages or steps. index is the step last reached, null before the
+  // first one, and is what StepIndicator lights up
+  export interface StageStatus {
+    index: number | null;
+    name: string | null;
+    stage_count: number;
+    completed: number;
+  }
+
+  // Which exercise the last few seconds of movement look like, see setRecognition.
+  // alternatives are exercises the angles can't tell apart from it
+  export interface Recognition {
+    exercise_id: string;
+    muscle_group: string;
+    confidence: number; // 0-1
+    alternatives: { exercise_id: string; score: number }[];
+  }
+
+  // A recorded set counted again by the peak detector, see recountSet. Times in seconds
+  export interface RepSegment {
+    number: number;
+    start: number;
+    turn: number; // The opposite extreme, e.g. the bottom of a squat
+    end: number;
+    min_angle: number;
+    max_angle: number;
+  }
+
+  export interface Recount {
+    live: number;
+    recounted: number;
+    reps: RepSegment[];
+  }
+
+  export type RepDetector = 'velocity' | 'peaks';
+
   export interface ValidationResult {
     angles: Record<string, number>;
     errors: string[];
     repDetected: boolean;
     repCount: number;
+    repReport: RepReport | null;
+    setStatus: SetStatus | null;
+    hold: HoldReport | null;
+    recognition: Recognition | null;
+    stage: StageStatus | null;
   }
 
   export class ExerciseValidator {
-    constructor(exerciseId: string);
-    validate(keypoints: Record<string, Keypoint>): ValidationResult;
+    constructor(exerciseId: string, layout?: string);
+    // An exercises file (see src/data/exercises/exercise.schema.json) as JSON/TOML text or parsed; returns the loaded ids
+    loadDefinitions(definitions: string | object): string[];
+    // Eccentric-pause-concentric-pause seconds, e.g. "3-1-2-0"; no argument returns to the exercise's tempo
+    setTempo(tempo?: string): void;
+    // Recognize the exercise being performed; a confident mismatch adds a "wrong_exercise" issue
+    setRecognition(enabled: boolean): void;
+    // How reps are counted; no argument returns to the exercise's detector
+    setRepDetector(detector?: RepDetector): void;
+    // Reps validated from here on count towards a new set; returns its number
+    startSet(): number;
+    // Counts a last rep the peak detector is still confirming, so recountSet agrees after it
+    endSet(): SetSummary | undefined;
+    // The set so far counted offline with the peak detector; undefined for holds and unknown exercises
+    recountSet(): Recount | undefined;
+    // Compare every rep against a reference rep; no argument stops comparing
+    setReference(reference?: Partial<ReferenceRep> & { angles: Record<string, number[]> }): void;
+    // The next full rep becomes the reference
+    recordReference(): void;
+    reference(): ReferenceRep | undefined;
+    // Timestamp in milliseconds, e.g. the requestAnimationFrame time or performance.now()
+    validatePose(keypoints: Record<string, Keypoint>, timestamp: number): ValidationResult;
   }
 
+  export type LogLevel = 'debug' | 'info' | 'warn' | 'error';
+
+  export function set_log_level(level: LogLevel | 'off'): void;
+  export function set_log_rate_limit(intervalMs: number): void;
+  export function set_log_callback(
+    callback?: (level: LogLevel, target: string, message: string, suppressed: number) => void
+  ): void;
+
   export default function init(): Promise<void>;
 }
 const point = new Vector2(wasmData.x, wasmData.y);
\ No newline at end of file
@@ -0,0 +1,76 @@
+import { create } from 'zustand';
+
+// Form errors kept for the session, the oldest are dropped first
+const MAX_FORM_ERRORS = 50;
+
+// The exercise being performed: the set plan, the reps the WASM engine has counted in the
+// current set and its RepReport for each of them
+export const useExerciseStore = create((set, get) => ({
+  currentExercise: null,
+  sets: 3,
+  reps: 10,
+  restTime: 60,
+  currentSet: 1,
+  isResting: false,
+
+  // Reps of the current set, each with the engine's report (see RepReport in
+  // src/libs/wasm/types.d.ts)
+  repCount: 0,
+  repReports: [],
+  lastRepReport: null,
+  formErrors: [],
+
+  exerciseStats: [],
+  wasmPerformance: null,
+
+  startSession: (exerciseId) => {
+    set({
+      currentExercise: { id: exerciseId, startedAt: new Date().toISOString() },
+      currentSet: 1,
+      isResting: false,
+      repCount: 0,
+      repReports: [],
+      lastRepReport: null,
+      formErrors: []
+    });
+  },
+
+  // Called with validatePose's repReport whenever the engine detects a rep. Movement
+  // during the rest period doesn't count towards the next set
+  updateRepCount: (report = null) => {
+    if (get().isResting) return;
+    set(state => ({
+      repCount: state.repCount + 1,
+      repReports: report ? [...state.repReports, report] : state.repReports,
+      lastRepReport: report ?? state.lastRepReport
+    }));
+  },
+
+  completeRep: () => get().updateRepCount(),
+
+  addFormError: (errors) => {
+    set(state => ({
+      formErrors: [...state.formErrors, ...errors].slice(-MAX_FORM_ERRORS)
+    }));
+  },
+
+  completeSet: () => {
+    set(state => ({
+      currentSet: state.currentSet + 1,
+      repCount: 0,
+      repReports: []
+    }));
+  },
+
+  startRestPeriod: () => set({ isResting: true }),
+
+  endRestPeriod: () => set({ isResting: false }),
+
+  updateExerciseStats: (stats) => {
+    set(state => ({ exerciseStats: [...state.exerciseStats, stats] }));
+  },
+
+  setWasmPerformance: (loadTime) => set({ wasmPerformance: loadTime })
+}));
+
+export default useExerciseStore;
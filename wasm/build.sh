@@ -2,7 +2,7 @@
 set -e
 
 # Build with wasm-pack
-wasm-pack build --target web --out-name ai_trainer --out-dir ./out
+wasm-pack build --target web --out-name ai_trainer --out-dir ./out -- --features wasm
 
 # Move files to public directory
 mkdir -p ../public/wasm
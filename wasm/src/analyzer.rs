@@ -0,0 +1,579 @@
+// wasm/src/analyzer.rs
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+use crate::definition::{ExerciseSet, StepDefinition};
+use crate::filter::{FilterConfig, KeypointFilter};
+use crate::form::{self, FormIssue, Severity};
+use crate::hold::{HoldReport, HoldTimer};
+use crate::logging::{self, Level};
+use crate::neon_render::{NeonRenderer, Palette};
+use crate::pose_detection::{self, PoseAnalyzer, Unmeasurable};
+use crate::recognition::{self, ExerciseRecognizer, Recognition};
+use crate::reference::{ReferenceRep, RepMatcher, RepRecorder};
+use crate::rep_counter::{MovementPhase, RepCounter, RepReport};
+use crate::rules::{RuleContext, RuleSet};
+use crate::segmentation::{Recount, RepDetector};
+use crate::sequence::{Stage, StageSequence, StageStatus};
+use crate::session::{SetConfig, SetSession, SetStatus, SetSummary};
+use crate::skeleton::{KeypointLayout, Side, Skeleton};
+use crate::symmetry::SymmetryTracker;
+use crate::tempo::Tempo;
+
+pub const SUPPORTED_EXERCISES: &[&str] = &[
+    // Quadriceps
+    "squat", "bulgarian-splits", "bodyweight-lunges", "goblet-squats",
+    "weighted-lunges", "side-squats", "obstacle-overstep-touches",
+    "stand-ups", "squat-steps", "front-squats", "leg-extensions",
+
+    // Abs/Core
+    "jack-knife", "back_arch", "lateral-leg-raises", "lateral-sit-ups",
+    "dumbbell-leg-raises", "russian-roulette", "hanging-leg-raises",
+    "ab-wheel-rollout", "reverse-crunch", "scissor-kicks", "plank-hip-dips",
+    "situp", "plank", "russian-twist", "bicycle-crunches", "toe-touches",
+
+    // Back
+    "dumbbell-rows", "barbell-rows", "seated-dumbbell-rows", "chin-up-pull-ups",
+    "open-butterfly", "lateral-russian-roulette", "deadlifts", "pull-ups",
+    "face-pulls", "t-bar-rows", "lat-pulldown", "reverse-fly", "hyperextensions",
+
+    // Biceps
+    "isolated-dumbbell-curls", "barbell-curls", "dumbbell-curls",
+    "open-grip-pull-ups", "lateral-push-ups", "half-rep-curls",
+    "resistance-bands-pull", "outward-dumbbell-curls", "concentration-curls",
+    "zottman-curls", "hammer-curls", "preacher-curls",
+
+    // Calves
+    "bench-calf-raises", "plate-raises", "bulgarian-raises", "barbell-raises",
+    "jump-rope", "donkey-calf-raises", "seated-calf-raises", "stair-calf-raises",
+    "farmer-walk-on-toes", "pogo-jumps", "standing-calf-raises",
+
+    // Chest
+    "pushup", "inner-push-ups", "superman-push-ups", "butterfly",
+    "dumbbell-overhead", "military-press", "bench-press-dumbbell",
+    "bench-press-barbell", "bench-butterfly", "dumbbell-fly", "chest-dips",
+    "incline-bench-press", "decline-bench-press",
+
+    // Glutes
+    "superman", "good-morning", "yoga-ball-glute-raises", "donkey-kick",
+    "inverse-kick-back", "barbell-bench-touches", "barbell-hip-thrust",
+    "curtsy-lunges", "cable-pull-through", "frog-pumps", "glute-bridge",
+    "single-leg-hip-thrust", "clamshells",
+
+    // Hamstrings
+    "romanian-deadlifts", "hamstring-curls", "kettlebell-good-morning",
+    "glute-ham-raises", "single-leg-deadlifts", "seated-leg-curls",
+    "nordic-hamstring-curls", "stiff-leg-deadlifts", "swiss-ball-hamstring-curls",
+    "reverse-hyperextensions", "sliding-leg-curls",
+
+    // Shoulders
+    "lateral-dumbbell-raises", "frontal-dumbbell-raises", "dumbbell-shrugs",
+    "bench-dumbbell-raises", "exterior-dumbbell-raises", "arnold-press",
+    "military-press", "handstand-pushups", "overhead-press", "rear-delt-fly",
+    "face-pulls", "upright-rows",
+
+    // Triceps
+    "closed-grip-barbell", "lateral-barbell-extensions", "diamond-push-ups",
+    "dumbbell-dips", "bench-dips", "lateral-dumbbell-raises",
+    "hammer-dumbbell-raises", "forearm-push-ups", "barbell-overhead",
+    "tricep-rope-pushdown", "skull-crushers", "close-grip-bench-press",
+
+    // Full Body/Compound
+    "burpees", "kettlebell-swings", "thrusters", "clean-and-jerk",
+    "snatch", "box-jumps", "jumping-jacks", "mountain-climbers"
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisResult {
+    pub rep_count: u32,
+    pub rep_detected: bool, // A rep completed on this frame
+    pub rep_report: Option<RepReport>, // Its report, when rep_detected
+    pub set_status: Option<SetStatus>, // While a set is running, see start_set
+    pub hold: Option<HoldReport>, // Isometric exercises only
+    pub recognition: Option<Recognition>, // With set_recognition(true), once enough movement is seen
+    pub stage: Option<StageStatus>, // Exercises with stages or steps, for step indicators
+    pub engagement: f32,
+    pub form_issues: Vec<FormIssue>,
+    pub form_errors: Vec<String>, // Default English rendering of form_issues, same order
+    pub angles: HashMap<String, f32>, // Per side and averaged, see pose_detection::bilateral_angles
+    pub left_angles: HashMap<String, f32>,
+    pub right_angles: HashMap<String, f32>,
+    pub symmetry: HashMap<String, f32>, // Symmetry index per joint pair, 0 = symmetric
+    pub occluded_landmarks: Vec<String>, // Landmarks too uncertain to measure, so the UI can ask for a better camera angle
+    pub unavailable_landmarks: Vec<String>, // Landmarks the exercise needs that the pose model doesn't track at all
+    pub unavailable_angles: Vec<String>, // Angles never measured for want of them, reps or rules on these won't fire
+}
+
+#[derive(Debug)]
+pub struct ExerciseAnalyzer {
+    rep_counter: RepCounter,
+    pose_analyzer: PoseAnalyzer,
+    form_rules: RuleSet,
+    keypoint_filter: KeypointFilter,
+    symmetry_tracker: SymmetryTracker,
+    neon_renderer: NeonRenderer,
+    overlay: Option<Vec<u8>>, // Last frame's overlay, None unless set_overlay(true)
+    current_exercise: String,
+    layout: KeypointLayout, // Keypoint layout of the pose model feeding us
+    unmeasurable: Unmeasurable, // What the exercise measures that the layout can't supply
+    last_frame_time: f64,
+    session_time: f64, // Seconds since the first frame
+    rep_issues: Vec<FormIssue>, // Raised since the last completed rep
+    set_session: SetSession,
+    hold_timer: Option<HoldTimer>, // Set for isometric exercises, see hold.rs
+    stage_sequence: Option<StageSequence>, // Set for multi-stage exercises, see sequence.rs
+    recognizer: Option<ExerciseRecognizer>, // Off unless set_recognition(true), see recognition.rs
+    reference: Option<RepMatcher>, // Set when the exercise has a reference rep, see reference.rs
+    reference_recorder: Option<RepRecorder>, // Between record_reference and the next full rep
+    tempo_override: Option<Tempo>,
+    detector_override: Option<RepDetector>,
+    last_phase: MovementPhase,
+    phase_started: f64, // Session time in seconds when last_phase began
+}
+
+impl ExerciseAnalyzer {
+    pub fn new(exercise_id: &str, layout: KeypointLayout) -> Self {
+        logging::info("analyzer", &format!("Initializing analyzer for {} ({:?})", exercise_id, layout));
+
+        let pose_analyzer = PoseAnalyzer::new();
+        let form_rules = RuleSet::new(pose_analyzer.form_rules(exercise_id));
+        let rep_counter = RepCounter::new(exercise_id);
+        let hold_timer = hold_timer(&rep_counter, &pose_analyzer, exercise_id);
+        let stage_sequence = stage_sequence(&pose_analyzer, exercise_id);
+        let unmeasurable = unmeasurable(&pose_analyzer, exercise_id, layout);
+
+        ExerciseAnalyzer {
+            rep_counter,
+            pose_analyzer,
+            form_rules,
+            keypoint_filter: KeypointFilter::new(FilterConfig::default()),
+            symmetry_tracker: SymmetryTracker::new(),
+            neon_renderer: NeonRenderer::new(0.0, 1.0, 1.0), // Cyan neon
+            overlay: None,
+            current_exercise: exercise_id.to_string(),
+            layout,
+            unmeasurable,
+            last_frame_time: 0.0,
+            session_time: 0.0,
+            rep_issues: Vec::new(),
+            set_session: SetSession::new(SetConfig::for_exercise(exercise_id)),
+            hold_timer,
+            stage_sequence,
+            recognizer: None,
+            reference: None,
+            reference_recorder: None,
+            tempo_override: None,
+            detector_override: None,
+            last_phase: MovementPhase::None,
+            phase_started: 0.0,
+        }
+    }
+
+    pub fn layout(&self) -> KeypointLayout {
+        self.layout
+    }
+
+    // Exercises in the set that define their own angles replace the built-in analysis
+    // for their ids; returns those ids
+    pub fn load_definitions(&mut self, set: &ExerciseSet) -> Result<Vec<String>, String> {
+        let loaded = self.pose_analyzer.load_definitions(set)?;
+
+        let current = set.engine_exercises().find(|exercise| exercise.id == self.current_exercise);
+        if let Some(rep) = current.and_then(|exercise| exercise.rep.as_ref()) {
+            self.rep_counter = RepCounter::from_definition(rep);
+            self.rep_counter.set_detector(self.detector_override);
+        }
+        self.hold_timer = hold_timer(&self.rep_counter, &self.pose_analyzer, &self.current_exercise);
+        self.stage_sequence = stage_sequence(&self.pose_analyzer, &self.current_exercise);
+        self.unmeasurable = unmeasurable(&self.pose_analyzer, &self.current_exercise, self.layout);
+        // Validation rules, steps and a reference rep from the file apply to catalog entries
+        // too, the steps taking over from the built-in stages
+        let exercise = set.exercises.iter().find(|exercise| exercise.id == self.current_exercise);
+        let angles = self.pose_analyzer.sample_angles(&self.current_exercise);
+        let mut form_rules = self.pose_analyzer.form_rules(&self.current_exercise);
+        if let Some(exercise) = exercise {
+            for warning in exercise.warnings() {
+                logging::warn("definitions", &format!("{}: {}", self.current_exercise, warning));
+            }
+            let (rules, unmeasured) = exercise.validation_form_rules(&angles);
+            if !unmeasured.is_empty() {
+                logging::warn(
+                    "definitions",
+                    &format!("Validation rules {} of {} read angles its analyzer doesn't measure", unmeasured.join(", "), self.current_exercise),
+                );
+            }
+            form_rules.extend(rules);
+        }
+        self.form_rules = RuleSet::new(form_rules);
+        if let Some(steps) = exercise.map(|exercise| &exercise.steps).filter(|steps| !steps.is_empty()) {
+            let stages: Vec<Stage> = steps.iter().map(StepDefinition::stage).collect();
+            // A step targeting an angle the analyzer never measures can't be reached
+            for stage in &stages {
+                let unmeasured = stage.unmeasured(&angles);
+                if !unmeasured.is_empty() {
+                    logging::warn(
+                        "definitions",
+                        &format!(
+                            "Step '{}' of {} targets {}, which its analyzer doesn't measure",
+                            stage.name(),
+                            self.current_exercise,
+                            unmeasured.join(", ")
+                        ),
+                    );
+                }
+            }
+            self.stage_sequence = Some(StageSequence::cycle(stages));
+        }
+        if let Some(reference) = exercise.and_then(|exercise| exercise.reference.clone()) {
+            self.reference = Some(self.matcher(reference));
+        }
+
+        logging::info("definitions", &format!("Loaded {} exercise definitions for {}", loaded.len(), set.muscle_group));
+        Ok(loaded)
+    }
+
+    // Reps completed from here on count towards the new set, hold time and any half-done
+    // multi-stage rep start over; returns its number
+    pub fn start_set(&mut self) -> u32 {
+        if let Some(timer) = &mut self.hold_timer {
+            timer.reset();
+        }
+        if let Some(sequence) = &mut self.stage_sequence {
+            sequence.reset();
+        }
+        if let Some(reference) = &mut self.reference {
+            reference.reset();
+        }
+        self.rep_counter.start_trace();
+        self.set_session.start_set(self.session_time)
+    }
+
+    // A last rep the peak detector hasn't confirmed yet counts towards the set it ends
+    pub fn end_set(&mut self) -> Option<SetSummary> {
+        if let Some(mut report) = self.rep_counter.finish_set() {
+            let mut issues = std::mem::take(&mut self.rep_issues);
+            if let Some(tempo) = self.tempo() {
+                issues.extend(tempo.check(&report));
+                report.tempo_adherence = Some(tempo.adherence(&report));
+            }
+            form::prioritize(&mut issues);
+            let report = report.with_form_issues(issues);
+            logging::info("rep_counter", &format!("Rep {} detected at the end of the set! Quality {:.0}", report.number, report.quality));
+            self.set_session.add_rep(&report);
+        }
+        self.set_session.end_set(self.session_time)
+    }
+
+    // The set so far counted again offline by the peak detector, to verify the live
+    // count. Covers everything since start_set, or since the analyzer was created
+    pub fn recount_set(&self) -> Option<Recount> {
+        self.rep_counter.recount()
+    }
+
+    pub fn set_session(&self) -> &SetSession {
+        &self.set_session
+    }
+
+    pub fn set_set_config(&mut self, config: SetConfig) {
+        self.set_session.set_config(config);
+    }
+
+    // Session prescription, e.g. a slow-eccentric block, over the exercise's own tempo.
+    // None goes back to the exercise's
+    pub fn set_tempo(&mut self, tempo: Option<Tempo>) {
+        self.tempo_override = tempo;
+    }
+
+    // Overrides the exercise's rep detector, e.g. to try the peak detector on a built-in
+    // exercise. None goes back to the exercise's
+    pub fn set_rep_detector(&mut self, detector: Option<RepDetector>) {
+        self.detector_override = detector;
+        self.rep_counter.set_detector(detector);
+    }
+
+    pub fn tempo(&self) -> Option<Tempo> {
+        self.tempo_override.or_else(|| self.rep_counter.tempo())
+    }
+
+    // Classifies the movement alongside the analysis and flags a mismatch with the
+    // exercise being analyzed. Costs every group's angle math per frame, so it is opt-in
+    pub fn set_recognition(&mut self, enabled: bool) {
+        self.recognizer = enabled.then(ExerciseRecognizer::new);
+    }
+
+    // Compares every rep against `reference`, over the exercise file's. None stops comparing
+    pub fn set_reference(&mut self, reference: Option<ReferenceRep>) -> Result<(), String> {
+        if let Some(reference) = &reference {
+            reference.validate()?;
+        }
+        self.reference = reference.map(|reference| self.matcher(reference));
+        Ok(())
+    }
+
+    // Before the first frame the first rep is whole, after it the rep in progress isn't
+    fn matcher(&self, reference: ReferenceRep) -> RepMatcher {
+        let matcher = RepMatcher::new(reference);
+        if self.last_frame_time > 0.0 { matcher.from_next_rep() } else { matcher }
+    }
+
+    // The next full rep becomes the reference, compared against from the rep after. The
+    // primary and secondary joints of the rep counter are recorded
+    pub fn record_reference(&mut self) {
+        let mut joints = vec![self.rep_counter.primary_joint().to_string()];
+        joints.extend(self.rep_counter.secondary_joints().iter().cloned());
+        joints.retain(|joint| !joint.is_empty());
+        self.reference_recorder = Some(RepRecorder::new(joints));
+    }
+
+    // The reference in use, e.g. to store a recorded one
+    pub fn reference(&self) -> Option<&ReferenceRep> {
+        self.reference.as_ref().map(RepMatcher::reference)
+    }
+
+    pub fn set_filter(&mut self, config: FilterConfig) {
+        self.keypoint_filter.set_config(config);
+    }
+
+    // Renders the neon overlay with every frame, read back with overlay(). Off by default,
+    // a full-size RGBA frame is far more than most callers want per frame
+    pub fn set_overlay(&mut self, enabled: bool) {
+        self.overlay = enabled.then(Vec::new);
+    }
+
+    // Neon overlay of the last frame as RGBA pixels, or PNG bytes once set_overlay_png(true).
+    // Empty while the overlay is off
+    pub fn overlay(&self) -> &[u8] {
+        self.overlay.as_deref().unwrap_or_default()
+    }
+
+    // Overlay resolution, should match the canvas it is drawn onto
+    pub fn set_overlay_size(&mut self, width: u32, height: u32) {
+        self.neon_renderer.set_resolution(width, height);
+    }
+
+    pub fn set_overlay_png(&mut self, enabled: bool) {
+        self.neon_renderer.set_png_output(enabled);
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.neon_renderer.set_palette(palette);
+    }
+
+    // Flat (x, y, confidence) or (x, y, z, visibility) keypoints in this analyzer's layout
+    pub fn process_frame(&mut self, keypoints: &[f32], timestamp: f64) -> Result<AnalysisResult, String> {
+        let skeleton = Skeleton::from_flat(self.layout, keypoints)?;
+        Ok(self.analyze(skeleton, timestamp))
+    }
+
+    // Runs one frame through the pipeline, timestamp in milliseconds
+    pub fn analyze(&mut self, mut skeleton: Skeleton, timestamp: f64) -> AnalysisResult {
+        // Calculate frame delta time
+        let delta_time = if self.last_frame_time > 0.0 {
+            (timestamp - self.last_frame_time) / 1000.0 // Convert to seconds
+        } else {
+            1.0 / 30.0 // Default to 30fps on first frame
+        };
+        self.last_frame_time = timestamp;
+        self.session_time += delta_time;
+
+        // Smooth detector jitter and drop glitched points before any angle math
+        self.keypoint_filter.apply(&mut skeleton, delta_time as f32);
+
+        // 1. Pose Analysis, joints that can't be seen are left out of the angle map
+        let angles = self.pose_analyzer.calculate_angles(&self.current_exercise, &skeleton);
+        let occluded_landmarks: Vec<String> = skeleton
+            .occluded_landmarks(pose_detection::MIN_CONFIDENCE)
+            .into_iter()
+            .map(|landmark| landmark.name().to_string())
+            .collect();
+        let recognition = self.recognizer.as_mut().and_then(|recognizer| recognizer.update(&skeleton, timestamp)).cloned();
+
+        // 2. Rep Counting
+        let mut rep = self.rep_counter.update(&angles, self.session_time as f32);
+
+        // 3. Form Analysis
+        let position = self.rep_counter.position();
+        let mut form_issues = self.pose_analyzer.check_form(&self.current_exercise, &angles, position);
+        self.symmetry_tracker.update(&angles, self.session_time as f32);
+        if let Some(tolerance) = self.pose_analyzer.symmetry_tolerance(&self.current_exercise) {
+            form_issues.extend(self.symmetry_tracker.check_symmetry(tolerance));
+        }
+        if let Some(issue) = recognition.as_ref().and_then(|recognition| self.wrong_exercise(recognition)) {
+            form_issues.push(issue);
+        }
+        let phase = position.phase;
+        if phase != self.last_phase {
+            self.last_phase = phase;
+            self.phase_started = self.session_time;
+        }
+        let context = RuleContext {
+            angles: &angles,
+            position,
+            reps: self.rep_counter.count(),
+            phase_ms: (self.session_time - self.phase_started) * 1000.0,
+            timestamp: self.session_time * 1000.0,
+        };
+        if let Some(sequence) = &mut self.stage_sequence {
+            let update = sequence.update(&context);
+            form_issues.extend(update.issues);
+            // Exercises the rep counter follows on its own just report the cycles
+            if update.completed && !self.rep_counter.counts_reps() {
+                rep = Some(self.rep_counter.complete_rep());
+            }
+        }
+        // Tempo can only be judged once the rep is done
+        let tempo = self.tempo();
+        if let (Some(report), Some(tempo)) = (&mut rep, tempo) {
+            form_issues.extend(tempo.check(report));
+            report.tempo_adherence = Some(tempo.adherence(report));
+        }
+        self.compare_to_reference(&angles, rep.as_mut(), &mut form_issues);
+        form_issues.extend(self.form_rules.evaluate(&RuleContext { reps: self.rep_counter.count(), ..context }));
+        for issue in &mut form_issues {
+            issue.phase.get_or_insert(phase);
+        }
+        form::prioritize(&mut form_issues);
+        let form_errors: Vec<String> = form_issues.iter().map(|issue| issue.to_string()).collect();
+        if !form_errors.is_empty() && logging::enabled(Level::Warn) {
+            logging::warn("form", &form_errors.join("; "));
+        }
+
+        // The rep that completed on this frame owns everything raised since the last one
+        self.rep_issues.extend(form_issues.iter().cloned());
+        form::prioritize(&mut self.rep_issues);
+        let rep_report = rep.map(|report| report.with_form_issues(std::mem::take(&mut self.rep_issues)));
+        if let Some(report) = &rep_report {
+            logging::info("rep_counter", &format!("Rep {} detected! Quality {:.0}", report.number, report.quality));
+            self.set_session.add_rep(report);
+        }
+        let set_status = self.set_session.status();
+
+        // Time under tension only counts while the position is held with good form
+        let in_hold = phase == MovementPhase::StaticHold;
+        let hold = self
+            .hold_timer
+            .as_mut()
+            .map(|timer| timer.update(&angles, &form_issues, in_hold, self.session_time));
+
+        // 4. Engagement Calculation
+        let engagement = self.pose_analyzer.calculate_engagement(&self.current_exercise, &angles, position);
+        self.neon_renderer.set_intensity(engagement);
+        if let Some(muscle_group) = self.pose_analyzer.muscle_group(&self.current_exercise) {
+            self.neon_renderer.set_muscle_engagement(muscle_group, engagement);
+        }
+        self.neon_renderer.set_form_issues(&form_issues);
+
+        // 5. Neon Rendering, only fails for a zero-sized output which set_resolution rules out
+        if let Some(overlay) = &mut self.overlay {
+            *overlay = self.neon_renderer.render_skeleton(&skeleton).unwrap_or_default();
+        }
+
+        AnalysisResult {
+            rep_count: self.rep_counter.count(),
+            rep_detected: rep_report.is_some(),
+            rep_report,
+            set_status,
+            hold,
+            recognition,
+            stage: self.stage_sequence.as_ref().map(StageSequence::status),
+            engagement,
+            form_issues,
+            form_errors,
+            left_angles: side_angles(&angles, Side::Left),
+            right_angles: side_angles(&angles, Side::Right),
+            angles,
+            symmetry: self.symmetry_tracker.symmetry_index(),
+            occluded_landmarks,
+            unavailable_landmarks: self.unmeasurable.landmarks.iter().map(|landmark| landmark.name().to_string()).collect(),
+            unavailable_angles: self.unmeasurable.angles.clone(),
+        }
+    }
+
+    // Scores a completed rep against the reference and records the next one when asked to
+    fn compare_to_reference(
+        &mut self,
+        angles: &HashMap<String, f32>,
+        rep: Option<&mut RepReport>,
+        form_issues: &mut Vec<FormIssue>,
+    ) {
+        let timestamp = self.session_time as f32;
+        if let Some(reference) = &mut self.reference {
+            reference.record(angles, timestamp);
+        }
+        if let Some(recorder) = &mut self.reference_recorder {
+            recorder.record(angles, timestamp);
+        }
+        let Some(report) = rep else {
+            return;
+        };
+
+        if let Some(reference) = &mut self.reference {
+            report.reference = reference.finish_rep();
+            form_issues.extend(report.reference.as_ref().and_then(|comparison| reference.check(comparison)));
+        }
+        if let Some(recorded) = self.reference_recorder.as_mut().and_then(RepRecorder::finish_rep) {
+            logging::info("reference", &format!("Recorded reference rep from rep {}", report.number));
+            let mut reference = RepMatcher::new(recorded);
+            reference.record(angles, timestamp);
+            self.reference = Some(reference);
+            self.reference_recorder = None;
+        }
+    }
+
+    // A confident recognition of something else. Exercises loaded from definitions can't be
+    // recognized, so they are never flagged
+    fn wrong_exercise(&self, recognition: &Recognition) -> Option<FormIssue> {
+        let recognizer = self.recognizer.as_ref()?;
+        let known = recognizer.knows(&self.current_exercise);
+        if recognition.confidence < recognition::MIN_CONFIDENCE
+            || recognizer.agrees_with(&self.current_exercise)
+            || (!known && self.pose_analyzer.supports(&self.current_exercise))
+        {
+            return None;
+        }
+        let message = format!(
+            "This looks like {} rather than {}",
+            recognition.exercise_id.replace('-', " "),
+            self.current_exercise.replace('-', " ")
+        );
+        Some(FormIssue::new("wrong_exercise", Severity::Warning, message))
+    }
+}
+
+fn hold_timer(rep_counter: &RepCounter, pose_analyzer: &PoseAnalyzer, exercise_id: &str) -> Option<HoldTimer> {
+    (pose_analyzer.supports(exercise_id) && rep_counter.is_isometric())
+        .then(|| HoldTimer::new(pose_analyzer.hold_ranges(exercise_id)))
+}
+
+// Logged once here, the result carries it on every frame
+fn unmeasurable(pose_analyzer: &PoseAnalyzer, exercise_id: &str, layout: KeypointLayout) -> Unmeasurable {
+    let unmeasurable = pose_analyzer.unmeasurable(exercise_id, layout);
+    if !unmeasurable.angles.is_empty() {
+        let landmarks: Vec<&str> = unmeasurable.landmarks.iter().map(|landmark| landmark.name()).collect();
+        logging::warn(
+            "analyzer",
+            &format!(
+                "{:?} has no {} landmarks, {} can't measure {}",
+                layout,
+                landmarks.join(", "),
+                exercise_id,
+                unmeasurable.angles.join(", ")
+            ),
+        );
+    }
+    unmeasurable
+}
+
+fn stage_sequence(pose_analyzer: &PoseAnalyzer, exercise_id: &str) -> Option<StageSequence> {
+    let stages = pose_analyzer.stages(exercise_id);
+    (!stages.is_empty()).then(|| StageSequence::new(stages))
+}
+
+// Pulls one side's "left_<name>"/"right_<name>" angles out under their plain names
+fn side_angles(angles: &HashMap<String, f32>, side: Side) -> HashMap<String, f32> {
+    let prefix = format!("{}_", side.prefix());
+    angles
+        .iter()
+        .filter_map(|(key, &angle)| key.strip_prefix(&prefix).map(|name| (name.to_string(), angle)))
+        .collect()
+}
@@ -0,0 +1,335 @@
+// wasm/src/bindings.rs
+// JS-facing wrappers around the analysis core, only built with the "wasm" feature.
+// Class and method names match what the React app and ai_trainer.d.ts expect
+use wasm_bindgen::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+
+use crate::analyzer::{ExerciseAnalyzer, SUPPORTED_EXERCISES};
+use crate::definition::ExerciseSet;
+use crate::form::Severity;
+use crate::logging::{self, JsCallbackSink, Level};
+use crate::neon_render::NeonRenderer;
+use crate::reference::ReferenceRep;
+use crate::segmentation::RepDetector;
+use crate::skeleton::KeypointLayout;
+use crate::tempo::Tempo;
+use crate::validator::{ExerciseValidator, NamedKeypoint};
+
+fn from_js<T: DeserializeOwned>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&format!("Invalid {}: {:?}", what, e)))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsValue::from_str(&format!("Serialization error: {:?}", e)))
+}
+
+// Definitions arrive either as the raw file text (JSON or TOML) or as an already parsed object
+fn parse_definitions(definitions: JsValue) -> Result<ExerciseSet, JsValue> {
+    let set = match definitions.as_string() {
+        Some(source) => ExerciseSet::parse(&source),
+        None => {
+            let set: ExerciseSet = from_js(definitions, "exercise definitions")?;
+            set.validate().map(|_| set)
+        }
+    };
+    set.map_err(|e| JsValue::from_str(&e))
+}
+
+// Tempo spec such as "3-1-2-0", nothing to clear it
+fn parse_tempo(spec: Option<String>) -> Result<Option<Tempo>, JsValue> {
+    spec.map(|spec| Tempo::parse(&spec)).transpose().map_err(|e| JsValue::from_str(&e))
+}
+
+// "velocity" or "peaks", nothing to go back to the exercise's own
+fn parse_detector(name: Option<String>) -> Result<Option<RepDetector>, JsValue> {
+    name.map(|name| from_js(JsValue::from(name), "rep detector")).transpose()
+}
+
+fn parse_reference(reference: JsValue) -> Result<Option<ReferenceRep>, JsValue> {
+    if reference.is_undefined() || reference.is_null() {
+        return Ok(None);
+    }
+    from_js(reference, "reference rep").map(Some)
+}
+
+// Keypoint layout of the pose model feeding us, MoveNet unless told otherwise
+fn parse_layout(layout: Option<String>) -> Result<KeypointLayout, JsValue> {
+    match layout {
+        Some(name) => KeypointLayout::from_name(&name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown keypoint layout: {}", name))),
+        None => Ok(KeypointLayout::default()),
+    }
+}
+
+#[wasm_bindgen(js_name = ExerciseAnalyzer)]
+pub struct JsExerciseAnalyzer {
+    inner: ExerciseAnalyzer,
+}
+
+#[wasm_bindgen(js_class = ExerciseAnalyzer)]
+impl JsExerciseAnalyzer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(exercise_id: &str, layout: Option<String>) -> Result<JsExerciseAnalyzer, JsValue> {
+        Ok(JsExerciseAnalyzer { inner: ExerciseAnalyzer::new(exercise_id, parse_layout(layout)?) })
+    }
+
+    // Recognize the exercise being performed and flag a mismatch, see AnalysisResult.recognition
+    #[wasm_bindgen(js_name = setRecognition)]
+    pub fn set_recognition(&mut self, enabled: bool) {
+        self.inner.set_recognition(enabled);
+    }
+
+    // Accepts a partial config, e.g. { kind: "kalman", measurementNoise: 0.1 } or { kind: "none" }
+    #[wasm_bindgen(js_name = setFilter)]
+    pub fn set_filter(&mut self, config: JsValue) -> Result<(), JsValue> {
+        self.inner.set_filter(from_js(config, "filter config")?);
+        Ok(())
+    }
+
+    // An exercises file (src/data/exercises/*.json or TOML), as text or parsed. Returns the loaded ids
+    #[wasm_bindgen(js_name = loadDefinitions)]
+    pub fn load_definitions(&mut self, definitions: JsValue) -> Result<JsValue, JsValue> {
+        let loaded = self.inner.load_definitions(&parse_definitions(definitions)?).map_err(|e| JsValue::from_str(&e))?;
+        to_js(&loaded)
+    }
+
+    #[wasm_bindgen(js_name = startSet)]
+    pub fn start_set(&mut self) -> u32 {
+        self.inner.start_set()
+    }
+
+    // Summary of the set that was running, or undefined
+    #[wasm_bindgen(js_name = endSet)]
+    pub fn end_set(&mut self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.end_set())
+    }
+
+    // The set so far counted again with the peak detector, next to the live count
+    #[wasm_bindgen(js_name = recountSet)]
+    pub fn recount_set(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.recount_set())
+    }
+
+    // Partial config, e.g. { velocityLossThreshold: 0.2 }; fields left out take the generic defaults
+    #[wasm_bindgen(js_name = setSetConfig)]
+    pub fn set_set_config(&mut self, config: JsValue) -> Result<(), JsValue> {
+        self.inner.set_set_config(from_js(config, "set config")?);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setTempo)]
+    pub fn set_tempo(&mut self, tempo: Option<String>) -> Result<(), JsValue> {
+        self.inner.set_tempo(parse_tempo(tempo)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setRepDetector)]
+    pub fn set_rep_detector(&mut self, detector: Option<String>) -> Result<(), JsValue> {
+        self.inner.set_rep_detector(parse_detector(detector)?);
+        Ok(())
+    }
+
+    // Reference rep in the exercise-file format, e.g. { angles: { knee: [170, 120, 90, 120, 170] } };
+    // nothing stops comparing
+    #[wasm_bindgen(js_name = setReference)]
+    pub fn set_reference(&mut self, reference: JsValue) -> Result<(), JsValue> {
+        self.inner.set_reference(parse_reference(reference)?).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // The next full rep becomes the reference, read it back with reference() to store it
+    #[wasm_bindgen(js_name = recordReference)]
+    pub fn record_reference(&mut self) {
+        self.inner.record_reference();
+    }
+
+    pub fn reference(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.reference())
+    }
+
+    #[wasm_bindgen(js_name = setOverlay)]
+    pub fn set_overlay(&mut self, enabled: bool) {
+        self.inner.set_overlay(enabled);
+    }
+
+    // The last frame's overlay as a Uint8Array, empty unless setOverlay(true)
+    pub fn overlay(&self) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(self.inner.overlay())
+    }
+
+    #[wasm_bindgen(js_name = setOverlaySize)]
+    pub fn set_overlay_size(&mut self, width: u32, height: u32) {
+        self.inner.set_overlay_size(width, height);
+    }
+
+    #[wasm_bindgen(js_name = setOverlayPng)]
+    pub fn set_overlay_png(&mut self, enabled: bool) {
+        self.inner.set_overlay_png(enabled);
+    }
+
+    // Partial palette, e.g. { base: [0, 1, 0.5], error: [1, 0, 0] }, see neon_render::Palette
+    #[wasm_bindgen(js_name = setPalette)]
+    pub fn set_palette(&mut self, palette: JsValue) -> Result<(), JsValue> {
+        self.inner.set_palette(from_js(palette, "palette")?);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = processFrame)]
+    pub fn process_frame(&mut self, keypoints: &[f32], timestamp: f64) -> Result<JsValue, JsValue> {
+        let result = self.inner.process_frame(keypoints, timestamp).map_err(|e| JsValue::from_str(&e))?;
+        to_js(&result)
+    }
+}
+
+#[wasm_bindgen(js_name = ExerciseValidator)]
+pub struct JsExerciseValidator {
+    inner: ExerciseValidator,
+}
+
+#[wasm_bindgen(js_class = ExerciseValidator)]
+impl JsExerciseValidator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(exercise_id: &str, layout: Option<String>) -> Result<JsExerciseValidator, JsValue> {
+        Ok(JsExerciseValidator { inner: ExerciseValidator::new(exercise_id, parse_layout(layout)?) })
+    }
+
+    #[wasm_bindgen(js_name = loadDefinitions)]
+    pub fn load_definitions(&mut self, definitions: JsValue) -> Result<JsValue, JsValue> {
+        let loaded = self.inner.load_definitions(&parse_definitions(definitions)?).map_err(|e| JsValue::from_str(&e))?;
+        to_js(&loaded)
+    }
+
+    #[wasm_bindgen(js_name = setTempo)]
+    pub fn set_tempo(&mut self, tempo: Option<String>) -> Result<(), JsValue> {
+        self.inner.set_tempo(parse_tempo(tempo)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setRepDetector)]
+    pub fn set_rep_detector(&mut self, detector: Option<String>) -> Result<(), JsValue> {
+        self.inner.set_rep_detector(parse_detector(detector)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setReference)]
+    pub fn set_reference(&mut self, reference: JsValue) -> Result<(), JsValue> {
+        self.inner.set_reference(parse_reference(reference)?).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(js_name = recordReference)]
+    pub fn record_reference(&mut self) {
+        self.inner.record_reference();
+    }
+
+    pub fn reference(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.reference())
+    }
+
+    #[wasm_bindgen(js_name = setRecognition)]
+    pub fn set_recognition(&mut self, enabled: bool) {
+        self.inner.set_recognition(enabled);
+    }
+
+    #[wasm_bindgen(js_name = startSet)]
+    pub fn start_set(&mut self) -> u32 {
+        self.inner.start_set()
+    }
+
+    #[wasm_bindgen(js_name = endSet)]
+    pub fn end_set(&mut self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.end_set())
+    }
+
+    #[wasm_bindgen(js_name = recountSet)]
+    pub fn recount_set(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner.recount_set())
+    }
+
+    // Timestamp in milliseconds from the caller's clock, e.g. the requestAnimationFrame time
+    #[wasm_bindgen(js_name = validatePose)]
+    pub fn validate_pose(&mut self, keypoints: JsValue, timestamp: f64) -> Result<JsValue, JsValue> {
+        let keypoints: HashMap<String, NamedKeypoint> = from_js(keypoints, "keypoints")?;
+        to_js(&self.inner.validate_pose(&keypoints, timestamp))
+    }
+}
+
+#[wasm_bindgen(js_name = NeonRenderer)]
+pub struct JsNeonRenderer {
+    inner: NeonRenderer,
+}
+
+#[wasm_bindgen(js_class = NeonRenderer)]
+impl JsNeonRenderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(r: f32, g: f32, b: f32) -> JsNeonRenderer {
+        JsNeonRenderer { inner: NeonRenderer::new(r, g, b) }
+    }
+
+    // Accepts a partial palette, e.g. { warning: [1, 0.5, 0], heatHigh: [1, 0, 0] }
+    pub fn set_palette(&mut self, palette: JsValue) -> Result<(), JsValue> {
+        self.inner.set_palette(from_js(palette, "palette")?);
+        Ok(())
+    }
+
+    // Angle keys to tint, e.g. { left_knee_flexion: "critical", hip_depth: "warning" }
+    pub fn set_flagged_joints(&mut self, joints: JsValue) -> Result<(), JsValue> {
+        let joints: HashMap<String, Severity> = from_js(joints, "flagged joints")?;
+        self.inner.set_flagged_joints(&joints);
+        Ok(())
+    }
+
+    // Muscle group -> engagement, e.g. { quads: 0.8, glutes: 0.4 }
+    pub fn set_engagement_map(&mut self, engagement: JsValue) -> Result<(), JsValue> {
+        self.inner.set_engagement_map(from_js(engagement, "engagement map")?);
+        Ok(())
+    }
+
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.inner.set_resolution(width, height);
+    }
+
+    pub fn set_png_output(&mut self, enabled: bool) {
+        self.inner.set_png_output(enabled);
+    }
+
+    pub fn render(&self, keypoints: &[f32]) -> Result<Vec<u8>, JsValue> {
+        self.inner.render(keypoints).map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn set_intensity(&mut self, engagement: f32) {
+        self.inner.set_intensity(engagement);
+    }
+}
+
+// Helper functions exposed to JS
+#[wasm_bindgen]
+pub fn get_supported_exercises() -> JsValue {
+    serde_wasm_bindgen::to_value(SUPPORTED_EXERCISES).unwrap()
+}
+
+// "debug" | "info" | "warn" | "error", or "off" to silence the analyzer
+#[wasm_bindgen]
+pub fn set_log_level(level: &str) -> Result<(), JsValue> {
+    if level.eq_ignore_ascii_case("off") {
+        logging::set_level(None);
+        return Ok(());
+    }
+    let level = Level::from_name(level).ok_or_else(|| JsValue::from_str(&format!("Unknown log level: {}", level)))?;
+    logging::set_level(Some(level));
+    Ok(())
+}
+
+// Minimum gap between repeats of the same message, 0 logs everything
+#[wasm_bindgen]
+pub fn set_log_rate_limit(interval_ms: f64) {
+    logging::set_rate_limit(interval_ms);
+}
+
+// callback(level, target, message, suppressed) replaces console output, pass nothing to restore it
+#[wasm_bindgen]
+pub fn set_log_callback(callback: Option<js_sys::Function>) {
+    match callback {
+        Some(callback) => logging::set_sink(JsCallbackSink::new(callback)),
+        None => logging::reset_sink(),
+    }
+}
@@ -0,0 +1,653 @@
+// wasm/src/definition.rs
+// Exercise definitions loaded at runtime. These are the src/data/exercises/*.json files:
+// the engine reads the fields exercise.schema.json describes under "angles", "rep",
+// "formRules", "validationRules", "engagement" and "steps" and ignores the presentation
+// fields (visuals, audio, model paths). Anything else it ignores is reported by warnings()
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::form::Severity;
+use crate::pose_detection::TOP_RANGE_MIN;
+use crate::reference::ReferenceRep;
+use crate::rep_counter::MovementPattern;
+use crate::rules::Rule;
+use crate::segmentation::RepDetector;
+use crate::sequence::{self, Stage};
+use crate::skeleton::Landmark;
+use crate::tempo::Tempo;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExerciseSet {
+    pub muscle_group: String,
+    pub exercises: Vec<ExerciseDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExerciseDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tracked_joints: Vec<String>,
+    // Angle key -> how to measure it. Exercises without angles are catalog entries only
+    // and stay with the built-in analyzers
+    #[serde(default)]
+    pub angles: HashMap<String, AngleDefinition>,
+    #[serde(default)]
+    pub rep: Option<RepDefinition>,
+    #[serde(default)]
+    pub form_rules: Vec<FormRule>,
+    // The data files' checks by name, e.g. "frontKneeAngle": { "min": 80, "max": 100 }.
+    // Joint angle ranges among them become form rules for catalog entries too, see
+    // validation_form_rules
+    #[serde(default)]
+    pub validation_rules: HashMap<String, ValidationRule>,
+    #[serde(default)]
+    pub engagement: HashMap<String, EngagementTerm>,
+    #[serde(default)]
+    pub symmetry_tolerance: Option<f32>,
+    // Poses of one rep in order, tracked by sequence.rs. Works for catalog entries too,
+    // the targets are read from the built-in analyzers' angles
+    #[serde(default)]
+    pub steps: Vec<StepDefinition>,
+    // A good rep every rep is compared against, see reference.rs. Like steps, catalog
+    // entries can have one
+    #[serde(default)]
+    pub reference: Option<ReferenceRep>,
+    // Fields the engine doesn't read, see PRESENTATION_FIELDS
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+// Fields for the app rather than the engine, ignored without a warning
+const PRESENTATION_FIELDS: &[&str] = &["difficulty", "visual", "visuals", "wslPaths", "audio", "feedback", "commonMistakes"];
+
+// Three landmarks measure the joint angle at the middle one, two measure their distance.
+// Names without a side ("knee") are measured on both sides and reported the way the
+// built-in analyzers do ("left_<key>", "right_<key>" and the averaged "<key>");
+// sided ("left_knee") and central ("nose", "neck") names are taken as written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AngleDefinition {
+    pub points: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointRef {
+    pub landmark: Landmark, // Left-side landmark when `sided`
+    pub sided: bool,
+}
+
+impl AngleDefinition {
+    pub fn resolve(&self) -> Result<Vec<PointRef>, String> {
+        if !(2..=3).contains(&self.points.len()) {
+            return Err(format!("needs 2 or 3 points, got {}", self.points.len()));
+        }
+
+        self.points
+            .iter()
+            .map(|name| {
+                if let Some(landmark) = Landmark::from_name(name) {
+                    Ok(PointRef { landmark, sided: false })
+                } else if let Some(landmark) = Landmark::from_name(&format!("left_{}", name)) {
+                    Ok(PointRef { landmark, sided: true })
+                } else {
+                    Err(format!("unknown landmark '{}'", name))
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_bilateral(&self) -> bool {
+        self.resolve().is_ok_and(|points| points.iter().any(|point| point.sided))
+    }
+}
+
+// Same knobs as the built-in rep_counter profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepDefinition {
+    pub joint: String, // Angle key, or a joint name such as "knee"
+    pub range: (f32, f32),
+    pub pattern: MovementPattern,
+    #[serde(default = "default_velocity_threshold")]
+    pub velocity_threshold: f32,
+    #[serde(default = "default_min_rom")]
+    pub min_rom: f32,
+    #[serde(default)]
+    pub lockout_angle: Option<f32>,
+    #[serde(default)]
+    pub stretch_angle: Option<f32>,
+    #[serde(default)]
+    pub secondary_joints: Vec<String>,
+    #[serde(default)]
+    pub tempo: Option<Tempo>, // e.g. "3-1-2-0", see tempo.rs
+    #[serde(default)]
+    pub detector: RepDetector, // "velocity" or "peaks", see segmentation.rs
+    #[serde(default)]
+    pub prominence: Option<f32>,
+    #[serde(default)]
+    pub hysteresis: Option<f32>,
+}
+
+fn default_velocity_threshold() -> f32 {
+    0.5
+}
+
+fn default_min_rom() -> f32 {
+    0.8
+}
+
+// Either a range check, firing when `angle` leaves [min, max] with `cue` as the message,
+// or a `rule` in the rules.rs expression language, e.g.
+// "knee_flexion < 75 during phase=bottom for 200ms => \"Go deeper\""
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormRule {
+    pub code: String,
+    #[serde(default)]
+    pub angle: Option<String>,
+    #[serde(default)]
+    pub min: Option<f32>,
+    #[serde(default)]
+    pub max: Option<f32>,
+    #[serde(default)]
+    pub cue: Option<String>,
+    #[serde(default)]
+    pub rule: Option<String>,
+    #[serde(default = "default_severity")]
+    pub severity: Severity, // For rules that don't name one themselves
+}
+
+impl FormRule {
+    pub fn compile(&self) -> Result<Rule, String> {
+        match (&self.rule, &self.angle) {
+            (Some(source), None) => Rule::parse(&self.code, source, self.severity),
+            (None, Some(angle)) => {
+                let cue = self.cue.as_deref().ok_or("needs a cue")?;
+                if self.min.is_none() && self.max.is_none() {
+                    return Err("needs a min or a max".to_string());
+                }
+                Ok(Rule::range(&self.code, angle, self.min, self.max, self.severity, cue))
+            }
+            _ => Err("needs either a rule or an angle".to_string()),
+        }
+    }
+}
+
+fn default_severity() -> Severity {
+    Severity::Warning
+}
+
+// One of the data files' validationRules. Only a min and max in degrees are checked, the
+// other measures (thresholds, deviations, durations) are kept for warnings()
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRule {
+    #[serde(default)]
+    pub min: Option<f32>,
+    #[serde(default)]
+    pub max: Option<f32>,
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+// validationRules names that are a joint angle range, the step target name measuring the
+// joint (see sequence.rs) and whether the range is flexion, degrees bent from straight,
+// rather than the interior angle the analyzers report. The rest of the files' vocabulary
+// (legAngle, torsoAngle, hipExtension) mixes conventions and ratios and isn't checked
+const VALIDATION_ANGLES: &[(&str, &str, bool)] = &[
+    ("kneeAngle", "knee", false),
+    ("frontKneeAngle", "frontKnee", false),
+    ("kneeFlexion", "knee", true),
+    ("elbowAngle", "elbow", false),
+    ("elbowExtension", "elbow", false),
+    ("elbowFlexion", "elbow", true),
+    ("hipAngle", "hip", false),
+    ("hipHinge", "hip", true),
+    ("shoulderFlexion", "shoulder", false), // Arm raised from the side of the torso
+    ("shoulderAbduction", "shoulder", false),
+];
+
+// Engagement grows as the angle moves from range.0 to range.1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngagementTerm {
+    pub range: (f32, f32),
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+// One pose of a rep, reached once every target angle is within `tolerance` degrees
+// for `dwellMs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepDefinition {
+    pub phase: String,
+    pub target_angles: HashMap<String, f32>,
+    #[serde(default = "default_step_tolerance")]
+    pub tolerance: f32,
+    #[serde(default)]
+    pub dwell_ms: Option<f64>,
+    #[serde(default)]
+    pub audio_cue: Option<String>,
+}
+
+fn default_step_tolerance() -> f32 {
+    15.0
+}
+
+impl StepDefinition {
+    pub fn stage(&self) -> Stage {
+        let cue = self.audio_cue.as_deref().unwrap_or(&self.phase);
+        let stage = Stage::targets(&self.phase, &self.target_angles, self.tolerance, cue);
+        match self.dwell_ms {
+            Some(dwell_ms) => stage.dwell(dwell_ms),
+            None => stage,
+        }
+    }
+}
+
+impl ExerciseSet {
+    pub fn from_json(source: &str) -> Result<ExerciseSet, String> {
+        let set: ExerciseSet = serde_json::from_str(source).map_err(|e| format!("Invalid exercise JSON: {}", e))?;
+        set.validate()?;
+        Ok(set)
+    }
+
+    pub fn from_toml(source: &str) -> Result<ExerciseSet, String> {
+        let set: ExerciseSet = toml::from_str(source).map_err(|e| format!("Invalid exercise TOML: {}", e))?;
+        set.validate()?;
+        Ok(set)
+    }
+
+    // JSON documents start with an object, anything else is read as TOML
+    pub fn parse(source: &str) -> Result<ExerciseSet, String> {
+        if source.trim_start().starts_with('{') {
+            Self::from_json(source)
+        } else {
+            Self::from_toml(source)
+        }
+    }
+
+    // Exercises the engine can run from this file alone
+    pub fn engine_exercises(&self) -> impl Iterator<Item = &ExerciseDefinition> {
+        self.exercises.iter().filter(|exercise| !exercise.angles.is_empty())
+    }
+
+    // What loading this file ignores, see ExerciseDefinition::warnings
+    pub fn warnings(&self) -> Vec<String> {
+        self.exercises
+            .iter()
+            .flat_map(|exercise| exercise.warnings().into_iter().map(move |warning| format!("Exercise '{}': {}", exercise.id, warning)))
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for exercise in &self.exercises {
+            exercise.validate().map_err(|e| format!("Exercise '{}': {}", exercise.id, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl ExerciseDefinition {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("missing id".to_string());
+        }
+
+        for (key, angle) in &self.angles {
+            angle.resolve().map_err(|e| format!("angle '{}' {}", key, e))?;
+        }
+
+        // Rules and engagement terms can only read angles this definition measures,
+        // per side or averaged
+        let defined = |key: &str| {
+            let base = key.strip_prefix("left_").or_else(|| key.strip_prefix("right_"));
+            self.angles.contains_key(key)
+                || base.is_some_and(|base| self.angles.get(base).is_some_and(AngleDefinition::is_bilateral))
+        };
+
+        for form_rule in &self.form_rules {
+            let rule = form_rule.compile().map_err(|e| format!("form rule '{}': {}", form_rule.code, e))?;
+            if let Some(angle) = rule.angles().into_iter().find(|angle| !defined(angle)) {
+                return Err(format!("form rule '{}' reads undefined angle '{}'", form_rule.code, angle));
+            }
+        }
+
+        for (key, term) in &self.engagement {
+            if !defined(key) {
+                return Err(format!("engagement reads undefined angle '{}'", key));
+            }
+            if term.range.0 == term.range.1 {
+                return Err(format!("engagement range for '{}' is empty", key));
+            }
+        }
+
+        if let Some(rep) = &self.rep {
+            if rep.range.1 <= rep.range.0 {
+                return Err(format!("rep range {:?} is empty", rep.range));
+            }
+            if rep.prominence.is_some_and(|prominence| prominence <= 0.0) {
+                return Err("rep prominence must be positive".to_string());
+            }
+            if rep.hysteresis.is_some_and(|hysteresis| hysteresis < 0.0) {
+                return Err("rep hysteresis can't be negative".to_string());
+            }
+        }
+
+        if self.steps.len() == 1 {
+            return Err("a single step can't make a rep, list at least two".to_string());
+        }
+        for step in &self.steps {
+            if step.target_angles.is_empty() {
+                return Err(format!("step '{}' has no target angles", step.phase));
+            }
+            if let Some(name) = step.target_angles.keys().find(|name| !sequence::is_target_name(name) && !defined(name)) {
+                return Err(format!("step '{}' targets unknown angle '{}'", step.phase, name));
+            }
+            if step.tolerance <= 0.0 {
+                return Err(format!("step '{}' needs a positive tolerance", step.phase));
+            }
+            if step.dwell_ms.is_some_and(|dwell_ms| dwell_ms < 0.0) {
+                return Err(format!("step '{}' has a negative dwell", step.phase));
+            }
+        }
+        if let Some(reference) = &self.reference {
+            reference.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ExerciseDefinition {
+    // Fields and validationRules the engine skips, one message each. Misspelled fields
+    // would otherwise vanish without a trace
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let mut unknown: Vec<&String> = self.other.keys().filter(|key| !PRESENTATION_FIELDS.contains(&key.as_str())).collect();
+        unknown.sort();
+        warnings.extend(unknown.into_iter().map(|key| format!("unknown field '{}' ignored", key)));
+
+        let mut names: Vec<&String> = self.validation_rules.keys().collect();
+        names.sort();
+        for name in names {
+            let rule = &self.validation_rules[name];
+            if validation_angle(name).is_none() {
+                warnings.push(format!("validation rule '{}' ignored, it isn't a joint angle range", name));
+            } else if rule.min.is_none() && rule.max.is_none() {
+                warnings.push(format!("validation rule '{}' ignored, it has no min or max", name));
+            } else {
+                let mut keys: Vec<&String> = rule.other.keys().collect();
+                keys.sort();
+                warnings.extend(keys.into_iter().map(|key| format!("validation rule '{}' ignores '{}'", name, key)));
+            }
+        }
+
+        warnings
+    }
+
+    // The validationRules that are joint angle ranges as form rules, reading `angles` the
+    // way the exercise's analyzer reports them. Like the built-in target ranges, each is
+    // checked at the end of the rep it describes: the top for ranges near full extension,
+    // the bottom otherwise. Also returns the names nothing in `angles` measures
+    pub fn validation_form_rules(&self, angles: &HashMap<String, f32>) -> (Vec<Rule>, Vec<String>) {
+        let mut rules = Vec::new();
+        let mut unmeasured = Vec::new();
+
+        let mut names: Vec<&String> = self.validation_rules.keys().collect();
+        names.sort();
+        for name in names {
+            let rule = &self.validation_rules[name];
+            let Some((target, flexion)) = validation_angle(name) else {
+                continue;
+            };
+            let (min, max) = if flexion {
+                (rule.max.map(|max| 180.0 - max), rule.min.map(|min| 180.0 - min))
+            } else {
+                (rule.min, rule.max)
+            };
+            let test = match (min, max) {
+                (Some(min), Some(max)) => format!("not in {}..{}", min, max),
+                (Some(min), None) => format!("< {}", min),
+                (None, Some(max)) => format!("> {}", max),
+                (None, None) => continue,
+            };
+            // In the file's own terms, "knee flexion between 120° and 140°"
+            let cue = match (rule.min, rule.max) {
+                (Some(min), Some(max)) => format!("between {}° and {}°", min, max),
+                (Some(min), None) => format!("at least {}°", min),
+                (None, Some(max)) => format!("at most {}°", max),
+                (None, None) => continue,
+            };
+
+            // Either side of a leading limb may be the one in range
+            let keys = sequence::target_keys(target, angles);
+            if keys.is_empty() {
+                unmeasured.push(name.clone());
+                continue;
+            }
+            let condition = keys.iter().map(|key| format!("{} {}", key, test)).collect::<Vec<_>>().join(" and ");
+            let phase = if min.unwrap_or(0.0) >= TOP_RANGE_MIN { "top" } else { "bottom" };
+            let source = format!("{} during phase={} => \"Keep the {} {}\"", condition, phase, words(name), cue);
+            rules.push(Rule::parse(&snake_case(name), &source, Severity::Warning).expect("validation rules compile to valid rules"));
+        }
+
+        (rules, unmeasured)
+    }
+}
+
+fn validation_angle(name: &str) -> Option<(&'static str, bool)> {
+    VALIDATION_ANGLES.iter().find(|(known, _, _)| *known == name).map(|&(_, target, flexion)| (target, flexion))
+}
+
+// "frontKneeAngle" -> "front_knee_angle"
+fn snake_case(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| if c.is_ascii_uppercase() { vec!['_', c.to_ascii_lowercase()] } else { vec![c] })
+        .collect()
+}
+
+// "frontKneeAngle" -> "front knee angle"
+fn words(name: &str) -> String {
+    snake_case(name).replace('_', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rep_counter::{MovementPhase, RepPosition};
+    use crate::rules::{RuleContext, RuleSet};
+
+    const JSON: &str = r#"{
+        "muscleGroup": "quads",
+        "exercises": [
+            {
+                "id": "box-squat",
+                "name": "Box squat",
+                "visuals": { "model": "box-squat.glb" },
+                "angles": {
+                    "knee": { "points": ["hip", "knee", "ankle"] },
+                    "stance": { "points": ["left_ankle", "right_ankle"] }
+                },
+                "rep": { "joint": "knee", "range": [80, 170], "pattern": "squat", "lockoutAngle": 165 },
+                "formRules": [
+                    { "code": "depth", "rule": "knee > 100 during phase=bottom => \"Sit back to the box\"" },
+                    { "code": "knee_cave", "angle": "left_knee", "min": 60, "cue": "Knees out", "severity": "critical" }
+                ],
+                "engagement": { "knee": { "range": [170, 80] } }
+            },
+            {
+                "id": "squat",
+                "steps": [
+                    { "phase": "stand", "targetAngles": { "knee": 170 } },
+                    { "phase": "bottom", "targetAngles": { "knee": 90 }, "tolerance": 10, "dwellMs": 300 }
+                ]
+            }
+        ]
+    }"#;
+
+    const TOML: &str = r#"
+        muscleGroup = "quads"
+
+        [[exercises]]
+        id = "box-squat"
+
+        [exercises.angles.knee]
+        points = ["hip", "knee", "ankle"]
+
+        [exercises.rep]
+        joint = "knee"
+        range = [80, 170]
+        pattern = "squat"
+        detector = "peaks"
+        tempo = "3-1-1-0"
+
+        [[exercises.formRules]]
+        code = "depth"
+        rule = 'knee > 100 during phase=bottom => "Sit back to the box"'
+    "#;
+
+    // The first exercise of JSON with one field swapped for `value`
+    fn with(field: &str, value: &str) -> String {
+        let mut exercise: serde_json::Value = serde_json::from_str(JSON).unwrap();
+        exercise["exercises"][0][field] = serde_json::from_str(value).unwrap();
+        exercise.to_string()
+    }
+
+    #[test]
+    fn loads_json() {
+        let set = ExerciseSet::parse(JSON).unwrap();
+        assert_eq!(set.muscle_group, "quads");
+        assert_eq!(set.engine_exercises().map(|exercise| exercise.id.as_str()).collect::<Vec<_>>(), ["box-squat"]);
+
+        let exercise = &set.exercises[0];
+        assert!(exercise.angles["knee"].is_bilateral());
+        assert!(!exercise.angles["stance"].is_bilateral());
+        let rep = exercise.rep.as_ref().unwrap();
+        assert_eq!((rep.range, rep.lockout_angle, rep.detector), ((80.0, 170.0), Some(165.0), RepDetector::Velocity));
+        assert_eq!(exercise.form_rules[0].severity, Severity::Warning);
+        assert_eq!(exercise.form_rules[1].severity, Severity::Critical);
+        assert_eq!(exercise.engagement["knee"].weight, 1.0);
+
+        let steps = &set.exercises[1].steps;
+        assert_eq!((steps[0].tolerance, steps[0].dwell_ms), (15.0, None));
+        assert_eq!((steps[1].tolerance, steps[1].dwell_ms), (10.0, Some(300.0)));
+    }
+
+    #[test]
+    fn loads_toml() {
+        let set = ExerciseSet::parse(TOML).unwrap();
+        let rep = set.exercises[0].rep.as_ref().unwrap();
+        assert_eq!(rep.detector, RepDetector::Peaks);
+        assert!(rep.tempo.is_some());
+        assert_eq!(rep.min_rom, default_min_rom());
+        assert_eq!(set.exercises[0].form_rules[0].compile().unwrap().code(), "depth");
+    }
+
+    #[test]
+    fn resolves_sided_and_central_points() {
+        let angle = |points: &[&str]| AngleDefinition { points: points.iter().map(|point| point.to_string()).collect() };
+        assert_eq!(
+            angle(&["hip", "left_knee", "neck"]).resolve().unwrap(),
+            [
+                PointRef { landmark: Landmark::LeftHip, sided: true },
+                PointRef { landmark: Landmark::LeftKnee, sided: false },
+                PointRef { landmark: Landmark::Neck, sided: false },
+            ]
+        );
+        assert!(angle(&["knee"]).resolve().unwrap_err().contains("2 or 3"));
+        assert!(angle(&["hip", "kneecap"]).resolve().unwrap_err().contains("kneecap"));
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        let error = |source: String| ExerciseSet::parse(&source).unwrap_err();
+        assert!(error(JSON.replace("\"muscleGroup\"", "\"group\"")).contains("Invalid exercise JSON"));
+        assert!(error("muscleGroup = ".to_string()).contains("Invalid exercise TOML"));
+        assert!(error(with("id", r#""""#)).contains("missing id"));
+        assert!(error(with("angles", r#"{ "knee": { "points": ["hip", "kneecap", "ankle"] } }"#)).contains("kneecap"));
+        assert!(error(with("formRules", r#"[{ "code": "x", "rule": "ankle < 90 => \"x\"" }]"#)).contains("undefined angle 'ankle'"));
+        assert!(error(with("formRules", r#"[{ "code": "x", "rule": "knee < => \"x\"" }]"#)).contains("form rule 'x'"));
+        assert!(error(with("formRules", r#"[{ "code": "x", "angle": "knee", "cue": "x" }]"#)).contains("min or a max"));
+        assert!(error(with("engagement", r#"{ "knee": { "range": [90, 90] } }"#)).contains("empty"));
+        assert!(error(with("rep", r#"{ "joint": "knee", "range": [170, 80], "pattern": "squat" }"#)).contains("empty"));
+        assert!(error(with("rep", r#"{ "joint": "knee", "range": [80, 170], "pattern": "sqaut" }"#)).contains("Invalid"));
+        assert!(error(with("steps", r#"[{ "phase": "stand", "targetAngles": { "knee": 170 } }]"#)).contains("single step"));
+        assert!(error(with("steps", r#"[{ "phase": "a", "targetAngles": {} }, { "phase": "b", "targetAngles": { "knee": 90 } }]"#))
+            .contains("no target angles"));
+        let steps = r#"[{ "phase": "a", "targetAngles": { "knee": 170 } }, { "phase": "b", "targetAngles": { "hipDrop": 20 } }]"#;
+        assert!(error(with("steps", steps)).contains("unknown angle 'hipDrop'"));
+        // Definitions can target their own angles
+        let steps = r#"[{ "phase": "a", "targetAngles": { "stance": 0.3 } }, { "phase": "b", "targetAngles": { "left_knee": 90 } }]"#;
+        assert!(ExerciseSet::parse(&with("steps", steps)).is_ok());
+    }
+    #[test]
+    fn maps_validation_rules_of_the_shipped_data_files() {
+        let quads = ExerciseSet::parse(include_str!("../../src/data/exercises/quads.json")).unwrap();
+        assert_eq!(quads.engine_exercises().count(), 0);
+        let exercise = |id: &str| quads.exercises.iter().find(|exercise| exercise.id == id).unwrap();
+
+        // Either knee may be the front one, checked at the bottom of the split squat
+        let angles: HashMap<String, f32> = [("knee_flexion", 120.0), ("left_knee_flexion", 90.0), ("right_knee_flexion", 150.0)]
+            .map(|(key, angle)| (key.to_string(), angle))
+            .into();
+        let (rules, unmeasured) = exercise("bulgarian-splits").validation_form_rules(&angles);
+        assert!(unmeasured.is_empty());
+        assert_eq!(rules.iter().map(Rule::code).collect::<Vec<_>>(), ["front_knee_angle"]);
+        assert_eq!(rules[0].angles(), ["left_knee_flexion", "right_knee_flexion"]);
+        let at = |angles: &HashMap<String, f32>, rom: f32| {
+            let position = RepPosition { phase: MovementPhase::Eccentric, rom: Some(rom) };
+            RuleSet::new(rules.clone()).evaluate(&RuleContext { angles, position, reps: 0, phase_ms: 0.0, timestamp: 0.0 })
+        };
+        assert!(at(&angles, 0.1).is_empty());
+        let shallow: HashMap<String, f32> = angles.keys().map(|key| (key.clone(), 150.0)).collect();
+        assert_eq!(at(&shallow, 0.1)[0].message, "Keep the front knee angle between 80° and 100°");
+        assert!(at(&shallow, 0.9).is_empty());
+
+        // Flexion is degrees bent, 180 less the interior angle
+        let legs = ExerciseSet::parse(include_str!("../../src/data/exercises/hamstrings.json")).unwrap();
+        let curl = legs.exercises.iter().find(|exercise| exercise.id == "hamstring-curls").unwrap();
+        let (rules, _) = curl.validation_form_rules(&angles);
+        assert_eq!(rules[0].angles(), ["knee_flexion"]);
+        assert!(RuleSet::new(rules).evaluate(&RuleContext {
+            angles: &[("knee_flexion".to_string(), 50.0)].into(),
+            position: RepPosition { phase: MovementPhase::Concentric, rom: Some(0.0) },
+            reps: 0,
+            phase_ms: 0.0,
+            timestamp: 0.0,
+        })
+        .is_empty());
+
+        assert_eq!(
+            exercise("goblet-squats").warnings(),
+            [
+                "validation rule 'depth' ignored, it isn't a joint angle range",
+                "validation rule 'torsoAngle' ignored, it isn't a joint angle range",
+            ]
+        );
+        let (_, unmeasured) = exercise("bulgarian-splits").validation_form_rules(&HashMap::new());
+        assert_eq!(unmeasured, ["frontKneeAngle"]);
+
+        // Every file loads, and only its validation rules go unread
+        for entry in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../src/data/exercises")).unwrap() {
+            let path = entry.unwrap().path();
+            if path.file_name().is_some_and(|name| name == "exercise.schema.json") {
+                continue;
+            }
+            let set = ExerciseSet::parse(&std::fs::read_to_string(&path).unwrap()).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            assert!(set.warnings().iter().all(|warning| warning.contains("validation rule")), "{:?}", set.warnings());
+        }
+
+        let set = ExerciseSet::parse(&with("formRule", "[]")).unwrap();
+        assert_eq!(set.warnings(), ["Exercise 'box-squat': unknown field 'formRule' ignored"]);
+    }
+}
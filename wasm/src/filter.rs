@@ -0,0 +1,310 @@
+// wasm/src/filter.rs
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+use crate::skeleton::{Keypoint, KeypointLayout, Landmark, Skeleton};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterKind {
+    OneEuro,
+    Kalman,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilterConfig {
+    pub kind: FilterKind,
+    // One Euro: smoothing at rest and how quickly it opens up with speed
+    pub min_cutoff: f32,
+    pub beta: f32,
+    pub derivative_cutoff: f32,
+    // Kalman: how much a point may wander per second vs how noisy the detector is,
+    // both as variances in keypoint units (pixels for MoveNet)
+    pub process_noise: f32,
+    pub measurement_noise: f32,
+    // Outlier rejection
+    pub min_confidence: f32,
+    pub max_jump: f32,        // Largest believable move per frame, in torso lengths
+    pub max_held_frames: u32, // After this many held frames the raw point is accepted again
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        FilterConfig {
+            kind: FilterKind::OneEuro,
+            min_cutoff: 1.0,
+            beta: 0.05,
+            derivative_cutoff: 1.0,
+            process_noise: 2000.0,
+            measurement_noise: 25.0,
+            min_confidence: 0.3,
+            max_jump: 0.5,
+            max_held_frames: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OneEuroAxis {
+    value: f32,
+    derivative: f32,
+}
+
+impl OneEuroAxis {
+    fn smoothing_factor(cutoff: f32, dt: f32) -> f32 {
+        let tau = 1.0 / (2.0 * PI * cutoff);
+        1.0 / (1.0 + tau / dt)
+    }
+
+    fn filter(&mut self, raw: f32, dt: f32, config: &FilterConfig) -> f32 {
+        let raw_derivative = (raw - self.value) / dt;
+        let alpha = Self::smoothing_factor(config.derivative_cutoff, dt);
+        self.derivative += alpha * (raw_derivative - self.derivative);
+
+        let cutoff = config.min_cutoff + config.beta * self.derivative.abs();
+        let alpha = Self::smoothing_factor(cutoff, dt);
+        self.value += alpha * (raw - self.value);
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KalmanAxis {
+    value: f32,
+    variance: f32,
+}
+
+impl KalmanAxis {
+    fn filter(&mut self, raw: f32, dt: f32, config: &FilterConfig) -> f32 {
+        // Random walk model: predict, then correct towards the measurement
+        self.variance += config.process_noise * dt;
+        let gain = self.variance / (self.variance + config.measurement_noise);
+        self.value += gain * (raw - self.value);
+        self.variance *= 1.0 - gain;
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AxisFilter {
+    OneEuro(OneEuroAxis),
+    Kalman(KalmanAxis),
+}
+
+impl AxisFilter {
+    fn new(kind: FilterKind, value: f32, config: &FilterConfig) -> Self {
+        match kind {
+            FilterKind::Kalman => AxisFilter::Kalman(KalmanAxis { value, variance: config.measurement_noise }),
+            _ => AxisFilter::OneEuro(OneEuroAxis { value, derivative: 0.0 }),
+        }
+    }
+
+    fn filter(&mut self, raw: f32, dt: f32, config: &FilterConfig) -> f32 {
+        match self {
+            AxisFilter::OneEuro(axis) => axis.filter(raw, dt, config),
+            AxisFilter::Kalman(axis) => axis.filter(raw, dt, config),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LandmarkTrack {
+    last: Keypoint, // Last accepted (filtered) point
+    axes: [AxisFilter; 3],
+    held_frames: u32,
+}
+
+impl LandmarkTrack {
+    fn new(point: Keypoint, config: &FilterConfig) -> Self {
+        let z = point.z.unwrap_or(0.0);
+        LandmarkTrack {
+            last: point,
+            axes: [
+                AxisFilter::new(config.kind, point.x, config),
+                AxisFilter::new(config.kind, point.y, config),
+                AxisFilter::new(config.kind, z, config),
+            ],
+            held_frames: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KeypointFilter {
+    config: FilterConfig,
+    tracks: Vec<Option<LandmarkTrack>>, // Indexed like the skeleton's layout
+}
+
+impl KeypointFilter {
+    pub fn new(config: FilterConfig) -> Self {
+        KeypointFilter { config, tracks: Vec::new() }
+    }
+
+    pub fn set_config(&mut self, config: FilterConfig) {
+        self.config = config;
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.tracks.clear();
+    }
+
+    // Smooths every landmark in place. Points that are low confidence or jump
+    // further than a body can move in one frame are replaced by the previous frame's
+    pub fn apply(&mut self, skeleton: &mut Skeleton, dt: f32) {
+        if self.config.kind == FilterKind::None || dt <= 0.0 {
+            return;
+        }
+
+        let max_jump = self.config.max_jump * self.torso_length(skeleton.layout());
+        let config = &self.config;
+        let points = skeleton.points_mut();
+        self.tracks.resize(points.len(), None);
+
+        for (point, slot) in points.iter_mut().zip(self.tracks.iter_mut()) {
+            let track = match slot {
+                Some(track) => track,
+                None => {
+                    if point.confidence >= config.min_confidence {
+                        *slot = Some(LandmarkTrack::new(*point, config));
+                    }
+                    continue;
+                }
+            };
+
+            let jump = ((point.x - track.last.x).powi(2) + (point.y - track.last.y).powi(2)).sqrt();
+            let is_outlier = point.confidence < config.min_confidence || (max_jump > 0.0 && jump > max_jump);
+
+            if is_outlier && track.held_frames < config.max_held_frames {
+                track.held_frames += 1;
+                *point = track.last;
+                continue;
+            }
+
+            if is_outlier {
+                // Held long enough, the point really has moved (or is really gone)
+                *track = LandmarkTrack::new(*point, config);
+                continue;
+            }
+
+            track.held_frames = 0;
+            point.x = track.axes[0].filter(point.x, dt, config);
+            point.y = track.axes[1].filter(point.y, dt, config);
+            point.z = point.z.map(|z| track.axes[2].filter(z, dt, config));
+            track.last = *point;
+        }
+    }
+
+    // Shoulder-to-hip distance from the last accepted frame, sets the scale for max_jump
+    fn torso_length(&self, layout: KeypointLayout) -> f32 {
+        let last = |landmark: Landmark| {
+            layout
+                .index_of(landmark)
+                .and_then(|index| self.tracks.get(index))
+                .and_then(|track| track.as_ref())
+                .map(|track| track.last)
+        };
+        let midpoint = |a: Landmark, b: Landmark| {
+            last(a).zip(last(b)).map(|(a, b)| ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0))
+        };
+
+        match (
+            midpoint(Landmark::LeftShoulder, Landmark::RightShoulder),
+            midpoint(Landmark::LeftHip, Landmark::RightHip),
+        ) {
+            (Some(shoulders), Some(hips)) => ((shoulders.0 - hips.0).powi(2) + (shoulders.1 - hips.1).powi(2)).sqrt(),
+            _ => 0.0, // Unknown scale, teleport check is skipped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 30.0;
+
+    // MoveNet skeleton with the torso 100 units tall and the left wrist at (x, y)
+    fn skeleton(x: f32, y: f32, confidence: f32) -> Skeleton {
+        let mut skeleton = Skeleton::from_flat(KeypointLayout::MoveNet, &[0.0; 17 * 3]).unwrap();
+        let layout = skeleton.layout();
+        let torso = [
+            (Landmark::LeftShoulder, 100.0),
+            (Landmark::RightShoulder, 100.0),
+            (Landmark::LeftHip, 200.0),
+            (Landmark::RightHip, 200.0),
+        ];
+        for (landmark, y) in torso {
+            skeleton.points_mut()[layout.index_of(landmark).unwrap()] = Keypoint { x: 300.0, y, z: None, confidence: 1.0 };
+        }
+        skeleton.points_mut()[layout.index_of(Landmark::LeftWrist).unwrap()] = Keypoint { x, y, z: None, confidence };
+        skeleton
+    }
+
+    // The filtered left wrist x for each raw x
+    fn run(config: FilterConfig, raw: impl IntoIterator<Item = f32>) -> Vec<f32> {
+        let mut filter = KeypointFilter::new(config);
+        raw.into_iter()
+            .map(|x| {
+                let mut skeleton = skeleton(x, 150.0, 1.0);
+                filter.apply(&mut skeleton, DT);
+                skeleton.point(Landmark::LeftWrist).x
+            })
+            .collect()
+    }
+
+    fn spread(values: &[f32]) -> f32 {
+        values.iter().fold(f32::MIN, |a, &b| a.max(b)) - values.iter().fold(f32::MAX, |a, &b| a.min(b))
+    }
+
+    fn jitter(frames: usize) -> impl Iterator<Item = f32> {
+        (0..frames).map(|frame| if frame % 2 == 0 { 196.0 } else { 204.0 })
+    }
+
+    #[test]
+    fn one_euro_damps_jitter_at_rest() {
+        let filtered = run(FilterConfig::default(), jitter(90));
+        assert!(spread(&filtered[30..]) < 4.0, "{:?}", &filtered[30..]);
+    }
+
+    #[test]
+    fn one_euro_catches_up_with_a_move() {
+        let filtered = run(FilterConfig::default(), (0..60).map(|frame| if frame < 10 { 200.0 } else { 230.0 }));
+        assert!((filtered[59] - 230.0).abs() < 1.0, "{}", filtered[59]);
+    }
+
+    #[test]
+    fn kalman_damps_jitter_and_settles_on_the_mean() {
+        let config = FilterConfig { kind: FilterKind::Kalman, ..FilterConfig::default() };
+        let filtered = run(config, jitter(90));
+        assert!(spread(&filtered[30..]) < 8.0, "{:?}", &filtered[30..]);
+        assert!((filtered[89] - 200.0).abs() < 4.0, "{}", filtered[89]);
+    }
+
+    #[test]
+    fn none_leaves_points_alone() {
+        let config = FilterConfig { kind: FilterKind::None, ..FilterConfig::default() };
+        assert_eq!(run(config, jitter(4)), vec![196.0, 204.0, 196.0, 204.0]);
+    }
+
+    #[test]
+    fn teleports_are_held_then_accepted() {
+        let config = FilterConfig { max_held_frames: 3, ..FilterConfig::default() };
+        // A jump of 500 is five torso lengths, far beyond max_jump
+        let filtered = run(config, (0..8).map(|frame| if frame < 2 { 200.0 } else { 700.0 }));
+        assert!(filtered[2..5].iter().all(|&x| (x - 200.0).abs() < 1.0), "{:?}", filtered);
+        assert_eq!(filtered[5], 700.0);
+    }
+
+    #[test]
+    fn low_confidence_points_keep_the_last_position() {
+        let mut filter = KeypointFilter::new(FilterConfig::default());
+        let mut first = skeleton(200.0, 150.0, 1.0);
+        filter.apply(&mut first, DT);
+        let mut dropout = skeleton(0.0, 0.0, 0.1);
+        filter.apply(&mut dropout, DT);
+        assert_eq!(dropout.point(Landmark::LeftWrist), first.point(Landmark::LeftWrist));
+    }
+}
@@ -0,0 +1,101 @@
+// wasm/src/form.rs
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::rep_counter::MovementPhase;
+
+// Ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,     // Coaching cue, the rep still counts
+    Warning,  // Range or control off target
+    Critical, // Injury risk, worth interrupting the set for
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TargetRange {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormIssue {
+    pub code: String, // Stable id for cue lookup and localisation, e.g. "knee_flexion_low"
+    pub severity: Severity,
+    pub joint: Option<String>, // Angle key the issue was measured on
+    pub measured: Option<f32>,
+    pub target: TargetRange,
+    pub phase: Option<MovementPhase>,
+    pub message: String, // Default English rendering
+}
+
+impl FormIssue {
+    pub fn new(code: impl Into<String>, severity: Severity, message: impl Into<String>) -> Self {
+        FormIssue {
+            code: code.into(),
+            severity,
+            joint: None,
+            measured: None,
+            target: TargetRange::default(),
+            phase: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn on_joint(mut self, joint: &str) -> Self {
+        self.joint = Some(joint.to_string());
+        self
+    }
+
+    pub fn measured(mut self, joint: &str, value: f32) -> Self {
+        self.joint = Some(joint.to_string());
+        self.measured = Some(value);
+        self
+    }
+
+    pub fn target(mut self, min: f32, max: f32) -> Self {
+        self.target = TargetRange { min: Some(min), max: Some(max) };
+        self
+    }
+
+    pub fn at_least(mut self, min: f32) -> Self {
+        self.target = TargetRange { min: Some(min), max: None };
+        self
+    }
+
+    pub fn at_most(mut self, max: f32) -> Self {
+        self.target = TargetRange { min: None, max: Some(max) };
+        self
+    }
+
+    pub fn within(mut self, target: TargetRange) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn in_phase(mut self, phase: MovementPhase) -> Self {
+        self.phase = Some(phase);
+        self
+    }
+}
+
+impl fmt::Display for FormIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// Most severe first, keeping check order within a severity, and one issue per code
+pub fn prioritize(issues: &mut Vec<FormIssue>) {
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    let mut seen = Vec::new();
+    issues.retain(|issue| {
+        if seen.contains(&issue.code) {
+            false
+        } else {
+            seen.push(issue.code.clone());
+            true
+        }
+    });
+}
@@ -0,0 +1,104 @@
+// wasm/src/hold.rs
+// Time under tension for static exercises (planks, superman holds). The clock only runs while
+// every required angle is inside its range and the frame is free of form breaks; anything else
+// pauses it and ends the current continuous hold
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::form::{FormIssue, Severity};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoldRange {
+    pub angle: String,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl HoldRange {
+    pub fn new(angle: &str, (min, max): (f32, f32)) -> Self {
+        HoldRange { angle: angle.to_string(), min, max }
+    }
+}
+
+// Times in seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldReport {
+    pub holding: bool,
+    pub current: f64, // The continuous hold in progress, 0 while paused
+    pub total: f64,
+    pub longest: f64,
+    pub breaks: u32, // Continuous holds ended by a form break or leaving the range
+    pub broken_by: Vec<String>, // Angle keys out of range and issue codes pausing the clock this frame
+}
+
+#[derive(Debug)]
+pub struct HoldTimer {
+    ranges: Vec<HoldRange>,
+    last_timestamp: Option<f64>, // Previous frame, while it was a valid one
+    current: f64,
+    total: f64,
+    longest: f64,
+    breaks: u32,
+}
+
+impl HoldTimer {
+    // Without ranges the hold is judged by `in_hold` alone, see update
+    pub fn new(ranges: Vec<HoldRange>) -> Self {
+        HoldTimer {
+            ranges,
+            last_timestamp: None,
+            current: 0.0,
+            total: 0.0,
+            longest: 0.0,
+            breaks: 0,
+        }
+    }
+
+    // `in_hold` is the rep counter's view (MovementPhase::StaticHold), used when the
+    // exercise names no ranges. Angles that can't be seen pause the clock, the hold
+    // can't be verified
+    pub fn update(&mut self, angles: &HashMap<String, f32>, form_issues: &[FormIssue], in_hold: bool, timestamp: f64) -> HoldReport {
+        let mut broken_by: Vec<String> = self
+            .ranges
+            .iter()
+            .filter(|range| angles.get(&range.angle).is_none_or(|angle| *angle < range.min || *angle > range.max))
+            .map(|range| range.angle.clone())
+            .collect();
+        broken_by.extend(
+            form_issues
+                .iter()
+                .filter(|issue| issue.severity >= Severity::Warning)
+                .map(|issue| issue.code.clone()),
+        );
+        let valid = broken_by.is_empty() && (in_hold || !self.ranges.is_empty());
+
+        if valid {
+            if let Some(last) = self.last_timestamp {
+                let dt = (timestamp - last).max(0.0);
+                self.current += dt;
+                self.total += dt;
+                self.longest = self.longest.max(self.current);
+            }
+            self.last_timestamp = Some(timestamp);
+        } else {
+            if self.current > 0.0 {
+                self.breaks += 1;
+            }
+            self.current = 0.0;
+            self.last_timestamp = None;
+        }
+
+        HoldReport {
+            holding: valid,
+            current: self.current,
+            total: self.total,
+            longest: self.longest,
+            breaks: self.breaks,
+            broken_by,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = HoldTimer::new(std::mem::take(&mut self.ranges));
+    }
+}
@@ -0,0 +1,324 @@
+// wasm/src/logging.rs
+// Small log facade for the analyzer. Messages go through a level filter and a rate limit
+// on repeats of the same message before reaching the sink, so per-frame diagnostics can't
+// flood the console while one-off events (each rep, each set) all get through
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub level: Level,
+    pub target: &'a str, // Which part of the pipeline is talking, e.g. "rep_counter"
+    pub message: &'a str,
+    pub suppressed: u32, // Repeats of this message dropped by the rate limit since it last went out
+}
+
+pub trait LogSink {
+    fn write(&self, record: &Record);
+}
+
+// Native default
+#[derive(Debug, Default)]
+pub struct StderrSink;
+
+impl LogSink for StderrSink {
+    fn write(&self, record: &Record) {
+        eprintln!("{}", format_record(record));
+    }
+}
+
+// Appends one line per record
+#[derive(Debug)]
+pub struct FileSink {
+    file: RefCell<File>,
+}
+
+impl FileSink {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<FileSink> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileSink { file: RefCell::new(file) })
+    }
+}
+
+impl LogSink for FileSink {
+    fn write(&self, record: &Record) {
+        // Logging must never take the analyzer down, a failed write is dropped
+        let _ = writeln!(self.file.borrow_mut(), "{}", format_record(record));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NullSink;
+
+impl LogSink for NullSink {
+    fn write(&self, _record: &Record) {}
+}
+
+// Browser default, maps levels onto the matching console method
+#[cfg(feature = "wasm")]
+#[derive(Debug, Default)]
+pub struct ConsoleSink;
+
+#[cfg(feature = "wasm")]
+impl LogSink for ConsoleSink {
+    fn write(&self, record: &Record) {
+        use wasm_bindgen::JsValue;
+        use web_sys::console;
+
+        let line = JsValue::from_str(&format_record(record));
+        match record.level {
+            Level::Debug => console::debug_1(&line),
+            Level::Info => console::info_1(&line),
+            Level::Warn => console::warn_1(&line),
+            Level::Error => console::error_1(&line),
+        }
+    }
+}
+
+// Hands records to a JS function as (level, target, message, suppressed), for app telemetry
+#[cfg(feature = "wasm")]
+#[derive(Debug)]
+pub struct JsCallbackSink {
+    callback: js_sys::Function,
+}
+
+#[cfg(feature = "wasm")]
+impl JsCallbackSink {
+    pub fn new(callback: js_sys::Function) -> Self {
+        JsCallbackSink { callback }
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl LogSink for JsCallbackSink {
+    fn write(&self, record: &Record) {
+        use wasm_bindgen::JsValue;
+
+        let args = js_sys::Array::of4(
+            &JsValue::from_str(record.level.name()),
+            &JsValue::from_str(record.target),
+            &JsValue::from_str(record.message),
+            &JsValue::from(record.suppressed),
+        );
+        // A throwing callback is the app's problem, keep analyzing
+        let _ = self.callback.apply(&JsValue::NULL, &args);
+    }
+}
+
+fn format_record(record: &Record) -> String {
+    let mut line = format!("[ai_trainer {} {}] {}", record.level.name(), record.target, record.message);
+    if record.suppressed > 0 {
+        line.push_str(&format!(" ({} similar suppressed)", record.suppressed));
+    }
+    line
+}
+
+fn default_sink() -> Rc<dyn LogSink> {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    return Rc::new(ConsoleSink);
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    return Rc::new(StderrSink);
+}
+
+// Wall clock in milliseconds, only used to space out messages
+fn now_ms() -> f64 {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    return js_sys::Date::now();
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0);
+}
+
+// Repeats tracked at most, past this the ones outside the rate limit window are forgotten
+const MAX_TRACKED: usize = 256;
+
+#[derive(Debug, Clone, Copy)]
+struct MessageState {
+    last_emitted: f64,
+    suppressed: u32,
+}
+
+struct Logger {
+    level: Option<Level>, // None silences everything
+    rate_limit_ms: f64,   // Minimum gap between repeats of one message, 0 = unlimited
+    sink: Rc<dyn LogSink>,
+    recent: HashMap<(Level, String, String), MessageState>, // By level, target and message
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Logger {
+            level: Some(Level::Info),
+            rate_limit_ms: 1000.0,
+            sink: default_sink(),
+            recent: HashMap::new(),
+        }
+    }
+}
+
+impl Logger {
+    // Returns the suppressed count to report if this message should go out now
+    fn admit(&mut self, level: Level, target: &str, message: &str, now: f64) -> Option<u32> {
+        if self.level.is_none_or(|min| level < min) {
+            return None;
+        }
+        if self.rate_limit_ms <= 0.0 {
+            return Some(0);
+        }
+
+        let key = (level, target.to_string(), message.to_string());
+        match self.recent.get_mut(&key) {
+            Some(state) if now - state.last_emitted < self.rate_limit_ms => {
+                state.suppressed += 1;
+                None
+            }
+            Some(state) => {
+                let suppressed = state.suppressed;
+                *state = MessageState { last_emitted: now, suppressed: 0 };
+                Some(suppressed)
+            }
+            None => {
+                if self.recent.len() >= MAX_TRACKED {
+                    let rate_limit_ms = self.rate_limit_ms;
+                    self.recent.retain(|_, state| now - state.last_emitted < rate_limit_ms);
+                }
+                self.recent.insert(key, MessageState { last_emitted: now, suppressed: 0 });
+                Some(0)
+            }
+        }
+    }
+}
+
+// The analyzer lives on one thread (the browser main thread or a worker), so a
+// thread-local logger avoids requiring Send from JS-backed sinks
+thread_local! {
+    static LOGGER: RefCell<Logger> = RefCell::new(Logger::default());
+}
+
+pub fn set_level(level: Option<Level>) {
+    LOGGER.with(|logger| logger.borrow_mut().level = level);
+}
+
+pub fn set_rate_limit(interval_ms: f64) {
+    LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        logger.rate_limit_ms = interval_ms.max(0.0);
+        logger.recent.clear();
+    });
+}
+
+pub fn set_sink(sink: impl LogSink + 'static) {
+    LOGGER.with(|logger| logger.borrow_mut().sink = Rc::new(sink));
+}
+
+pub fn reset_sink() {
+    LOGGER.with(|logger| logger.borrow_mut().sink = default_sink());
+}
+
+pub fn enabled(level: Level) -> bool {
+    LOGGER.with(|logger| logger.borrow().level.is_some_and(|min| level >= min))
+}
+
+pub fn log(level: Level, target: &str, message: &str) {
+    if !enabled(level) {
+        return;
+    }
+
+    // The sink is called outside the borrow so a sink that logs (say a JS callback
+    // calling back into wasm) can't trip the RefCell
+    let admitted = LOGGER.with(|logger| {
+        let mut logger = logger.try_borrow_mut().ok()?;
+        let suppressed = logger.admit(level, target, message, now_ms())?;
+        Some((Rc::clone(&logger.sink), suppressed))
+    });
+
+    if let Some((sink, suppressed)) = admitted {
+        sink.write(&Record { level, target, message, suppressed });
+    }
+}
+
+pub fn debug(target: &str, message: &str) {
+    log(Level::Debug, target, message);
+}
+
+pub fn info(target: &str, message: &str) {
+    log(Level::Info, target, message);
+}
+
+pub fn warn(target: &str, message: &str) {
+    log(Level::Warn, target, message);
+}
+
+pub fn error(target: &str, message: &str) {
+    log(Level::Error, target, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Capture(Rc<RefCell<Vec<String>>>);
+
+    impl LogSink for Capture {
+        fn write(&self, record: &Record) {
+            self.0.borrow_mut().push(format_record(record));
+        }
+    }
+
+    #[test]
+    fn rate_limits_repeats_but_not_each_rep() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        set_sink(Capture(Rc::clone(&lines)));
+        // Reps closer together than the default limit all make it to the sink
+        for rep in 1..=3 {
+            info("rep_counter", &format!("Rep {} detected!", rep));
+        }
+        assert_eq!(lines.borrow().len(), 3);
+
+        let mut logger = Logger::default();
+        let admit = |logger: &mut Logger, message: &str, now: f64| logger.admit(Level::Warn, "form", message, now);
+        assert_eq!(admit(&mut logger, "Knees out", 0.0), Some(0));
+        assert_eq!(admit(&mut logger, "Knees out", 30.0), None);
+        assert_eq!(admit(&mut logger, "Chest up", 60.0), Some(0));
+        assert_eq!(admit(&mut logger, "Knees out", 90.0), None);
+        assert_eq!(admit(&mut logger, "Knees out", 1000.0), Some(2));
+        assert_eq!(logger.admit(Level::Error, "form", "Knees out", 1010.0), Some(0));
+    }
+}
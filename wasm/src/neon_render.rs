@@ -1,33 +1,394 @@
 // wasm/src/neon_render.rs
-use wasm_bindgen::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 
-#[wasm_bindgen]
+use crate::form::{FormIssue, Severity};
+use crate::png;
+use crate::pose_detection::MIN_CONFIDENCE;
+use crate::skeleton::{Keypoint, KeypointLayout, Landmark, Skeleton, BONES};
+
+// CameraFeed.jsx asks the camera for 640x480
+const DEFAULT_WIDTH: u32 = 640;
+const DEFAULT_HEIGHT: u32 = 480;
+const WHITE_HOT: f32 = 0.7; // How far the bone core is pushed towards white
+
+type Bone = (Landmark, Landmark);
+
+// RGB in 0-1. Bones go from base to the heat ramp where their muscle is engaged,
+// and to warning/error where a form issue involves one of their joints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Palette {
+    pub base: [f32; 3],
+    pub warning: [f32; 3],
+    pub error: [f32; 3],
+    pub heat_low: [f32; 3],
+    pub heat_high: [f32; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            base: [0.0, 1.0, 1.0],     // Cyan
+            warning: [1.0, 0.7, 0.0],  // Amber
+            error: [1.0, 0.15, 0.15],  // Red
+            heat_low: [0.3, 0.4, 1.0], // Blue
+            heat_high: [1.0, 0.0, 0.8], // Magenta
+        }
+    }
+}
+
+impl Palette {
+    fn heat(&self, engagement: f32) -> [f32; 3] {
+        let t = engagement.clamp(0.0, 1.0);
+        [0, 1, 2].map(|i| self.heat_low[i] + (self.heat_high[i] - self.heat_low[i]) * t)
+    }
+}
+
+// Left-side bones over each muscle group, mirrored for the right
+const MUSCLE_REGIONS: [(&str, &[Bone]); 11] = [
+    ("quads", &[(Landmark::LeftHip, Landmark::LeftKnee)]),
+    ("hamstrings", &[(Landmark::LeftHip, Landmark::LeftKnee)]),
+    ("glutes", &[(Landmark::LeftHip, Landmark::RightHip), (Landmark::LeftHip, Landmark::LeftKnee)]),
+    ("calves", &[(Landmark::LeftKnee, Landmark::LeftAnkle)]),
+    ("abs", &[(Landmark::LeftShoulder, Landmark::LeftHip)]),
+    ("back", &[(Landmark::LeftShoulder, Landmark::RightShoulder), (Landmark::LeftShoulder, Landmark::LeftHip)]),
+    ("chest", &[(Landmark::LeftShoulder, Landmark::RightShoulder), (Landmark::LeftShoulder, Landmark::LeftElbow)]),
+    ("shoulders", &[(Landmark::LeftShoulder, Landmark::RightShoulder), (Landmark::LeftShoulder, Landmark::LeftElbow)]),
+    ("biceps", &[(Landmark::LeftShoulder, Landmark::LeftElbow)]),
+    ("triceps", &[(Landmark::LeftShoulder, Landmark::LeftElbow)]),
+    ("full_body", &[
+        (Landmark::LeftShoulder, Landmark::LeftHip),
+        (Landmark::LeftHip, Landmark::LeftKnee),
+        (Landmark::LeftKnee, Landmark::LeftAnkle),
+        (Landmark::LeftShoulder, Landmark::LeftElbow),
+    ]),
+];
+
+// Angle key keywords and the (left-side) landmarks they are measured at, first match wins.
+// Bare "flexion"/"rotation" keys come from the shoulder analyzer
+const JOINT_KEYWORDS: [(&str, &[Landmark]); 19] = [
+    ("knee", &[Landmark::LeftKnee]),
+    ("ankle", &[Landmark::LeftAnkle]),
+    ("plantar", &[Landmark::LeftAnkle]),
+    ("rom_achieved", &[Landmark::LeftAnkle]),
+    ("stance", &[Landmark::LeftAnkle]),
+    ("muscle_balance", &[Landmark::LeftKnee]),
+    ("hip", &[Landmark::LeftHip]),
+    ("lumbar", &[Landmark::LeftHip]),
+    ("unilateral", &[Landmark::LeftHip]),
+    ("torso", &[Landmark::LeftShoulder, Landmark::LeftHip]),
+    ("body", &[Landmark::LeftShoulder, Landmark::LeftHip]),
+    ("elbow", &[Landmark::LeftElbow]),
+    ("curl", &[Landmark::LeftElbow]),
+    ("wrist", &[Landmark::LeftWrist]),
+    ("press", &[Landmark::LeftShoulder, Landmark::LeftElbow]),
+    ("shoulder", &[Landmark::LeftShoulder]),
+    ("scapular", &[Landmark::LeftShoulder]),
+    ("abduction", &[Landmark::LeftShoulder]),
+    ("flexion", &[Landmark::LeftShoulder]),
+];
+
+#[derive(Debug)]
 pub struct NeonRenderer {
-    color: [f32; 3],
+    palette: Palette,
     intensity: f32,
+    width: u32,
+    height: u32,
+    png_output: bool,
+    flagged: HashMap<Landmark, Severity>, // Joints implicated in this frame's form issues
+    engagement: HashMap<String, f32>,     // Muscle group -> engagement, 0-1
 }
 
-#[wasm_bindgen]
 impl NeonRenderer {
-    #[wasm_bindgen(constructor)]
     pub fn new(r: f32, g: f32, b: f32) -> Self {
         NeonRenderer {
-            color: [r, g, b],
+            palette: Palette { base: [r, g, b], ..Palette::default() },
             intensity: 1.0,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            png_output: false,
+            flagged: HashMap::new(),
+            engagement: HashMap::new(),
+        }
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    // Angle keys to tint, e.g. "left_knee_flexion" -> Critical, "hip_depth" -> Warning
+    pub fn set_flagged_joints(&mut self, joints: &HashMap<String, Severity>) {
+        self.flagged.clear();
+        for (joint, &severity) in joints {
+            self.flag_joint(joint, severity);
         }
     }
 
-    #[wasm_bindgen]
-    pub fn render(&self, keypoints: &[f32]) -> Vec<f32> {
-        keypoints.chunks_exact(3)
-            .flat_map(|kp| {
-                [kp[0], kp[1], self.color[0], self.color[1], self.color[2], self.intensity * kp[2]]
-            })
-            .collect()
+    // Muscle group -> engagement, e.g. "quads" -> 0.8
+    pub fn set_engagement_map(&mut self, engagement: HashMap<String, f32>) {
+        self.engagement = engagement;
+    }
+
+    // Output size in pixels, normally the canvas the overlay is blitted onto
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.width = width.max(1);
+        self.height = height.max(1);
+    }
+
+    // Raw RGBA (for ImageData) when off, an encoded PNG when on
+    pub fn set_png_output(&mut self, enabled: bool) {
+        self.png_output = enabled;
+    }
+
+    // Flat (x, y, confidence) keypoints in MoveNet/COCO-17 or MediaPipe-33 order
+    pub fn render(&self, keypoints: &[f32]) -> Result<Vec<u8>, String> {
+        let skeleton = [KeypointLayout::MoveNet, KeypointLayout::MediaPipe33]
+            .into_iter()
+            .find_map(|layout| Skeleton::from_flat(layout, keypoints).ok())
+            .ok_or_else(|| format!("Unrecognised keypoint array length {}", keypoints.len()))?;
+        self.render_skeleton(&skeleton)
     }
 
-    #[wasm_bindgen]
     pub fn set_intensity(&mut self, engagement: f32) {
         self.intensity = engagement.clamp(0.3, 1.0);
     }
+
+    // Replaces the flagged joints with those of this frame's issues. Info-level cues
+    // aren't drawn, only warnings and worse
+    pub fn set_form_issues(&mut self, issues: &[FormIssue]) {
+        self.flagged.clear();
+        for issue in issues {
+            if let Some(joint) = &issue.joint {
+                self.flag_joint(joint, issue.severity);
+            }
+        }
+    }
+
+    pub fn set_muscle_engagement(&mut self, muscle_group: &str, engagement: f32) {
+        self.engagement.clear();
+        self.engagement.insert(muscle_group.to_string(), engagement);
+    }
+
+    fn flag_joint(&mut self, joint: &str, severity: Severity) {
+        if severity < Severity::Warning {
+            return;
+        }
+        for landmark in joint_landmarks(joint) {
+            let flagged = self.flagged.entry(landmark).or_insert(severity);
+            *flagged = (*flagged).max(severity);
+        }
+    }
+
+    // Worst issue at either end wins, then the hottest engaged muscle under the bone
+    fn bone_color(&self, bone: Bone) -> [f32; 3] {
+        let severity = [bone.0, bone.1].iter().filter_map(|landmark| self.flagged.get(landmark)).max();
+        match severity {
+            Some(Severity::Critical) => return self.palette.error,
+            Some(Severity::Warning) => return self.palette.warning,
+            _ => {}
+        }
+
+        MUSCLE_REGIONS
+            .iter()
+            .filter(|(_, bones)| bones.iter().any(|&region_bone| same_bone(region_bone, bone)))
+            .filter_map(|(group, _)| self.engagement.get(*group))
+            .copied()
+            .reduce(f32::max)
+            .map_or(self.palette.base, |engagement| self.palette.heat(engagement))
+    }
+
+    fn joint_color(&self, landmark: Landmark) -> [f32; 3] {
+        match self.flagged.get(&landmark) {
+            Some(Severity::Critical) => self.palette.error,
+            Some(Severity::Warning) => self.palette.warning,
+            _ => self.palette.base,
+        }
+    }
+
+    pub fn render_skeleton(&self, skeleton: &Skeleton) -> Result<Vec<u8>, String> {
+        let rgba = self.rasterize(skeleton);
+        if self.png_output {
+            png::encode_rgba(self.width, self.height, &rgba)
+        } else {
+            Ok(rgba)
+        }
+    }
+
+    fn rasterize(&self, skeleton: &Skeleton) -> Vec<u8> {
+        let mut canvas = GlowCanvas::new(self.width, self.height, self.intensity);
+
+        // Bone thickness follows the output size, the glow widens with engagement
+        let core_radius = (self.width.min(self.height) as f32 / 240.0).max(1.5);
+        let glow_radius = core_radius * (2.0 + 6.0 * self.intensity);
+
+        let visible: Vec<(Landmark, Keypoint)> = skeleton
+            .layout()
+            .landmarks()
+            .iter()
+            .map(|&landmark| (landmark, skeleton.point(landmark)))
+            .filter(|(_, point)| point.confidence >= MIN_CONFIDENCE)
+            .collect();
+        let to_pixels = self.pixel_mapping(&visible);
+
+        for bone in BONES {
+            let (a, b) = (skeleton.point(bone.0), skeleton.point(bone.1));
+            if a.confidence < MIN_CONFIDENCE || b.confidence < MIN_CONFIDENCE {
+                continue;
+            }
+            let stroke = Stroke {
+                core_radius,
+                glow_radius,
+                strength: a.confidence.min(b.confidence).min(1.0),
+                color: self.bone_color(bone),
+            };
+            canvas.stroke(to_pixels(&a), to_pixels(&b), &stroke);
+        }
+
+        for (landmark, point) in &visible {
+            let center = to_pixels(point);
+            let stroke = Stroke {
+                core_radius: core_radius * 1.8,
+                glow_radius,
+                strength: point.confidence.min(1.0),
+                color: self.joint_color(*landmark),
+            };
+            canvas.stroke(center, center, &stroke);
+        }
+
+        canvas.to_rgba()
+    }
+
+    // MoveNet reports pixels, BlazePose normalised 0-1 coordinates
+    fn pixel_mapping(&self, points: &[(Landmark, Keypoint)]) -> impl Fn(&Keypoint) -> (f32, f32) {
+        let normalized = !points.is_empty()
+            && points.iter().all(|(_, point)| point.x.abs() <= 1.5 && point.y.abs() <= 1.5);
+        let scale = if normalized { (self.width as f32, self.height as f32) } else { (1.0, 1.0) };
+        move |point: &Keypoint| (point.x * scale.0, point.y * scale.1)
+    }
+}
+
+struct Stroke {
+    core_radius: f32,
+    glow_radius: f32,
+    strength: f32, // Keypoint confidence, fades uncertain bones
+    color: [f32; 3],
+}
+
+// Per-pixel glow alpha, white-hot core weight and color. Combined by max so
+// overlapping bones don't saturate; the brighter stroke also sets the color
+struct GlowCanvas {
+    width: usize,
+    height: usize,
+    intensity: f32,
+    glow: Vec<f32>,
+    core: Vec<f32>,
+    color: Vec<[f32; 3]>,
+}
+
+impl GlowCanvas {
+    fn new(width: u32, height: u32, intensity: f32) -> Self {
+        let size = width as usize * height as usize;
+        GlowCanvas {
+            width: width as usize,
+            height: height as usize,
+            intensity,
+            glow: vec![0.0; size],
+            core: vec![0.0; size],
+            color: vec![[0.0; 3]; size],
+        }
+    }
+
+    // Capsule from a to b (a circle when they coincide): solid out to core_radius,
+    // then a quadratic falloff to glow_radius whose brightness follows intensity
+    fn stroke(&mut self, a: (f32, f32), b: (f32, f32), stroke: &Stroke) {
+        let core_radius = stroke.core_radius;
+        let glow_radius = stroke.glow_radius.max(core_radius);
+        let min_x = (a.0.min(b.0) - glow_radius).floor().max(0.0) as usize;
+        let min_y = (a.1.min(b.1) - glow_radius).floor().max(0.0) as usize;
+        let max_x = ((a.0.max(b.0) + glow_radius).ceil().max(0.0) as usize).min(self.width);
+        let max_y = ((a.1.max(b.1) + glow_radius).ceil().max(0.0) as usize).min(self.height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let distance = distance_to_segment((x as f32 + 0.5, y as f32 + 0.5), a, b);
+                if distance >= glow_radius {
+                    continue;
+                }
+
+                let (glow, core) = if distance <= core_radius {
+                    (1.0, 1.0)
+                } else {
+                    let falloff = 1.0 - (distance - core_radius) / (glow_radius - core_radius);
+                    (self.intensity * falloff * falloff, 0.0)
+                };
+
+                let index = y * self.width + x;
+                let glow = glow * stroke.strength;
+                if glow > self.glow[index] {
+                    self.glow[index] = glow;
+                    self.color[index] = stroke.color;
+                }
+                self.core[index] = self.core[index].max(core * stroke.strength);
+            }
+        }
+    }
+
+    fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.glow.len() * 4);
+        for ((&glow, &core), &color) in self.glow.iter().zip(&self.core).zip(&self.color) {
+            let white = core * WHITE_HOT;
+            for channel in color {
+                rgba.push(to_byte(channel * (1.0 - white) + white));
+            }
+            rgba.push(to_byte(glow));
+        }
+        rgba
+    }
+}
+
+// Landmarks an angle key is measured at. "left_"/"right_" keys name one side,
+// plain keys are averaged over both so they flag both
+fn joint_landmarks(joint: &str) -> Vec<Landmark> {
+    let (sides, name): (&[bool], &str) = if let Some(name) = joint.strip_prefix("left_") {
+        (&[false], name)
+    } else if let Some(name) = joint.strip_prefix("right_") {
+        (&[true], name)
+    } else {
+        (&[false, true], joint)
+    };
+
+    JOINT_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| name.contains(keyword))
+        .map(|(_, landmarks)| {
+            sides
+                .iter()
+                .flat_map(|&mirrored| landmarks.iter().map(move |&landmark| if mirrored { landmark.mirrored() } else { landmark }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn same_bone(region_bone: Bone, bone: Bone) -> bool {
+    let mirrored = (region_bone.0.mirrored(), region_bone.1.mirrored());
+    [region_bone, mirrored]
+        .iter()
+        .any(|&(a, b)| (a, b) == bone || (b, a) == bone)
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (nearest_x, nearest_y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - nearest_x).powi(2) + (p.1 - nearest_y).powi(2)).sqrt()
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
 }
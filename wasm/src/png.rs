@@ -0,0 +1,199 @@
+// wasm/src/png.rs
+
+// Minimal PNG writer for RGBA8 frames. Image data goes out as stored (uncompressed)
+// deflate blocks: the overlay is mostly transparent but encoding speed matters more
+// per frame than size, and it keeps the crate free of a compression dependency
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const MAX_STORED_BLOCK: usize = 65_535;
+
+pub fn encode_rgba(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    if width == 0 || height == 0 {
+        return Err(format!("PNG dimensions must be non-zero, got {}x{}", width, height));
+    }
+    let row_bytes = width as usize * 4;
+    if rgba.len() != row_bytes * height as usize {
+        return Err(format!(
+            "RGBA buffer is {} bytes, expected {} for {}x{}",
+            rgba.len(),
+            row_bytes * height as usize,
+            width,
+            height
+        ));
+    }
+
+    // Every scanline is prefixed with filter type 0 (none)
+    let mut raw = Vec::with_capacity((row_bytes + 1) * height as usize);
+    for row in rgba.chunks_exact(row_bytes) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bits, RGBA, deflate, adaptive filtering, no interlace
+
+    let mut png = Vec::with_capacity(raw.len() + raw.len() / MAX_STORED_BLOCK * 5 + 64);
+    png.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_STORED_BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]); // Deflate, 32K window, no preset dictionary
+
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(is_last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let crc = bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    });
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the longest run that can't overflow b before the modulo
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (kind, data) of every chunk, checking each CRC on the way
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(png[..8], SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let body = &rest[4..8 + len];
+            let crc = u32::from_be_bytes(rest[8 + len..12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(body), "CRC of {:?}", String::from_utf8_lossy(&body[..4]));
+            chunks.push((body[..4].try_into().unwrap(), body[4..].to_vec()));
+            rest = &rest[12 + len..];
+        }
+        chunks
+    }
+
+    // Undoes zlib_stored, checking the block lengths and the Adler-32
+    fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(zlib[..2], [0x78, 0x01]);
+        assert_eq!(u16::from_be_bytes([zlib[0], zlib[1]]) % 31, 0);
+        let mut out = Vec::new();
+        let mut at = 2;
+        loop {
+            let last = zlib[at] == 1;
+            let len = u16::from_le_bytes([zlib[at + 1], zlib[at + 2]]);
+            assert_eq!(!len, u16::from_le_bytes([zlib[at + 3], zlib[at + 4]]));
+            out.extend_from_slice(&zlib[at + 5..at + 5 + len as usize]);
+            at += 5 + len as usize;
+            if last {
+                break;
+            }
+        }
+        assert_eq!(zlib[at..], adler32(&out).to_be_bytes());
+        out
+    }
+
+    fn round_trip(width: u32, height: u32) {
+        let rgba: Vec<u8> = (0..width * height * 4).map(|i| (i * 7 % 251) as u8).collect();
+        let png = encode_rgba(width, height, &rgba).unwrap();
+
+        let chunks = chunks(&png);
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+
+        let header = &chunks[0].1;
+        assert_eq!(header[..4], width.to_be_bytes());
+        assert_eq!(header[4..8], height.to_be_bytes());
+        assert_eq!(header[8..], [8, 6, 0, 0, 0]);
+
+        let raw = inflate_stored(&chunks[1].1);
+        let row_bytes = width as usize * 4 + 1;
+        assert_eq!(raw.len(), row_bytes * height as usize);
+        let mut pixels = Vec::new();
+        for row in raw.chunks(row_bytes) {
+            assert_eq!(row[0], 0);
+            pixels.extend_from_slice(&row[1..]);
+        }
+        assert_eq!(pixels, rgba);
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn round_trips_a_small_image() {
+        round_trip(3, 2);
+    }
+
+    #[test]
+    fn round_trips_across_stored_blocks() {
+        // 80,100 bytes of scanlines, two stored blocks
+        round_trip(200, 100);
+    }
+
+    #[test]
+    fn rejects_bad_dimensions() {
+        assert!(encode_rgba(0, 4, &[]).is_err());
+        assert!(encode_rgba(2, 2, &[0; 15]).is_err());
+    }
+}
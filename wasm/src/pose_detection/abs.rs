@@ -0,0 +1,388 @@
+// wasm/src/pose_detection/abs.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, insert_angle, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::hold::HoldRange;
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct AbsAnalyzer {
+    abs_exercises: HashMap<&'static str, AbExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct AbExerciseProfile {
+    primary_joints: Vec<&'static str>,
+    target_angles: HashMap<&'static str, (f32, f32)>,
+    #[allow(dead_code)] // Coaching copy, the checks below emit their own messages
+    common_errors: Vec<&'static str>,
+    engagement_factor: f32,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+impl AbsAnalyzer {
+    pub fn new() -> Self {
+        let mut abs_exercises = HashMap::new();
+
+        // 1. Jack Knife
+        abs_exercises.insert("jack-knife", AbExerciseProfile {
+            primary_joints: vec!["hip", "shoulder"],
+            target_angles: HashMap::from([
+                ("hip", (45.0, 90.0)),
+                ("shoulder", (30.0, 60.0)),
+            ]),
+            common_errors: vec!["Overarching lower back", "Using momentum"],
+            engagement_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Hanging Leg Raises
+        abs_exercises.insert("hanging-leg-raises", AbExerciseProfile {
+            primary_joints: vec!["hip", "shoulder"],
+            target_angles: HashMap::from([
+                ("hip", (70.0, 120.0)),
+                ("shoulder", (160.0, 180.0)), // Should maintain near-full extension
+            ]),
+            common_errors: vec!["Swinging body", "Partial range of motion"],
+            engagement_factor: 1.5,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Russian Twist
+        abs_exercises.insert("russian-roulette", AbExerciseProfile { // Note: Using your ID "russian-roulette"
+            primary_joints: vec!["torso_rotation", "hip"],
+            target_angles: HashMap::from([
+                ("torso_rotation", (45.0, 60.0)), // Rotation angle
+                ("hip", (100.0, 130.0)), // Hip flexion
+            ]),
+            common_errors: vec!["Rotating from arms only", "Rounding shoulders"],
+            engagement_factor: 1.3,
+            symmetry_tolerance: None,
+        });
+
+        // 4. Ab Wheel Rollout
+        abs_exercises.insert("ab-wheel-rollout", AbExerciseProfile {
+            primary_joints: vec!["shoulder", "hip"],
+            target_angles: HashMap::from([
+                ("shoulder", (150.0, 180.0)),
+                ("hip", (170.0, 180.0)), // Near full extension at peak
+            ]),
+            common_errors: vec!["Dropping hips", "Overextending lower back"],
+            engagement_factor: 1.4,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Reverse Crunch
+        abs_exercises.insert("reverse-crunch", AbExerciseProfile {
+            primary_joints: vec!["hip", "knee"],
+            target_angles: HashMap::from([
+                ("hip", (90.0, 120.0)),
+                ("knee", (90.0, 120.0)),
+            ]),
+            common_errors: vec!["Using hip flexors only", "Neck strain"],
+            engagement_factor: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 6. Scissor Kicks
+        abs_exercises.insert("scissor-kicks", AbExerciseProfile {
+            primary_joints: vec!["hip", "leg_angle"],
+            target_angles: HashMap::from([
+                ("hip", (150.0, 180.0)), // Near-flat on ground
+                ("leg_angle", (30.0, 60.0)), // Scissor angle
+            ]),
+            common_errors: vec!["Arching back", "Moving too quickly"],
+            engagement_factor: 1.0,
+            symmetry_tolerance: None,
+        });
+
+        // 7. Plank Hip Dips
+        abs_exercises.insert("plank-hip-dips", AbExerciseProfile {
+            primary_joints: vec!["shoulder", "hip_lateral"],
+            target_angles: HashMap::from([
+                ("shoulder", (170.0, 180.0)), // Stable shoulders
+                ("hip_lateral", (10.0, 20.0)), // Lateral flexion range
+            ]),
+            common_errors: vec!["Sagging hips", "Over-rotating"],
+            engagement_factor: 1.2,
+            symmetry_tolerance: None,
+        });
+
+        // 8. Back Arch (for core stability)
+        abs_exercises.insert("back_arch", AbExerciseProfile {
+            primary_joints: vec!["spine_extension", "shoulder"],
+            target_angles: HashMap::from([
+                ("spine_extension", (15.0, 30.0)), // Controlled extension
+                ("shoulder", (160.0, 180.0)),
+            ]),
+            common_errors: vec!["Over-arching", "Neck strain"],
+            engagement_factor: 0.9,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Lateral Leg Raises
+        abs_exercises.insert("lateral-leg-raises", AbExerciseProfile {
+            primary_joints: vec!["hip_abduction", "torso_lateral"],
+            target_angles: HashMap::from([
+                ("hip_abduction", (30.0, 60.0)),
+                ("torso_lateral", (0.0, 10.0)), // Minimal torso lean
+            ]),
+            common_errors: vec!["Using momentum", "Leaning torso"],
+            engagement_factor: 1.1,
+            symmetry_tolerance: None,
+        });
+
+        // 10. Dumbbell Leg Raises
+        abs_exercises.insert("dumbbell-leg-raises", AbExerciseProfile {
+            primary_joints: vec!["hip", "shoulder"],
+            target_angles: HashMap::from([
+                ("hip", (60.0, 90.0)),
+                ("shoulder", (150.0, 180.0)), // Stable shoulders
+            ]),
+            common_errors: vec!["Swinging weights", "Partial range"],
+            engagement_factor: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Lateral Sit-ups
+        abs_exercises.insert("lateral-sit-ups", AbExerciseProfile {
+            primary_joints: vec!["torso_lateral", "hip"],
+            target_angles: HashMap::from([
+                ("torso_lateral", (20.0, 45.0)), // Side bend at the top
+                ("hip", (60.0, 100.0)),
+            ]),
+            common_errors: vec!["Pulling on the neck", "Rotating instead of bending"],
+            engagement_factor: 1.1,
+            symmetry_tolerance: None,
+        });
+
+        // 12. Sit-up
+        abs_exercises.insert("situp", AbExerciseProfile {
+            primary_joints: vec!["hip", "torso_lateral"],
+            target_angles: HashMap::from([
+                ("hip", (60.0, 120.0)),
+                ("torso_lateral", (0.0, 10.0)), // Stay square
+            ]),
+            common_errors: vec!["Pulling on the neck", "Feet lifting"],
+            engagement_factor: 1.0,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 13. Plank
+        abs_exercises.insert("plank", AbExerciseProfile {
+            primary_joints: vec!["hip", "shoulder"],
+            target_angles: HashMap::from([
+                ("hip", (165.0, 180.0)),     // Straight line shoulder to heel
+                ("shoulder", (80.0, 100.0)), // Elbows under shoulders
+            ]),
+            common_errors: vec!["Sagging hips", "Piking hips"],
+            engagement_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 14. Russian Twist
+        abs_exercises.insert("russian-twist", AbExerciseProfile {
+            primary_joints: vec!["torso_rotation", "hip"],
+            target_angles: HashMap::from([
+                ("torso_rotation", (45.0, 60.0)),
+                ("hip", (100.0, 130.0)),
+            ]),
+            common_errors: vec!["Rotating from arms only", "Rounding shoulders"],
+            engagement_factor: 1.3,
+            symmetry_tolerance: None,
+        });
+
+        // 15. Bicycle Crunches
+        abs_exercises.insert("bicycle-crunches", AbExerciseProfile {
+            primary_joints: vec!["hip", "torso_rotation"],
+            target_angles: HashMap::from([
+                ("hip", (60.0, 110.0)),
+                ("torso_rotation", (30.0, 60.0)), // Elbow to opposite knee
+            ]),
+            common_errors: vec!["Pulling on the neck", "Moving too quickly"],
+            engagement_factor: 1.2,
+            symmetry_tolerance: None,
+        });
+
+        // 16. Toe Touches
+        abs_exercises.insert("toe-touches", AbExerciseProfile {
+            primary_joints: vec!["hip", "shoulder"],
+            target_angles: HashMap::from([
+                ("hip", (60.0, 100.0)),
+                ("shoulder", (150.0, 180.0)), // Arms reaching up
+            ]),
+            common_errors: vec!["Legs dropping", "Using momentum"],
+            engagement_factor: 1.1,
+            symmetry_tolerance: None,
+        });
+
+        AbsAnalyzer { abs_exercises }
+    }
+
+    pub fn calculate_abs_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_abs_side_angles(skeleton, side))
+    }
+
+    fn calculate_abs_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Core angle calculations (using 17-keypoint COCO model format)
+        // Hip angle (nose - hip - knee)
+        insert_angle(&mut angles, "hip", calculate_angle(
+            &skeleton.point(Landmark::Nose),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        // Shoulder stability (hip - shoulder - elbow)
+        insert_angle(&mut angles, "shoulder", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Torso rotation (left shoulder - nose - right shoulder)
+        insert_angle(&mut angles, "torso_rotation", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::Nose),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Lateral flexion (left hip - nose - right hip)
+        insert_angle(&mut angles, "torso_lateral", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::Nose),
+            &skeleton.point(Landmark::RightHip.on_side(side))
+        ));
+        
+        // Hip abduction (for lateral movements)
+        insert_angle(&mut angles, "hip_abduction", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::RightHip.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_abs_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.abs_exercises.get(exercise_id) {
+            // Check target angles at the end of the rep each one describes
+            for (joint, &(min, max)) in &profile.target_angles {
+                if let Some(angle) = angles.get(*joint).filter(|_| at_range_end((min, max), position)) {
+                    if *angle < min {
+                        errors.push(FormIssue::new(format!("{}_too_small", joint), Severity::Warning, format!("{} angle too small ({}° < {}°)", joint, angle.round(), min))
+                            .measured(joint, *angle)
+                            .target(min, max));
+                    } else if *angle > max {
+                        errors.push(FormIssue::new(format!("{}_too_large", joint), Severity::Warning, format!("{} angle too large ({}° > {}°)", joint, angle.round(), max))
+                            .measured(joint, *angle)
+                            .target(min, max));
+                    }
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_abs_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        let base_engagement = if let Some(profile) = self.abs_exercises.get(exercise_id) {
+            // Calculate based on primary joints
+            let mut total = 0.0;
+            let mut visible_joints = 0;
+            for joint in &profile.primary_joints {
+                if let Some(angle) = angles.get(*joint) {
+                    // Normalize angle to 0-1 range based on target
+                    if let Some((min, max)) = profile.target_angles.get(*joint) {
+                        let normalized = (angle - min) / (max - min);
+                        total += normalized.clamp(0.0, 1.0);
+                        visible_joints += 1;
+                    }
+                }
+            }
+            // Joints out of view are left out of the average rather than counted as zero
+            if visible_joints > 0 {
+                (total / visible_joints as f32) * profile.engagement_factor
+            } else {
+                0.5
+            }
+        } else {
+            0.5 // Default for unknown exercises
+        };
+        
+        base_engagement.clamp(0.1, 1.0)
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("hanging-leg-raises", "bent_arms", r#"shoulder < 160 => "Maintain straight arm position""#),
+    ("plank-hip-dips", "excessive_hip_dip", r#"hip_lateral > 25 => "Control lateral movement - reduce range""#),
+];
+
+impl MuscleGroupAnalyzer for AbsAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "abs"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.abs_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_abs_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_abs_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_abs_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.abs_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+
+    fn hold_ranges(&self, exercise_id: &str) -> Vec<HoldRange> {
+        let mut ranges: Vec<HoldRange> = self
+            .abs_exercises
+            .get(exercise_id)
+            .map_or_else(Vec::new, |profile| {
+                profile.target_angles.iter().map(|(joint, &range)| HoldRange::new(joint, range)).collect()
+            });
+        ranges.sort_by(|a, b| a.angle.cmp(&b.angle));
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn a_dead_hang_is_not_a_missed_leg_raise() {
+        let analyzer = AbsAnalyzer::new();
+        let hang = angle_map(&[("hip", 178.0), ("shoulder", 172.0)]);
+        assert!(analyzer.check_abs_form("hanging-leg-raises", &hang, at_rom(1.0)).is_empty());
+
+        let raised = angle_map(&[("hip", 95.0), ("shoulder", 170.0)]);
+        assert!(analyzer.check_abs_form("hanging-leg-raises", &raised, at_rom(0.0)).is_empty());
+
+        let issues = analyzer.check_abs_form("hanging-leg-raises", &hang, at_rom(0.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "hip_too_large");
+    }
+}
@@ -0,0 +1,350 @@
+// wasm/src/pose_detection/back.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, insert_angle, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct BackAnalyzer {
+    back_exercises: HashMap<&'static str, BackExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct BackExerciseProfile {
+    #[allow(dead_code)] // Catalog data kept with the profile
+    primary_muscles: Vec<&'static str>,
+    joint_ranges: HashMap<&'static str, (f32, f32)>,
+    strictness_factor: f32,
+    #[allow(dead_code)]
+    common_mistakes: Vec<&'static str>,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+impl BackAnalyzer {
+    pub fn new() -> Self {
+        let mut back_exercises = HashMap::new();
+
+        // 1. Dumbbell Rows
+        back_exercises.insert("dumbbell-rows", BackExerciseProfile {
+            primary_muscles: vec!["lats", "rhomboids", "rear-delts"],
+            joint_ranges: HashMap::from([
+                ("shoulder_flexion", (30.0, 60.0)),
+                ("elbow", (75.0, 90.0)),
+                ("torso", (15.0, 30.0)), // Torso angle from horizontal
+            ]),
+            strictness_factor: 1.2,
+            common_mistakes: vec!["Using momentum", "Shrugging shoulders"],
+            symmetry_tolerance: None,
+        });
+
+        // 2. Barbell Rows
+        back_exercises.insert("barbell-rows", BackExerciseProfile {
+            primary_muscles: vec!["mid-traps", "lats", "erectors"],
+            joint_ranges: HashMap::from([
+                ("shoulder_extension", (45.0, 75.0)),
+                ("elbow", (90.0, 120.0)),
+                ("torso", (30.0, 45.0)),
+            ]),
+            strictness_factor: 1.3,
+            common_mistakes: vec!["Rounding lower back", "Partial range"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 3. Seated Dumbbell Rows
+        back_exercises.insert("seated-dumbbell-rows", BackExerciseProfile {
+            primary_muscles: vec!["lower-lats", "biceps"],
+            joint_ranges: HashMap::from([
+                ("shoulder_extension", (60.0, 90.0)),
+                ("elbow", (90.0, 120.0)),
+                ("torso", (0.0, 15.0)), // More upright
+            ]),
+            strictness_factor: 1.1,
+            common_mistakes: vec!["Overextending shoulders", "Using legs"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Chin-up/Pull-up
+        back_exercises.insert("chin-up-pull-ups", BackExerciseProfile {
+            primary_muscles: vec!["upper-lats", "biceps"],
+            joint_ranges: HashMap::from([
+                ("shoulder_flexion", (180.0, 210.0)), // Full range
+                ("elbow", (30.0, 180.0)), // Wide range
+            ]),
+            strictness_factor: 1.4,
+            common_mistakes: vec!["Partial reps", "Kipping"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 5. Deadlifts
+        back_exercises.insert("deadlifts", BackExerciseProfile {
+            primary_muscles: vec!["erectors", "glutes", "hamstrings"],
+            joint_ranges: HashMap::from([
+                ("hip", (60.0, 90.0)), // Starting position
+                ("knee", (90.0, 120.0)),
+                ("torso", (45.0, 60.0)),
+            ]),
+            strictness_factor: 1.5,
+            common_mistakes: vec!["Rounded back", "Hips rising first"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 6. Pull-ups
+        back_exercises.insert("pull-ups", BackExerciseProfile {
+            primary_muscles: vec!["lats", "teres-major"],
+            joint_ranges: HashMap::from([
+                ("shoulder_adduction", (170.0, 220.0)),
+                ("elbow", (30.0, 180.0)),
+            ]),
+            strictness_factor: 1.4,
+            common_mistakes: vec!["Incomplete extension", "Elbow flaring"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 7. Face Pulls
+        back_exercises.insert("face-pulls", BackExerciseProfile {
+            primary_muscles: vec!["rear-delts", "rotator-cuff"],
+            joint_ranges: HashMap::from([
+                ("shoulder_horizontal", (90.0, 120.0)),
+                ("elbow", (90.0, 120.0)),
+            ]),
+            strictness_factor: 1.0,
+            common_mistakes: vec!["Using too much weight", "Shrugging"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 8. T-bar Rows
+        back_exercises.insert("t-bar-rows", BackExerciseProfile {
+            primary_muscles: vec!["mid-back", "lats"],
+            joint_ranges: HashMap::from([
+                ("shoulder_extension", (60.0, 90.0)),
+                ("torso_rotation", (0.0, 15.0)), // Minimal rotation
+            ]),
+            strictness_factor: 1.2,
+            common_mistakes: vec!["Twisting torso", "Partial contraction"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 9. Open Butterfly (Rear Delt Fly)
+        back_exercises.insert("open-butterfly", BackExerciseProfile {
+            primary_muscles: vec!["rear-delts", "traps"],
+            joint_ranges: HashMap::from([
+                ("shoulder_horizontal", (120.0, 150.0)),
+                ("elbow", (10.0, 30.0)), // Slight bend
+            ]),
+            strictness_factor: 0.9,
+            common_mistakes: vec!["Using arms instead of back", "Overextending"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 10. Lateral Russian Roulette
+        back_exercises.insert("lateral-russian-roulette", BackExerciseProfile {
+            primary_muscles: vec!["obliques", "erectors"],
+            joint_ranges: HashMap::from([
+                ("torso_rotation", (45.0, 60.0)),
+                ("hip", (90.0, 120.0)),
+            ]),
+            strictness_factor: 1.1,
+            common_mistakes: vec!["Using arms only", "Over-rotating"],
+            symmetry_tolerance: None,
+        });
+
+        // 11. Lat Pulldown
+        back_exercises.insert("lat-pulldown", BackExerciseProfile {
+            primary_muscles: vec!["lats", "teres-major"],
+            joint_ranges: HashMap::from([
+                ("shoulder_flexion", (90.0, 170.0)),
+                ("elbow", (60.0, 160.0)),
+                ("torso", (0.0, 20.0)), // Slight lean back only
+            ]),
+            strictness_factor: 1.2,
+            common_mistakes: vec!["Leaning back too far", "Pulling behind the neck"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 12. Reverse Fly
+        back_exercises.insert("reverse-fly", BackExerciseProfile {
+            primary_muscles: vec!["rear-delts", "rhomboids"],
+            joint_ranges: HashMap::from([
+                ("shoulder_horizontal", (120.0, 150.0)),
+                ("elbow", (150.0, 175.0)), // Soft bend
+            ]),
+            strictness_factor: 1.0,
+            common_mistakes: vec!["Using momentum", "Shrugging"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 13. Hyperextensions
+        back_exercises.insert("hyperextensions", BackExerciseProfile {
+            primary_muscles: vec!["erectors", "glutes"],
+            joint_ranges: HashMap::from([
+                ("hip", (150.0, 190.0)),
+                ("torso", (0.0, 30.0)),
+            ]),
+            strictness_factor: 1.1,
+            common_mistakes: vec!["Hyperextending lower back", "Using momentum"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        BackAnalyzer { back_exercises }
+    }
+
+    pub fn calculate_back_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_back_side_angles(skeleton, side))
+    }
+
+    fn calculate_back_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Shoulder angles
+        insert_angle(&mut angles, "shoulder_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        if let Some(&shoulder_flexion) = angles.get("shoulder_flexion") {
+            angles.insert("shoulder_extension".to_string(), 180.0 - shoulder_flexion);
+        }
+        
+        insert_angle(&mut angles, "shoulder_horizontal", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Elbow angle
+        insert_angle(&mut angles, "elbow", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Torso angles
+        insert_angle(&mut angles, "torso", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::Nose),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        insert_angle(&mut angles, "torso_rotation", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::Nose),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Hip angle
+        insert_angle(&mut angles, "hip", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_back_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.back_exercises.get(exercise_id) {
+            // Check joint ranges at the end of the rep each one describes
+            for (joint, &(min, max)) in &profile.joint_ranges {
+                if let Some(angle) = angles.get(*joint).filter(|_| at_range_end((min, max), position)) {
+                    if *angle < min {
+                        errors.push(FormIssue::new(format!("{}_too_closed", joint), Severity::Warning, format!("{} too closed ({}° < {}°)", joint, angle.round(), min))
+                            .measured(joint, *angle)
+                            .target(min, max));
+                    } else if *angle > max {
+                        errors.push(FormIssue::new(format!("{}_too_open", joint), Severity::Warning, format!("{} too open ({}° > {}°)", joint, angle.round(), max))
+                            .measured(joint, *angle)
+                            .target(min, max));
+                    }
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_back_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.back_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut valid_angles = 0;
+            
+            for (joint, &(min, max)) in &profile.joint_ranges {
+                if let Some(angle) = angles.get(*joint) {
+                    let normalized = (angle - min) / (max - min);
+                    score += normalized.clamp(0.0, 1.0);
+                    valid_angles += 1;
+                }
+            }
+            
+            if valid_angles > 0 {
+                (score / valid_angles as f32) * profile.strictness_factor
+            } else {
+                0.5
+            }
+        } else {
+            0.5
+        }.clamp(0.1, 1.0)
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("deadlifts", "hip_knee_out_of_sync", r#"abs(hip - knee) > 30 => critical "Hip-knee synchronization off""#),
+    ("pull-ups", "incomplete_shoulder_extension", r#"shoulder_adduction < 170 during phase=top => "Incomplete shoulder extension""#),
+    ("face-pulls", "elbow_too_wide", r#"elbow > 120 => info "Elbow angle too wide - focus on rear delts""#),
+];
+
+impl MuscleGroupAnalyzer for BackAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "back"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.back_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_back_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_back_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_back_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.back_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn each_joint_range_is_checked_at_its_own_end() {
+        let analyzer = BackAnalyzer::new();
+        let hang = angle_map(&[("shoulder_adduction", 178.0), ("elbow", 175.0)]);
+        assert!(analyzer.check_back_form("pull-ups", &hang, at_rom(1.0)).is_empty());
+        let chin_over_bar = angle_map(&[("shoulder_adduction", 120.0), ("elbow", 50.0)]);
+        assert!(analyzer.check_back_form("pull-ups", &chin_over_bar, at_rom(0.0)).is_empty());
+
+        let standing = angle_map(&[("hip", 178.0), ("knee", 178.0), ("torso", 170.0)]);
+        assert!(analyzer.check_back_form("deadlifts", &standing, at_rom(1.0)).is_empty());
+        let issues = analyzer.check_back_form("deadlifts", &standing, at_rom(0.0));
+        assert!(issues.iter().any(|issue| issue.code == "hip_too_open"), "{:?}", issues);
+    }
+}
@@ -0,0 +1,344 @@
+// wasm/src/pose_detection/biceps.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, calculate_distance, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct BicepsAnalyzer {
+    biceps_exercises: HashMap<&'static str, BicepsExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct BicepsExerciseProfile {
+    curl_angle_range: (f32, f32),
+    shoulder_stabilization: f32, // 0-1 how much shoulder should stay fixed
+    elbow_travel: (f32, f32),   // Expected elbow movement range
+    strictness: f32,
+    #[allow(dead_code)] // Catalog only, not surfaced yet
+    common_faults: Vec<&'static str>,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+impl BicepsAnalyzer {
+    pub fn new() -> Self {
+        let mut biceps_exercises = HashMap::new();
+
+        // 1. Isolated Dumbbell Curls
+        biceps_exercises.insert("isolated-dumbbell-curls", BicepsExerciseProfile {
+            curl_angle_range: (30.0, 160.0),
+            shoulder_stabilization: 0.9,
+            elbow_travel: (5.0, 15.0), // Minimal elbow movement
+            strictness: 1.3,
+            common_faults: vec!["Shoulder involvement", "Body English"],
+            symmetry_tolerance: None,
+        });
+
+        // 2. Barbell Curls
+        biceps_exercises.insert("barbell-curls", BicepsExerciseProfile {
+            curl_angle_range: (45.0, 150.0),
+            shoulder_stabilization: 0.7,
+            elbow_travel: (10.0, 30.0),
+            strictness: 1.2,
+            common_faults: vec!["Elbow drift", "Wrist flexion"],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 3. Dumbbell Curls
+        biceps_exercises.insert("dumbbell-curls", BicepsExerciseProfile {
+            curl_angle_range: (30.0, 160.0),
+            shoulder_stabilization: 0.6,
+            elbow_travel: (15.0, 40.0),
+            strictness: 1.1,
+            common_faults: vec!["Alternating unevenly", "Momentum use"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Open-Grip Pull-ups
+        biceps_exercises.insert("open-grip-pull-ups", BicepsExerciseProfile {
+            curl_angle_range: (60.0, 180.0),
+            shoulder_stabilization: 0.3,
+            elbow_travel: (50.0, 100.0),
+            strictness: 1.4,
+            common_faults: vec!["Partial range", "Kipping"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Lateral Push-ups
+        biceps_exercises.insert("lateral-push-ups", BicepsExerciseProfile {
+            curl_angle_range: (90.0, 150.0),
+            shoulder_stabilization: 0.8,
+            elbow_travel: (20.0, 60.0),
+            strictness: 1.5,
+            common_faults: vec!["Elbow flare", "Shoulder roll"],
+            symmetry_tolerance: None,
+        });
+
+        // 6. Half-Rep Curls
+        biceps_exercises.insert("half-rep-curls", BicepsExerciseProfile {
+            curl_angle_range: (90.0, 120.0),
+            shoulder_stabilization: 0.95,
+            elbow_travel: (2.0, 10.0),
+            strictness: 1.6,
+            common_faults: vec!["Breaking form", "Overloading"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Resistance Band Pulls
+        biceps_exercises.insert("resistance-bands-pull", BicepsExerciseProfile {
+            curl_angle_range: (45.0, 135.0),
+            shoulder_stabilization: 0.5,
+            elbow_travel: (30.0, 60.0),
+            strictness: 1.0,
+            common_faults: vec!["Inconsistent tension", "Body lean"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 8. Outward Dumbbell Curls
+        biceps_exercises.insert("outward-dumbbell-curls", BicepsExerciseProfile {
+            curl_angle_range: (40.0, 140.0),
+            shoulder_stabilization: 0.7,
+            elbow_travel: (10.0, 25.0),
+            strictness: 1.3,
+            common_faults: vec!["Wrist rotation", "Shoulder elevation"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Concentration Curls
+        biceps_exercises.insert("concentration-curls", BicepsExerciseProfile {
+            curl_angle_range: (30.0, 150.0),
+            shoulder_stabilization: 1.0,
+            elbow_travel: (0.0, 5.0), // Elbow should stay fixed
+            strictness: 1.7,
+            common_faults: vec!["Elbow movement", "Body sway"],
+            symmetry_tolerance: None,
+        });
+
+        // 10. Zottman Curls
+        biceps_exercises.insert("zottman-curls", BicepsExerciseProfile {
+            curl_angle_range: (45.0, 135.0),
+            shoulder_stabilization: 0.8,
+            elbow_travel: (5.0, 15.0),
+            strictness: 1.4,
+            common_faults: vec!["Grip inconsistency", "Tempo variation"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Hammer Curls
+        biceps_exercises.insert("hammer-curls", BicepsExerciseProfile {
+            curl_angle_range: (30.0, 160.0),
+            shoulder_stabilization: 0.7,
+            elbow_travel: (10.0, 25.0),
+            strictness: 1.2,
+            common_faults: vec!["Elbow drift", "Swinging"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 12. Preacher Curls
+        biceps_exercises.insert("preacher-curls", BicepsExerciseProfile {
+            curl_angle_range: (40.0, 150.0),
+            shoulder_stabilization: 1.0,
+            elbow_travel: (0.0, 5.0), // Elbows pinned to the pad
+            strictness: 1.5,
+            common_faults: vec!["Lifting elbows off pad", "Partial range"],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        BicepsAnalyzer { biceps_exercises }
+    }
+
+    pub fn calculate_biceps_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_biceps_side_angles(skeleton, side))
+    }
+
+    fn calculate_biceps_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Elbow angle (shoulder-elbow-wrist)
+        insert_angle(&mut angles, "elbow_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Shoulder stabilization (hip-shoulder-elbow)
+        insert_angle(&mut angles, "shoulder_stability", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Elbow travel (distance from starting position)
+        insert_angle(&mut angles, "elbow_travel", calculate_distance(
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side))
+        ));
+        
+        // Wrist angle for Zottman curls
+        insert_angle(&mut angles, "wrist_pronation", calculate_angle(
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side)),
+            &skeleton.point(Landmark::LeftIndex.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_biceps_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.biceps_exercises.get(exercise_id) {
+            // Check curl range
+            if let Some(curl_angle) = angles.get("elbow_flexion").filter(|_| at_range_end(profile.curl_angle_range, position)) {
+                if *curl_angle < profile.curl_angle_range.0 {
+                    errors.push(FormIssue::new("incomplete_extension", Severity::Warning, format!("Incomplete extension ({}° < {}°)", 
+                        curl_angle.round(), profile.curl_angle_range.0))
+                        .measured("elbow_flexion", *curl_angle)
+                        .target(profile.curl_angle_range.0, profile.curl_angle_range.1));
+                }
+                if *curl_angle > profile.curl_angle_range.1 {
+                    errors.push(FormIssue::new("over_flexion", Severity::Warning, format!("Over-flexion ({}° > {}°)", 
+                        curl_angle.round(), profile.curl_angle_range.1))
+                        .measured("elbow_flexion", *curl_angle)
+                        .target(profile.curl_angle_range.0, profile.curl_angle_range.1));
+                }
+            }
+            
+            // Check shoulder stability
+            if let Some(shoulder_angle) = angles.get("shoulder_stability") {
+                let tolerance = (1.0 - profile.shoulder_stabilization) * 30.0;
+                if (shoulder_angle - 180.0).abs() > tolerance {
+                    errors.push(FormIssue::new("shoulder_swing", Severity::Warning, "Excessive shoulder movement")
+                        .measured("shoulder_stability", *shoulder_angle)
+                        .target(180.0 - tolerance, 180.0 + tolerance));
+                }
+            }
+            
+            // Check elbow travel
+            if let Some(travel) = angles.get("elbow_travel") {
+                if *travel < profile.elbow_travel.0 {
+                    errors.push(FormIssue::new("insufficient_elbow_travel", Severity::Warning, "Insufficient range of motion")
+                        .measured("elbow_travel", *travel)
+                        .target(profile.elbow_travel.0, profile.elbow_travel.1));
+                }
+                if *travel > profile.elbow_travel.1 {
+                    errors.push(FormIssue::new("elbow_drift", Severity::Warning, "Excessive elbow drift")
+                        .measured("elbow_travel", *travel)
+                        .target(profile.elbow_travel.0, profile.elbow_travel.1));
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_biceps_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.biceps_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Curl range component (40% weight)
+            if let Some(curl) = angles.get("elbow_flexion") {
+                let curl_norm = (*curl - profile.curl_angle_range.0) / 
+                               (profile.curl_angle_range.1 - profile.curl_angle_range.0);
+                score += 0.4 * curl_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.4;
+            }
+            
+            // Shoulder stability component (30% weight)
+            if let Some(shoulder) = angles.get("shoulder_stability") {
+                let shoulder_dev = 1.0 - ((shoulder - 180.0).abs() / 30.0).clamp(0.0, 1.0);
+                score += 0.3 * shoulder_dev * profile.shoulder_stabilization;
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Elbow travel component (20% weight)
+            if let Some(travel) = angles.get("elbow_travel") {
+                let travel_norm = 1.0 - ((*travel - profile.elbow_travel.0) / 
+                                        (profile.elbow_travel.1 - profile.elbow_travel.0)).clamp(0.0, 1.0);
+                score += 0.2 * travel_norm;
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            // Exercise-specific components (10% weight)
+            score += match exercise_id {
+                "zottman-curls" => {
+                    angles.get("wrist_pronation")
+                        .map_or_else(|| { hidden_weight += 0.1; 0.0 }, |p| 0.1 * (p.abs() / 90.0).clamp(0.0, 1.0))
+                },
+                "concentration-curls" => {
+                    angles.get("elbow_travel")
+                        .map_or_else(|| { hidden_weight += 0.1; 0.0 }, |t| 0.1 * (1.0 - (t / 5.0).clamp(0.0, 1.0)))
+                },
+                _ => 0.1
+            };
+            
+            (visible_score(score, hidden_weight) * profile.strictness).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("zottman-curls", "incomplete_wrist_rotation", r#"abs(wrist_pronation) < 45 => info "Incomplete wrist rotation""#),
+    ("concentration-curls", "elbow_not_fixed", r#"elbow_travel > 5 => "Elbow should remain fixed""#),
+];
+
+impl MuscleGroupAnalyzer for BicepsAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "biceps"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.biceps_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_biceps_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_biceps_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_biceps_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.biceps_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn hanging_arms_are_not_an_unfinished_curl() {
+        let analyzer = BicepsAnalyzer::new();
+        let hanging = angle_map(&[("elbow_flexion", 172.0), ("shoulder_stability", 180.0), ("elbow_travel", 20.0)]);
+        assert!(analyzer.check_biceps_form("barbell-curls", &hanging, at_rom(1.0)).is_empty());
+
+        let curled = angle_map(&[("elbow_flexion", 60.0), ("shoulder_stability", 178.0), ("elbow_travel", 20.0)]);
+        assert!(analyzer.check_biceps_form("barbell-curls", &curled, at_rom(0.0)).is_empty());
+
+        let crushed = angle_map(&[("elbow_flexion", 35.0), ("shoulder_stability", 178.0), ("elbow_travel", 20.0)]);
+        let issues = analyzer.check_biceps_form("barbell-curls", &crushed, at_rom(0.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "incomplete_extension");
+    }
+}
@@ -0,0 +1,365 @@
+// wasm/src/pose_detection/calves.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::hold::HoldRange;
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct CalfAnalyzer {
+    calf_exercises: HashMap<&'static str, CalfExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct CalfExerciseProfile {
+    plantar_flexion_range: (f32, f32), // Expected ankle angle range
+    knee_angle_range: (f32, f32),      // Knee position during exercise
+    stability_threshold: f32,          // How much hip/knee movement allowed
+    emphasis: CalfEmphasis,            // Which head of calf is emphasized
+    strictness: f32,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum CalfEmphasis {
+    Gastrocnemius,
+    Soleus,
+    Both,
+}
+
+impl CalfAnalyzer {
+    pub fn new() -> Self {
+        let mut calf_exercises = HashMap::new();
+
+        // 1. Bench Calf Raises
+        calf_exercises.insert("bench-calf-raises", CalfExerciseProfile {
+            plantar_flexion_range: (90.0, 150.0),
+            knee_angle_range: (175.0, 180.0), // Nearly straight
+            stability_threshold: 0.9,
+            emphasis: CalfEmphasis::Gastrocnemius,
+            strictness: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Plate Raises
+        calf_exercises.insert("plate-raises", CalfExerciseProfile {
+            plantar_flexion_range: (100.0, 160.0),
+            knee_angle_range: (160.0, 180.0),
+            stability_threshold: 0.8,
+            emphasis: CalfEmphasis::Both,
+            strictness: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Bulgarian Raises
+        calf_exercises.insert("bulgarian-raises", CalfExerciseProfile {
+            plantar_flexion_range: (80.0, 140.0),
+            knee_angle_range: (120.0, 150.0), // Bent knee position
+            stability_threshold: 0.7,
+            emphasis: CalfEmphasis::Soleus,
+            strictness: 1.4,
+            symmetry_tolerance: None,
+        });
+
+        // 4. Barbell Raises
+        calf_exercises.insert("barbell-raises", CalfExerciseProfile {
+            plantar_flexion_range: (95.0, 155.0),
+            knee_angle_range: (170.0, 180.0),
+            stability_threshold: 0.85,
+            emphasis: CalfEmphasis::Gastrocnemius,
+            strictness: 1.3,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 5. Jump Rope
+        calf_exercises.insert("jump-rope", CalfExerciseProfile {
+            plantar_flexion_range: (120.0, 170.0),
+            knee_angle_range: (150.0, 180.0),
+            stability_threshold: 0.6,
+            emphasis: CalfEmphasis::Both,
+            strictness: 0.9,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 6. Donkey Calf Raises
+        calf_exercises.insert("donkey-calf-raises", CalfExerciseProfile {
+            plantar_flexion_range: (85.0, 145.0),
+            knee_angle_range: (175.0, 180.0),
+            stability_threshold: 0.95,
+            emphasis: CalfEmphasis::Gastrocnemius,
+            strictness: 1.5,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Seated Calf Raises
+        calf_exercises.insert("seated-calf-raises", CalfExerciseProfile {
+            plantar_flexion_range: (70.0, 130.0),
+            knee_angle_range: (90.0, 100.0), // Fixed bent position
+            stability_threshold: 1.0,
+            emphasis: CalfEmphasis::Soleus,
+            strictness: 1.6,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 8. Stair Calf Raises
+        calf_exercises.insert("stair-calf-raises", CalfExerciseProfile {
+            plantar_flexion_range: (60.0, 150.0), // Extra range
+            knee_angle_range: (175.0, 180.0),
+            stability_threshold: 0.75,
+            emphasis: CalfEmphasis::Both,
+            strictness: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Farmer Walk on Toes
+        calf_exercises.insert("farmer-walk-on-toes", CalfExerciseProfile {
+            plantar_flexion_range: (140.0, 170.0), // Maintained contraction
+            knee_angle_range: (175.0, 180.0),
+            stability_threshold: 0.5,
+            emphasis: CalfEmphasis::Gastrocnemius,
+            strictness: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 10. Pogo Jumps
+        calf_exercises.insert("pogo-jumps", CalfExerciseProfile {
+            plantar_flexion_range: (130.0, 180.0), // Explosive movement
+            knee_angle_range: (160.0, 180.0),
+            stability_threshold: 0.4,
+            emphasis: CalfEmphasis::Both,
+            strictness: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Standing Calf Raises
+        calf_exercises.insert("standing-calf-raises", CalfExerciseProfile {
+            plantar_flexion_range: (90.0, 150.0),
+            knee_angle_range: (170.0, 180.0),
+            stability_threshold: 0.85,
+            emphasis: CalfEmphasis::Gastrocnemius,
+            strictness: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        CalfAnalyzer { calf_exercises }
+    }
+
+    pub fn calculate_calf_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_calf_side_angles(skeleton, side))
+    }
+
+    fn calculate_calf_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Ankle angle (knee-ankle-foot)
+        insert_angle(&mut angles, "plantar_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side)),
+            &skeleton.point(Landmark::LeftFootIndex.on_side(side))
+        ));
+        
+        // Knee angle (hip-knee-ankle)
+        insert_angle(&mut angles, "knee_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+        
+        // Stability metric (shoulder-hip-ankle)
+        insert_angle(&mut angles, "body_stability", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+        
+        // Range of motion tracking, needs both joints in view
+        if let (Some(plantar_flexion), Some(knee_flexion)) = (angles.get("plantar_flexion"), angles.get("knee_flexion")) {
+            let rom_achieved = plantar_flexion - knee_flexion;
+            angles.insert("rom_achieved".to_string(), rom_achieved);
+        }
+        
+        angles
+    }
+
+    pub fn check_calf_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.calf_exercises.get(exercise_id) {
+            // Check plantar flexion range
+            if let Some(pf) = angles.get("plantar_flexion").filter(|_| at_range_end(profile.plantar_flexion_range, position)) {
+                if *pf < profile.plantar_flexion_range.0 {
+                    errors.push(FormIssue::new("insufficient_plantar_flexion", Severity::Warning, format!(
+                        "Insufficient plantar flexion ({}° < {}°)",
+                        pf.round(), profile.plantar_flexion_range.0
+                    ))
+                        .measured("plantar_flexion", *pf)
+                        .target(profile.plantar_flexion_range.0, profile.plantar_flexion_range.1));
+                }
+                if *pf > profile.plantar_flexion_range.1 {
+                    errors.push(FormIssue::new("ankle_over_extension", Severity::Warning, format!(
+                        "Over-extension ({}° > {}°)",
+                        pf.round(), profile.plantar_flexion_range.1
+                    ))
+                        .measured("plantar_flexion", *pf)
+                        .target(profile.plantar_flexion_range.0, profile.plantar_flexion_range.1));
+                }
+            }
+            
+            // Check knee position
+            if let Some(knee) = angles.get("knee_flexion") {
+                match profile.emphasis {
+                    CalfEmphasis::Gastrocnemius if *knee < 170.0 => {
+                        errors.push(FormIssue::new("knees_bent_for_gastrocnemius", Severity::Info, "Keep knees straighter for gastrocnemius emphasis")
+                            .measured("knee_flexion", *knee)
+                            .at_least(170.0));
+                    },
+                    CalfEmphasis::Soleus if *knee > 110.0 => {
+                        errors.push(FormIssue::new("knees_straight_for_soleus", Severity::Info, "Maintain proper knee bend for soleus emphasis")
+                            .measured("knee_flexion", *knee)
+                            .at_most(110.0));
+                    },
+                    _ => {}
+                }
+                
+                if *knee < profile.knee_angle_range.0 {
+                    errors.push(FormIssue::new("knees_too_bent", Severity::Warning, format!(
+                        "Knees too bent ({}° < {}°)",
+                        knee.round(), profile.knee_angle_range.0
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_angle_range.0, profile.knee_angle_range.1));
+                }
+                if *knee > profile.knee_angle_range.1 {
+                    errors.push(FormIssue::new("knees_too_straight", Severity::Warning, format!(
+                        "Knees too straight ({}° > {}°)",
+                        knee.round(), profile.knee_angle_range.1
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_angle_range.0, profile.knee_angle_range.1));
+                }
+            }
+            
+            // Check body stability
+            if let Some(stability) = angles.get("body_stability") {
+                let tolerance = (1.0 - profile.stability_threshold) * 30.0;
+                if (stability - 180.0).abs() > tolerance {
+                    errors.push(FormIssue::new("body_sway", Severity::Warning, "Excessive body movement")
+                        .measured("body_stability", *stability)
+                        .target(180.0 - tolerance, 180.0 + tolerance));
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_calf_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.calf_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Plantar flexion component (50% weight)
+            if let Some(pf) = angles.get("plantar_flexion") {
+                let pf_norm = (*pf - profile.plantar_flexion_range.0) / 
+                             (profile.plantar_flexion_range.1 - profile.plantar_flexion_range.0);
+                score += 0.5 * pf_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.5;
+            }
+            
+            // Knee position component (30% weight)
+            if let Some(knee) = angles.get("knee_flexion") {
+                let knee_dev = match profile.emphasis {
+                    CalfEmphasis::Gastrocnemius => 1.0 - (knee - 180.0).abs() / 30.0,
+                    CalfEmphasis::Soleus => 1.0 - (knee - 90.0).abs() / 30.0,
+                    CalfEmphasis::Both => 1.0 - (knee - 135.0).abs() / 45.0,
+                };
+                score += 0.3 * knee_dev.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Stability component (20% weight)
+            if let Some(stability) = angles.get("body_stability") {
+                let stab_dev = 1.0 - (stability - 180.0).abs() / 30.0;
+                score += 0.2 * stab_dev.clamp(0.0, 1.0) * profile.stability_threshold;
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            (visible_score(score, hidden_weight) * profile.strictness).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("pogo-jumps", "insufficient_explosive_range", r#"rom_achieved < 40 => "Insufficient explosive range""#),
+    ("jump-rope", "insufficient_explosive_range", r#"rom_achieved < 40 => "Insufficient explosive range""#),
+    ("seated-calf-raises", "inconsistent_knee_angle", r#"knee_flexion not in 90..100 => info "Maintain consistent knee angle""#),
+];
+
+impl MuscleGroupAnalyzer for CalfAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "calves"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.calf_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_calf_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_calf_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_calf_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.calf_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+
+    fn hold_ranges(&self, exercise_id: &str) -> Vec<HoldRange> {
+        self.calf_exercises.get(exercise_id).map_or_else(Vec::new, |profile| {
+            vec![
+                HoldRange::new("plantar_flexion", profile.plantar_flexion_range),
+                HoldRange::new("knee_flexion", profile.knee_angle_range),
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn flat_feet_and_the_raise_itself_pass() {
+        let analyzer = CalfAnalyzer::new();
+        let flat = angle_map(&[("plantar_flexion", 95.0), ("knee_flexion", 178.0), ("body_stability", 180.0)]);
+        assert!(analyzer.check_calf_form("standing-calf-raises", &flat, at_rom(0.0)).is_empty());
+
+        let raised = angle_map(&[("plantar_flexion", 165.0), ("knee_flexion", 178.0), ("body_stability", 179.0)]);
+        assert!(analyzer.check_calf_form("standing-calf-raises", &raised, at_rom(1.0)).is_empty());
+
+        let dropped = angle_map(&[("plantar_flexion", 80.0), ("knee_flexion", 178.0), ("body_stability", 180.0)]);
+        let issues = analyzer.check_calf_form("standing-calf-raises", &dropped, at_rom(0.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "insufficient_plantar_flexion");
+    }
+}
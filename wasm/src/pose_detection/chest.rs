@@ -0,0 +1,431 @@
+// wasm/src/pose_detection/chest.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, calculate_projected_angle, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct ChestAnalyzer {
+    chest_exercises: HashMap<&'static str, ChestExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct ChestExerciseProfile {
+    press_angle_range: (f32, f32),      // Shoulder flexion range
+    elbow_path: ElbowPath,              // Ideal elbow trajectory
+    scapular_behavior: ScapularSetting, // Proper scapular movement
+    depth_requirement: f32,             // 0-1 how deep the rep should be
+    stability_factor: f32,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum ElbowPath {
+    Flared(f32),  // Degrees from torso
+    Tucked(f32),  // Degrees from torso
+    Variable(f32, f32) // Min-max degrees
+}
+
+#[derive(Debug, Clone)]
+enum ScapularSetting {
+    Retracted,
+    Protracted,
+    Dynamic
+}
+
+impl ChestAnalyzer {
+    pub fn new() -> Self {
+        let mut chest_exercises = HashMap::new();
+
+        // 1. Inner Push-ups
+        chest_exercises.insert("inner-push-ups", ChestExerciseProfile {
+            press_angle_range: (70.0, 120.0),
+            elbow_path: ElbowPath::Tucked(30.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.9,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Superman Push-ups
+        chest_exercises.insert("superman-push-ups", ChestExerciseProfile {
+            press_angle_range: (100.0, 150.0),
+            elbow_path: ElbowPath::Flared(60.0),
+            scapular_behavior: ScapularSetting::Protracted,
+            depth_requirement: 0.8,
+            stability_factor: 1.5,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Butterfly (Pec Deck)
+        chest_exercises.insert("butterfly", ChestExerciseProfile {
+            press_angle_range: (120.0, 180.0),
+            elbow_path: ElbowPath::Flared(75.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.95,
+            stability_factor: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Dumbbell Overhead
+        chest_exercises.insert("dumbbell-overhead", ChestExerciseProfile {
+            press_angle_range: (150.0, 210.0),
+            elbow_path: ElbowPath::Variable(45.0, 75.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.85,
+            stability_factor: 1.4,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Military Press
+        chest_exercises.insert("military-press", ChestExerciseProfile {
+            press_angle_range: (160.0, 200.0),
+            elbow_path: ElbowPath::Tucked(45.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.8,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 6. Dumbbell Bench Press
+        chest_exercises.insert("bench-press-dumbbell", ChestExerciseProfile {
+            press_angle_range: (75.0, 135.0),
+            elbow_path: ElbowPath::Variable(45.0, 60.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.9,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Barbell Bench Press
+        chest_exercises.insert("bench-press-barbell", ChestExerciseProfile {
+            press_angle_range: (80.0, 140.0),
+            elbow_path: ElbowPath::Variable(50.0, 70.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.95,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 8. Bench Butterfly
+        chest_exercises.insert("bench-butterfly", ChestExerciseProfile {
+            press_angle_range: (100.0, 160.0),
+            elbow_path: ElbowPath::Flared(80.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.85,
+            stability_factor: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Dumbbell Rows (for chest crossover)
+        chest_exercises.insert("dumbbell-rows", ChestExerciseProfile {
+            press_angle_range: (60.0, 120.0),
+            elbow_path: ElbowPath::Variable(30.0, 60.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.7,
+            stability_factor: 1.0,
+            symmetry_tolerance: None,
+        });
+
+        // 10. Open Butterfly
+        chest_exercises.insert("open-butterfly", ChestExerciseProfile {
+            press_angle_range: (90.0, 180.0),
+            elbow_path: ElbowPath::Flared(90.0),
+            scapular_behavior: ScapularSetting::Protracted,
+            depth_requirement: 0.75,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Push-up
+        chest_exercises.insert("pushup", ChestExerciseProfile {
+            press_angle_range: (70.0, 120.0),
+            elbow_path: ElbowPath::Variable(30.0, 60.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.9,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 12. Dumbbell Fly
+        chest_exercises.insert("dumbbell-fly", ChestExerciseProfile {
+            press_angle_range: (100.0, 170.0),
+            elbow_path: ElbowPath::Flared(80.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.9,
+            stability_factor: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 13. Chest Dips
+        chest_exercises.insert("chest-dips", ChestExerciseProfile {
+            press_angle_range: (20.0, 70.0), // Arms stay close to the torso
+            elbow_path: ElbowPath::Tucked(30.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            depth_requirement: 0.85,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 14. Incline Bench Press
+        chest_exercises.insert("incline-bench-press", ChestExerciseProfile {
+            press_angle_range: (90.0, 150.0),
+            elbow_path: ElbowPath::Variable(45.0, 70.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.9,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 15. Decline Bench Press
+        chest_exercises.insert("decline-bench-press", ChestExerciseProfile {
+            press_angle_range: (70.0, 130.0),
+            elbow_path: ElbowPath::Variable(45.0, 65.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            depth_requirement: 0.9,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        ChestAnalyzer { chest_exercises }
+    }
+
+    pub fn calculate_chest_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_chest_side_angles(skeleton, side))
+    }
+
+    fn calculate_chest_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Shoulder flexion (hip-shoulder-elbow)
+        insert_angle(&mut angles, "shoulder_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Elbow angle (shoulder-elbow-wrist), the primary joint of the presses
+        insert_angle(&mut angles, "elbow", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Elbow path (hip-shoulder-elbow projected angle)
+        insert_angle(&mut angles, "elbow_path", calculate_projected_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Scapular movement (shoulder-hip-opposite shoulder)
+        insert_angle(&mut angles, "scapular_movement", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Press depth (wrist-shoulder-hip)
+        insert_angle(&mut angles, "press_depth", calculate_angle(
+            &skeleton.point(Landmark::LeftWrist.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_chest_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.chest_exercises.get(exercise_id) {
+            // Check shoulder flexion range
+            if let Some(flexion) = angles.get("shoulder_flexion").filter(|_| at_range_end(profile.press_angle_range, position)) {
+                if *flexion < profile.press_angle_range.0 {
+                    errors.push(FormIssue::new("insufficient_press_depth", Severity::Warning, format!(
+                        "Insufficient press depth ({}° < {}°)",
+                        flexion.round(), profile.press_angle_range.0
+                    ))
+                        .measured("shoulder_flexion", *flexion)
+                        .target(profile.press_angle_range.0, profile.press_angle_range.1));
+                }
+                if *flexion > profile.press_angle_range.1 {
+                    errors.push(FormIssue::new("shoulder_over_extension", Severity::Warning, format!(
+                        "Over-extension ({}° > {}°)",
+                        flexion.round(), profile.press_angle_range.1
+                    ))
+                        .measured("shoulder_flexion", *flexion)
+                        .target(profile.press_angle_range.0, profile.press_angle_range.1));
+                }
+            }
+            
+            // Check elbow path
+            if let Some(elbow_path) = angles.get("elbow_path") {
+                let (min, max) = match profile.elbow_path {
+                    ElbowPath::Flared(angle) => (angle - 15.0, angle + 15.0),
+                    ElbowPath::Tucked(angle) => (angle - 15.0, angle + 15.0),
+                    ElbowPath::Variable(min, max) => (min, max),
+                };
+                
+                if *elbow_path < min {
+                    errors.push(FormIssue::new("elbows_too_tucked", Severity::Warning, format!(
+                        "Elbows too tucked ({}° < {}°)",
+                        elbow_path.round(), min
+                    ))
+                        .measured("elbow_path", *elbow_path)
+                        .target(min, max));
+                }
+                if *elbow_path > max {
+                    errors.push(FormIssue::new("elbows_too_flared", Severity::Critical, format!(
+                        "Elbows too flared ({}° > {}°)",
+                        elbow_path.round(), max
+                    ))
+                        .measured("elbow_path", *elbow_path)
+                        .target(min, max));
+                }
+            }
+            
+            // Check scapular behavior
+            if let Some(scap_movement) = angles.get("scapular_movement") {
+                match profile.scapular_behavior {
+                    ScapularSetting::Retracted if *scap_movement < 160.0 => {
+                        errors.push(FormIssue::new("scapulae_not_retracted", Severity::Warning, "Maintain retracted scapulae")
+                            .measured("scapular_movement", *scap_movement)
+                            .at_least(160.0));
+                    },
+                    ScapularSetting::Protracted if *scap_movement > 200.0 => {
+                        errors.push(FormIssue::new("excessive_protraction", Severity::Warning, "Control scapular protraction")
+                            .measured("scapular_movement", *scap_movement)
+                            .at_most(200.0));
+                    },
+                    ScapularSetting::Dynamic if *scap_movement < 150.0 || *scap_movement > 210.0 => {
+                        errors.push(FormIssue::new("abnormal_scapular_movement", Severity::Warning, "Abnormal scapular movement")
+                            .measured("scapular_movement", *scap_movement)
+                            .target(150.0, 210.0));
+                    },
+                    _ => {}
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_chest_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.chest_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Press range component (40% weight)
+            if let Some(flexion) = angles.get("shoulder_flexion") {
+                let flex_norm = (*flexion - profile.press_angle_range.0) / 
+                                (profile.press_angle_range.1 - profile.press_angle_range.0);
+                score += 0.4 * flex_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.4;
+            }
+            
+            // Elbow path component (30% weight)
+            if let Some(&elbow_path) = angles.get("elbow_path") {
+                let path_score = match profile.elbow_path {
+                    ElbowPath::Flared(target) => 1.0 - (elbow_path - target).abs() / 30.0,
+                    ElbowPath::Tucked(target) => 1.0 - (elbow_path - target).abs() / 30.0,
+                    ElbowPath::Variable(min, max) => {
+                        if elbow_path < min {
+                            1.0 - (min - elbow_path) / 30.0
+                        } else if elbow_path > max {
+                            1.0 - (elbow_path - max) / 30.0
+                        } else {
+                            1.0
+                        }
+                    }
+                };
+                score += 0.3 * path_score.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Scapular control component (20% weight)
+            if let Some(scap_movement) = angles.get("scapular_movement") {
+                let scap_score = match profile.scapular_behavior {
+                    ScapularSetting::Retracted => 1.0 - (scap_movement - 180.0).abs() / 30.0,
+                    ScapularSetting::Protracted => 1.0 - (scap_movement - 190.0).abs() / 20.0,
+                    ScapularSetting::Dynamic => 1.0 - (scap_movement - 180.0).abs() / 45.0,
+                };
+                score += 0.2 * scap_score.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            // Depth component (10% weight)
+            if let Some(depth) = angles.get("press_depth") {
+                score += 0.1 * (*depth / 180.0).clamp(0.0, 1.0) * profile.depth_requirement;
+            } else {
+                hidden_weight += 0.1;
+            }
+            
+            (visible_score(score, hidden_weight) * profile.stability_factor).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("superman-push-ups", "shallow_push_up", r#"press_depth < 60 => "Insufficient chest-to-floor distance""#),
+    ("military-press", "incomplete_overhead_press", r#"shoulder_flexion < 170 => "Press should go fully overhead""#),
+];
+
+impl MuscleGroupAnalyzer for ChestAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "chest"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.chest_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_chest_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_chest_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_chest_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.chest_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn press_depth_is_checked_at_the_chest() {
+        let analyzer = ChestAnalyzer::new();
+        let lockout = angle_map(&[("shoulder_flexion", 150.0), ("elbow_path", 60.0), ("scapular_movement", 170.0)]);
+        assert!(analyzer.check_chest_form("bench-press-barbell", &lockout, at_rom(1.0)).is_empty());
+
+        let touch = angle_map(&[("shoulder_flexion", 100.0), ("elbow_path", 60.0), ("scapular_movement", 170.0)]);
+        assert!(analyzer.check_chest_form("bench-press-barbell", &touch, at_rom(0.0)).is_empty());
+
+        let issues = analyzer.check_chest_form("bench-press-barbell", &lockout, at_rom(0.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "shoulder_over_extension");
+    }
+}
@@ -0,0 +1,141 @@
+// wasm/src/pose_detection/defined.rs
+// Muscle group analyzer driven by exercise definitions loaded at runtime
+use std::collections::HashMap;
+
+use super::{bilateral_angles, calculate_angle, calculate_distance, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::definition::{ExerciseDefinition, ExerciseSet, PointRef};
+use crate::form::FormIssue;
+use crate::rep_counter::RepPosition;
+use crate::rules::Rule;
+use crate::skeleton::{Side, Skeleton};
+
+#[derive(Debug)]
+pub struct DefinedAnalyzer {
+    muscle_group: String,
+    exercises: HashMap<String, DefinedExercise>,
+}
+
+#[derive(Debug)]
+struct DefinedExercise {
+    definition: ExerciseDefinition,
+    bilateral: Vec<(String, Vec<PointRef>)>,
+    fixed: Vec<(String, Vec<PointRef>)>,
+    rules: Vec<Rule>,
+}
+
+impl DefinedAnalyzer {
+    pub fn new(set: &ExerciseSet) -> Result<Self, String> {
+        set.validate()?;
+
+        let mut exercises = HashMap::new();
+        for definition in set.engine_exercises() {
+            let mut bilateral = Vec::new();
+            let mut fixed = Vec::new();
+            for (key, angle) in &definition.angles {
+                let points = angle.resolve()?;
+                if points.iter().any(|point| point.sided) {
+                    bilateral.push((key.clone(), points));
+                } else {
+                    fixed.push((key.clone(), points));
+                }
+            }
+
+            let rules = definition.form_rules.iter().map(|rule| rule.compile()).collect::<Result<_, _>>()?;
+
+            exercises.insert(definition.id.clone(), DefinedExercise {
+                definition: definition.clone(),
+                bilateral,
+                fixed,
+                rules,
+            });
+        }
+
+        Ok(DefinedAnalyzer {
+            muscle_group: set.muscle_group.clone(),
+            exercises,
+        })
+    }
+
+    fn measure(skeleton: &Skeleton, points: &[PointRef], side: Side) -> Option<f32> {
+        let keypoints: Vec<_> = points
+            .iter()
+            .map(|point| {
+                let landmark = if point.sided { point.landmark.on_side(side) } else { point.landmark };
+                skeleton.point(landmark)
+            })
+            .collect();
+
+        match keypoints.as_slice() {
+            [a, b, c] => calculate_angle(a, b, c),
+            [a, b] => calculate_distance(a, b),
+            _ => None,
+        }
+    }
+}
+
+impl MuscleGroupAnalyzer for DefinedAnalyzer {
+    fn muscle_group(&self) -> &str {
+        &self.muscle_group
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.exercises.keys().map(String::as_str).collect()
+    }
+
+    fn calculate_angles(&self, exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        let Some(exercise) = self.exercises.get(exercise_id) else {
+            return HashMap::new();
+        };
+
+        let mut angles = bilateral_angles(|side| {
+            let mut angles = HashMap::new();
+            for (key, points) in &exercise.bilateral {
+                insert_angle(&mut angles, key, Self::measure(skeleton, points, side));
+            }
+            angles
+        });
+        for (key, points) in &exercise.fixed {
+            insert_angle(&mut angles, key, Self::measure(skeleton, points, Side::Left));
+        }
+
+        angles
+    }
+
+    // Every definition check is a rule, see form_rules
+    fn check_form(&self, _exercise_id: &str, _angles: &HashMap<String, f32>, _position: RepPosition) -> Vec<FormIssue> {
+        Vec::new()
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        let Some(exercise) = self.exercises.get(exercise_id) else {
+            return 0.5;
+        };
+        let terms = &exercise.definition.engagement;
+        let total_weight: f32 = terms.values().map(|term| term.weight).sum();
+        if total_weight <= 0.0 {
+            return 0.5;
+        }
+
+        let mut score = 0.0;
+        let mut hidden_weight = 0.0;
+        for (key, term) in terms {
+            let weight = term.weight / total_weight;
+            if let Some(angle) = angles.get(key) {
+                let (start, end) = term.range;
+                score += weight * ((angle - start) / (end - start)).clamp(0.0, 1.0);
+            } else {
+                hidden_weight += weight;
+            }
+        }
+
+        visible_score(score, hidden_weight).clamp(0.1, 1.0)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.exercises.get(exercise_id).and_then(|exercise| exercise.definition.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        self.exercises.get(exercise_id).map_or_else(Vec::new, |exercise| exercise.rules.clone())
+    }
+}
@@ -0,0 +1,234 @@
+// wasm/src/pose_detection/full_body.rs
+// Compound movements (burpees, thrusters, swings, Olympic lifts, HIIT moves). These chain
+// several poses per rep, so each one is described as an ordered list of stages for
+// sequence.rs rather than a single joint range
+use std::collections::HashMap;
+
+use super::{bilateral_angles, calculate_angle, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::FormIssue;
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::sequence::{self, Stage};
+use crate::skeleton::{Keypoint, Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct FullBodyAnalyzer {
+    full_body_exercises: HashMap<&'static str, FullBodyProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct FullBodyProfile {
+    engagement_joints: Vec<(&'static str, (f32, f32))>, // Engagement grows as the angle moves from range.0 to range.1
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for alternating movements
+}
+
+impl FullBodyAnalyzer {
+    pub fn new() -> Self {
+        let mut full_body_exercises = HashMap::new();
+
+        // 1. Burpees
+        full_body_exercises.insert("burpees", FullBodyProfile {
+            engagement_joints: vec![("hip", (180.0, 60.0)), ("knee", (180.0, 70.0)), ("elbow", (180.0, 90.0))],
+            symmetry_tolerance: Some(0.2),
+        });
+
+        // 2. Kettlebell Swings
+        full_body_exercises.insert("kettlebell-swings", FullBodyProfile {
+            engagement_joints: vec![("hip", (90.0, 180.0)), ("shoulder", (0.0, 90.0))],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Thrusters
+        full_body_exercises.insert("thrusters", FullBodyProfile {
+            engagement_joints: vec![("knee", (180.0, 80.0)), ("shoulder", (30.0, 170.0))],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Clean and Jerk
+        full_body_exercises.insert("clean-and-jerk", FullBodyProfile {
+            engagement_joints: vec![("hip", (60.0, 180.0)), ("shoulder", (0.0, 170.0))],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 5. Snatch
+        full_body_exercises.insert("snatch", FullBodyProfile {
+            engagement_joints: vec![("hip", (60.0, 180.0)), ("shoulder", (0.0, 170.0))],
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 6. Box Jumps
+        full_body_exercises.insert("box-jumps", FullBodyProfile {
+            engagement_joints: vec![("knee", (180.0, 90.0)), ("hip", (180.0, 90.0))],
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Jumping Jacks
+        full_body_exercises.insert("jumping-jacks", FullBodyProfile {
+            engagement_joints: vec![("shoulder", (20.0, 170.0)), ("leg_spread", (10.0, 45.0))],
+            symmetry_tolerance: Some(0.2),
+        });
+
+        // 8. Mountain Climbers
+        full_body_exercises.insert("mountain-climbers", FullBodyProfile {
+            engagement_joints: vec![("hip", (180.0, 80.0)), ("torso_incline", (0.0, 90.0))],
+            symmetry_tolerance: None, // Legs alternate
+        });
+
+        FullBodyAnalyzer { full_body_exercises }
+    }
+
+    pub fn calculate_full_body_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        let mut angles = bilateral_angles(|side| self.calculate_full_body_side_angles(skeleton, side));
+
+        // Torso against vertical (neck-midhip-straight up): 0 standing, 90 in a plank
+        let mid_hip = skeleton.point(Landmark::MidHip);
+        let above = Keypoint { y: mid_hip.y - 1.0, ..mid_hip };
+        insert_angle(&mut angles, "torso_incline", calculate_angle(
+            &skeleton.point(Landmark::Neck),
+            &mid_hip,
+            &above
+        ));
+
+        // Stance width (ankle-midhip-ankle)
+        insert_angle(&mut angles, "leg_spread", calculate_angle(
+            &skeleton.point(Landmark::LeftAnkle),
+            &mid_hip,
+            &skeleton.point(Landmark::RightAnkle)
+        ));
+
+        angles
+    }
+
+    fn calculate_full_body_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+
+        // Knee (hip-knee-ankle)
+        insert_angle(&mut angles, "knee", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+
+        // Hip (shoulder-hip-knee)
+        insert_angle(&mut angles, "hip", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+
+        // Elbow (shoulder-elbow-wrist)
+        insert_angle(&mut angles, "elbow", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+
+        // Shoulder (hip-shoulder-elbow), 0 arms down, 180 overhead
+        insert_angle(&mut angles, "shoulder", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+
+        angles
+    }
+
+    pub fn calculate_full_body_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        let Some(profile) = self.full_body_exercises.get(exercise_id) else {
+            return 0.5;
+        };
+
+        let weight = 1.0 / profile.engagement_joints.len() as f32;
+        let mut score = 0.0;
+        let mut hidden_weight = 0.0;
+        for (joint, (start, end)) in &profile.engagement_joints {
+            if let Some(angle) = angles.get(*joint) {
+                score += weight * ((angle - start) / (end - start)).clamp(0.0, 1.0);
+            } else {
+                hidden_weight += weight;
+            }
+        }
+
+        visible_score(score, hidden_weight).clamp(0.1, 1.0)
+    }
+}
+
+// Stages of each rep, in order: (exercise id, stage, pose condition, cue when skipped)
+const STAGES: &[(&str, &str, &str, &str)] = &[
+    ("burpees", "squat", "knee < 110 and hip < 100", "Squat down and plant the hands"),
+    ("burpees", "plank", "torso_incline > 60 and hip > 150 and elbow > 150", "Kick the feet back to a plank"),
+    ("burpees", "push_up", "torso_incline > 60 and elbow < 110", "Lower the chest to the floor"),
+    // No height from angles alone, the overhead reach stands in for the jump
+    ("burpees", "jump", "torso_incline < 30 and hip > 160 and knee > 160 and shoulder > 140", "Finish with a jump, arms overhead"),
+
+    ("kettlebell-swings", "hinge", "hip < 110 and knee > 120", "Hinge the hips back between the legs"),
+    ("kettlebell-swings", "lockout", "hip > 165 and knee > 160", "Snap the hips through and stand tall"),
+    ("kettlebell-swings", "float", "hip > 160 and shoulder > 80", "Let the bell float to chest height"),
+
+    ("thrusters", "squat", "knee < 100 and elbow < 90", "Squat below parallel with the bar in the rack"),
+    ("thrusters", "press", "knee > 160 and hip > 160 and shoulder > 150 and elbow > 150", "Drive the bar to lockout overhead"),
+
+    ("clean-and-jerk", "pull", "hip < 120 and knee < 140 and shoulder < 45", "Start the pull from the floor"),
+    ("clean-and-jerk", "catch", "elbow < 70 and shoulder > 45", "Catch the bar in the front rack"),
+    ("clean-and-jerk", "stand", "hip > 160 and knee > 160 and elbow < 70", "Stand up with the bar in the rack"),
+    ("clean-and-jerk", "jerk", "shoulder > 160 and elbow > 160", "Drive the bar to lockout overhead"),
+
+    ("snatch", "pull", "hip < 120 and knee < 140 and shoulder < 45", "Start the pull from the floor"),
+    ("snatch", "catch", "shoulder > 150 and elbow > 160 and knee < 120", "Catch the bar in an overhead squat"),
+    ("snatch", "stand", "shoulder > 150 and elbow > 160 and hip > 160 and knee > 160", "Stand up with the bar locked out"),
+
+    ("box-jumps", "load", "knee < 120 and hip < 120", "Load the hips before the jump"),
+    ("box-jumps", "take_off", "knee > 160 and hip > 160", "Extend hips and knees fully on take-off"),
+    ("box-jumps", "landing", "knee < 130 and hip < 130", "Land softly in a quarter squat"),
+    ("box-jumps", "stand", "knee > 160 and hip > 160", "Stand tall on the box"),
+
+    ("jumping-jacks", "open", "shoulder > 140 and leg_spread > 30", "Arms overhead and feet wide"),
+    ("jumping-jacks", "closed", "shoulder < 40 and leg_spread < 20", "Arms down and feet together"),
+
+    ("mountain-climbers", "left_drive", "torso_incline > 50 and left_hip < 110", "Drive the left knee to the chest"),
+    ("mountain-climbers", "right_drive", "torso_incline > 50 and right_hip < 110", "Drive the right knee to the chest"),
+];
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("burpees", "sagging_plank", r#"torso_incline > 60 and elbow > 150 and hip < 150 for 300ms => "Keep the hips in line in the plank""#),
+    ("kettlebell-swings", "squatting_swing", r#"hip < 120 and knee < 110 => "Hinge at the hips, don't squat the swing""#),
+    ("thrusters", "soft_lockout", r#"shoulder > 150 and elbow < 160 for 300ms => info "Lock the elbows out overhead""#),
+    ("jumping-jacks", "bent_arms", r#"shoulder > 120 and elbow < 140 => info "Keep the arms long overhead""#),
+    ("mountain-climbers", "piked_hips", r#"torso_incline in 20..55 for 500ms => "Keep the hips down, in line with the shoulders""#),
+];
+
+impl MuscleGroupAnalyzer for FullBodyAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "full_body"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.full_body_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_full_body_angles(skeleton)
+    }
+
+    // Checks are rules (see FORM_RULES), skipped stages are reported by the sequence
+    fn check_form(&self, _exercise_id: &str, _angles: &HashMap<String, f32>, _position: RepPosition) -> Vec<FormIssue> {
+        Vec::new()
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_full_body_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.full_body_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+
+    fn stages(&self, exercise_id: &str) -> Vec<Stage> {
+        sequence::from_table(STAGES, exercise_id)
+    }
+}
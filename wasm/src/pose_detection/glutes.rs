@@ -0,0 +1,388 @@
+// wasm/src/pose_detection/glutes.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, insert_angle, is_visible, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::hold::HoldRange;
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct GluteAnalyzer {
+    glute_exercises: HashMap<&'static str, GluteExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct GluteExerciseProfile {
+    hip_extension_range: (f32, f32),   // Optimal hip extension angles
+    knee_angle_range: (f32, f32),      // Companion knee angles
+    lumbar_stability_threshold: f32,   // Lower back stability
+    unilateral_factor: f32,            // 0=bilateral, 1=unilateral
+    activation_emphasis: GluteActivation,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum GluteActivation {
+    Maximus,
+    Medius,
+    Both,
+}
+
+impl GluteAnalyzer {
+    pub fn new() -> Self {
+        let mut glute_exercises = HashMap::new();
+
+        // 1. Superman
+        glute_exercises.insert("superman", GluteExerciseProfile {
+            hip_extension_range: (10.0, 30.0), // Isometric hold range
+            knee_angle_range: (170.0, 180.0),
+            lumbar_stability_threshold: 0.9,
+            unilateral_factor: 0.3, // Slightly unilateral
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Good Morning
+        glute_exercises.insert("good-morning", GluteExerciseProfile {
+            hip_extension_range: (60.0, 180.0), // Full ROM
+            knee_angle_range: (170.0, 180.0),   // Near-locked
+            lumbar_stability_threshold: 0.95,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Yoga Ball Glute Raises
+        glute_exercises.insert("yoga-ball-glute-raises", GluteExerciseProfile {
+            hip_extension_range: (160.0, 200.0), // Hyperextension
+            knee_angle_range: (90.0, 120.0),     // Bent knees
+            lumbar_stability_threshold: 0.85,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Both,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Donkey Kick
+        glute_exercises.insert("donkey-kick", GluteExerciseProfile {
+            hip_extension_range: (120.0, 180.0),
+            knee_angle_range: (90.0, 120.0),
+            lumbar_stability_threshold: 0.8,
+            unilateral_factor: 1.0,
+            activation_emphasis: GluteActivation::Medius,
+            symmetry_tolerance: None,
+        });
+
+        // 5. Inverse Kick Back
+        glute_exercises.insert("inverse-kick-back", GluteExerciseProfile {
+            hip_extension_range: (140.0, 190.0),
+            knee_angle_range: (160.0, 180.0),
+            lumbar_stability_threshold: 0.75,
+            unilateral_factor: 1.0,
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: None,
+        });
+
+        // 6. Barbell Bench Touches
+        glute_exercises.insert("barbell-bench-touches", GluteExerciseProfile {
+            hip_extension_range: (70.0, 120.0),
+            knee_angle_range: (160.0, 180.0),
+            lumbar_stability_threshold: 0.7,
+            unilateral_factor: 0.5,
+            activation_emphasis: GluteActivation::Medius,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 7. Barbell Hip Thrust
+        glute_exercises.insert("barbell-hip-thrust", GluteExerciseProfile {
+            hip_extension_range: (160.0, 190.0),
+            knee_angle_range: (90.0, 100.0),
+            lumbar_stability_threshold: 0.9,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 8. Curtsy Lunges
+        glute_exercises.insert("curtsy-lunges", GluteExerciseProfile {
+            hip_extension_range: (80.0, 140.0),
+            knee_angle_range: (90.0, 130.0),
+            lumbar_stability_threshold: 0.85,
+            unilateral_factor: 0.8,
+            activation_emphasis: GluteActivation::Medius,
+            symmetry_tolerance: None,
+        });
+
+        // 9. Cable Pull Through
+        glute_exercises.insert("cable-pull-through", GluteExerciseProfile {
+            hip_extension_range: (100.0, 170.0),
+            knee_angle_range: (120.0, 160.0),
+            lumbar_stability_threshold: 0.8,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Both,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 10. Frog Pumps
+        glute_exercises.insert("frog-pumps", GluteExerciseProfile {
+            hip_extension_range: (150.0, 190.0),
+            knee_angle_range: (45.0, 90.0), // Wide stance
+            lumbar_stability_threshold: 0.7,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Medius,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Glute Bridge
+        glute_exercises.insert("glute-bridge", GluteExerciseProfile {
+            hip_extension_range: (150.0, 190.0),
+            knee_angle_range: (80.0, 100.0),
+            lumbar_stability_threshold: 0.85,
+            unilateral_factor: 0.0,
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 12. Single-Leg Hip Thrust
+        glute_exercises.insert("single-leg-hip-thrust", GluteExerciseProfile {
+            hip_extension_range: (160.0, 190.0),
+            knee_angle_range: (85.0, 100.0),
+            lumbar_stability_threshold: 0.85,
+            unilateral_factor: 1.0,
+            activation_emphasis: GluteActivation::Maximus,
+            symmetry_tolerance: None,
+        });
+
+        // 13. Clamshells
+        glute_exercises.insert("clamshells", GluteExerciseProfile {
+            hip_extension_range: (120.0, 150.0), // Hips stay flexed
+            knee_angle_range: (80.0, 100.0),
+            lumbar_stability_threshold: 0.8,
+            unilateral_factor: 1.0,
+            activation_emphasis: GluteActivation::Medius,
+            symmetry_tolerance: None,
+        });
+
+        GluteAnalyzer { glute_exercises }
+    }
+
+    pub fn calculate_glute_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_glute_side_angles(skeleton, side))
+    }
+
+    fn calculate_glute_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Hip extension (shoulder-hip-knee)
+        insert_angle(&mut angles, "hip_extension", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        // Knee angle (hip-knee-ankle)
+        insert_angle(&mut angles, "knee_angle", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+        
+        // Lumbar stability (shoulder-hip-opposite hip)
+        insert_angle(&mut angles, "lumbar_stability", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightHip.on_side(side))
+        ));
+        
+        // Unilateral loading (hip height difference)
+        let (left_hip, right_hip) = (skeleton.point(Landmark::LeftHip), skeleton.point(Landmark::RightHip));
+        if is_visible(&[&left_hip, &right_hip]) {
+            angles.insert("unilateral_loading".to_string(), (right_hip.y - left_hip.y).abs());
+        }
+        
+        angles
+    }
+
+    pub fn check_glute_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.glute_exercises.get(exercise_id) {
+            // Check hip extension range, the lockout of thrusts and bridges and the depth of hinges
+            if let Some(hip_ext) = angles.get("hip_extension").filter(|_| at_range_end(profile.hip_extension_range, position)) {
+                if *hip_ext < profile.hip_extension_range.0 {
+                    errors.push(FormIssue::new("insufficient_hip_extension", Severity::Warning, format!(
+                        "Insufficient hip extension ({}° < {}°)",
+                        hip_ext.round(), profile.hip_extension_range.0
+                    ))
+                        .measured("hip_extension", *hip_ext)
+                        .target(profile.hip_extension_range.0, profile.hip_extension_range.1));
+                }
+                if *hip_ext > profile.hip_extension_range.1 {
+                    errors.push(FormIssue::new("hip_over_extension", Severity::Warning, format!(
+                        "Over-extension ({}° > {}°)",
+                        hip_ext.round(), profile.hip_extension_range.1
+                    ))
+                        .measured("hip_extension", *hip_ext)
+                        .target(profile.hip_extension_range.0, profile.hip_extension_range.1));
+                }
+            }
+            
+            // Check knee angle
+            if let Some(knee) = angles.get("knee_angle") {
+                if *knee < profile.knee_angle_range.0 {
+                    errors.push(FormIssue::new("knees_too_bent", Severity::Warning, format!(
+                        "Knees too bent ({}° < {}°)",
+                        knee.round(), profile.knee_angle_range.0
+                    ))
+                        .measured("knee_angle", *knee)
+                        .target(profile.knee_angle_range.0, profile.knee_angle_range.1));
+                }
+                if *knee > profile.knee_angle_range.1 {
+                    errors.push(FormIssue::new("knees_too_straight", Severity::Warning, format!(
+                        "Knees too straight ({}° > {}°)",
+                        knee.round(), profile.knee_angle_range.1
+                    ))
+                        .measured("knee_angle", *knee)
+                        .target(profile.knee_angle_range.0, profile.knee_angle_range.1));
+                }
+            }
+            
+            // Check lumbar stability
+            if let Some(lumbar) = angles.get("lumbar_stability") {
+                let tolerance = (1.0 - profile.lumbar_stability_threshold) * 30.0;
+                if (lumbar - 180.0).abs() > tolerance {
+                    errors.push(FormIssue::new("lumbar_instability", Severity::Critical, "Lumbar instability detected")
+                        .measured("lumbar_stability", *lumbar)
+                        .target(180.0 - tolerance, 180.0 + tolerance));
+                }
+            }
+            
+            // Check unilateral loading
+            if let Some(unilateral) = angles.get("unilateral_loading") {
+                let expected_asymmetry = profile.unilateral_factor * 0.2; // Normalized
+                if (unilateral - expected_asymmetry).abs() > 0.1 {
+                    errors.push(FormIssue::new("uneven_weight_distribution", Severity::Warning, "Improper weight distribution")
+                        .measured("unilateral_loading", *unilateral)
+                        .target(expected_asymmetry - 0.1, expected_asymmetry + 0.1));
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_glute_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.glute_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Hip extension component (50% weight)
+            if let Some(hip_ext) = angles.get("hip_extension") {
+                let ext_norm = (*hip_ext - profile.hip_extension_range.0) / 
+                              (profile.hip_extension_range.1 - profile.hip_extension_range.0);
+                score += 0.5 * ext_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.5;
+            }
+            
+            // Knee position component (20% weight)
+            if let Some(knee) = angles.get("knee_angle") {
+                let knee_dev = match profile.activation_emphasis {
+                    GluteActivation::Maximus => 1.0 - (knee - 90.0).abs() / 45.0,
+                    GluteActivation::Medius => 1.0 - (knee - 120.0).abs() / 60.0,
+                    GluteActivation::Both => 1.0 - (knee - 105.0).abs() / 50.0,
+                };
+                score += 0.2 * knee_dev.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            // Lumbar stability component (20% weight)
+            if let Some(lumbar) = angles.get("lumbar_stability") {
+                let lumbar_score = 1.0 - (lumbar - 180.0).abs() / 30.0;
+                score += 0.2 * lumbar_score.clamp(0.0, 1.0) * profile.lumbar_stability_threshold;
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            // Unilateral component (10% weight)
+            if let Some(unilateral) = angles.get("unilateral_loading") {
+                let uni_score = 1.0 - (unilateral - profile.unilateral_factor * 0.2).abs() / 0.1;
+                score += 0.1 * uni_score.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.1;
+            }
+            
+            visible_score(score, hidden_weight).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("frog-pumps", "narrow_frog_stance", r#"knee_angle > 100 => info "Maintain wider stance""#),
+    ("good-morning", "incomplete_hip_extension", r#"hip_extension < 170 during phase=top => "Incomplete hip extension""#),
+];
+
+impl MuscleGroupAnalyzer for GluteAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "glutes"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.glute_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_glute_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_glute_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_glute_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.glute_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+
+    fn hold_ranges(&self, exercise_id: &str) -> Vec<HoldRange> {
+        self.glute_exercises.get(exercise_id).map_or_else(Vec::new, |profile| {
+            vec![
+                HoldRange::new("hip_extension", profile.hip_extension_range),
+                HoldRange::new("knee_angle", profile.knee_angle_range),
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn hip_thrust_lockout_is_only_checked_at_the_top() {
+        let analyzer = GluteAnalyzer::new();
+        let lockout = angle_map(&[("hip_extension", 175.0), ("knee_angle", 95.0), ("lumbar_stability", 180.0), ("unilateral_loading", 0.0)]);
+        assert!(analyzer.check_glute_form("barbell-hip-thrust", &lockout, at_rom(1.0)).is_empty());
+
+        let bottom = angle_map(&[("hip_extension", 105.0), ("knee_angle", 95.0), ("lumbar_stability", 180.0), ("unilateral_loading", 0.0)]);
+        assert!(analyzer.check_glute_form("barbell-hip-thrust", &bottom, at_rom(0.0)).is_empty());
+
+        let arched = angle_map(&[("hip_extension", 195.0), ("knee_angle", 95.0), ("lumbar_stability", 180.0), ("unilateral_loading", 0.0)]);
+        let issues = analyzer.check_glute_form("barbell-hip-thrust", &arched, at_rom(1.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "hip_over_extension");
+    }
+}
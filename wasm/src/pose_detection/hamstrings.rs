@@ -0,0 +1,365 @@
+// wasm/src/pose_detection/hamstrings.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct HamstringAnalyzer {
+    hamstring_exercises: HashMap<&'static str, HamstringExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct HamstringExerciseProfile {
+    knee_flexion_range: (f32, f32),      // Optimal knee angles
+    hip_hinge_range: (f32, f32),         // Hip flexion/extension
+    lumbar_stability_threshold: f32,     // Lower back control
+    eccentric_emphasis: f32,             // 0-1 how important lowering phase is
+    muscle_balance: HamstringBalance,    // Which part of hamstrings is emphasized
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum HamstringBalance {
+    BicepsFemoris,
+    Semitendinosus,
+    MedialLateral,
+    All,
+}
+
+impl HamstringAnalyzer {
+    pub fn new() -> Self {
+        let mut hamstring_exercises = HashMap::new();
+
+        // 1. Romanian Deadlifts
+        hamstring_exercises.insert("romanian-deadlifts", HamstringExerciseProfile {
+            knee_flexion_range: (160.0, 180.0), // Slightly bent
+            hip_hinge_range: (60.0, 120.0),
+            lumbar_stability_threshold: 0.95,
+            eccentric_emphasis: 0.8,
+            muscle_balance: HamstringBalance::All,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Hamstring Curls
+        hamstring_exercises.insert("hamstring-curls", HamstringExerciseProfile {
+            knee_flexion_range: (30.0, 150.0), // Full ROM
+            hip_hinge_range: (170.0, 180.0),   // Minimal hip movement
+            lumbar_stability_threshold: 0.85,
+            eccentric_emphasis: 0.7,
+            muscle_balance: HamstringBalance::MedialLateral,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 3. Kettlebell Good Morning
+        hamstring_exercises.insert("kettlebell-good-morning", HamstringExerciseProfile {
+            knee_flexion_range: (170.0, 180.0),
+            hip_hinge_range: (70.0, 130.0),
+            lumbar_stability_threshold: 0.9,
+            eccentric_emphasis: 0.6,
+            muscle_balance: HamstringBalance::BicepsFemoris,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Glute-Ham Raises
+        hamstring_exercises.insert("glute-ham-raises", HamstringExerciseProfile {
+            knee_flexion_range: (90.0, 180.0),
+            hip_hinge_range: (120.0, 180.0),
+            lumbar_stability_threshold: 0.8,
+            eccentric_emphasis: 0.9,
+            muscle_balance: HamstringBalance::All,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 5. Single-Leg Deadlifts
+        hamstring_exercises.insert("single-leg-deadlifts", HamstringExerciseProfile {
+            knee_flexion_range: (170.0, 180.0),
+            hip_hinge_range: (80.0, 140.0),
+            lumbar_stability_threshold: 0.85,
+            eccentric_emphasis: 0.7,
+            muscle_balance: HamstringBalance::Semitendinosus,
+            symmetry_tolerance: None,
+        });
+
+        // 6. Seated Leg Curls
+        hamstring_exercises.insert("seated-leg-curls", HamstringExerciseProfile {
+            knee_flexion_range: (45.0, 135.0),
+            hip_hinge_range: (175.0, 180.0),
+            lumbar_stability_threshold: 0.75,
+            eccentric_emphasis: 0.5,
+            muscle_balance: HamstringBalance::MedialLateral,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 7. Nordic Hamstring Curls
+        hamstring_exercises.insert("nordic-hamstring-curls", HamstringExerciseProfile {
+            knee_flexion_range: (120.0, 180.0),
+            hip_hinge_range: (160.0, 180.0),
+            lumbar_stability_threshold: 0.7,
+            eccentric_emphasis: 1.0,
+            muscle_balance: HamstringBalance::BicepsFemoris,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 8. Stiff-Leg Deadlifts
+        hamstring_exercises.insert("stiff-leg-deadlifts", HamstringExerciseProfile {
+            knee_flexion_range: (175.0, 180.0),
+            hip_hinge_range: (60.0, 120.0),
+            lumbar_stability_threshold: 0.9,
+            eccentric_emphasis: 0.8,
+            muscle_balance: HamstringBalance::All,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Swiss Ball Hamstring Curls
+        hamstring_exercises.insert("swiss-ball-hamstring-curls", HamstringExerciseProfile {
+            knee_flexion_range: (90.0, 160.0),
+            hip_hinge_range: (150.0, 180.0),
+            lumbar_stability_threshold: 0.8,
+            eccentric_emphasis: 0.6,
+            muscle_balance: HamstringBalance::Semitendinosus,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 10. Reverse Hyperextensions
+        hamstring_exercises.insert("reverse-hyperextensions", HamstringExerciseProfile {
+            knee_flexion_range: (150.0, 180.0),
+            hip_hinge_range: (160.0, 200.0), // Hyperextension
+            lumbar_stability_threshold: 0.85,
+            eccentric_emphasis: 0.5,
+            muscle_balance: HamstringBalance::BicepsFemoris,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 11. Sliding Leg Curls
+        hamstring_exercises.insert("sliding-leg-curls", HamstringExerciseProfile {
+            knee_flexion_range: (90.0, 170.0),
+            hip_hinge_range: (150.0, 180.0), // Hips stay bridged
+            lumbar_stability_threshold: 0.8,
+            eccentric_emphasis: 0.7,
+            muscle_balance: HamstringBalance::All,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        HamstringAnalyzer { hamstring_exercises }
+    }
+
+    pub fn calculate_hamstring_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_hamstring_side_angles(skeleton, side))
+    }
+
+    fn calculate_hamstring_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Knee flexion (hip-knee-ankle)
+        insert_angle(&mut angles, "knee_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+        
+        // Hip hinge (shoulder-hip-knee)
+        insert_angle(&mut angles, "hip_hinge", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        // Lumbar stability (shoulder-hip-opposite hip)
+        insert_angle(&mut angles, "lumbar_stability", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightHip.on_side(side))
+        ));
+        
+        // Muscle balance (knee-ankle-foot angle)
+        insert_angle(&mut angles, "muscle_balance", calculate_angle(
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side)),
+            &skeleton.point(Landmark::LeftFootIndex.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_hamstring_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.hamstring_exercises.get(exercise_id) {
+            // Check knee flexion range
+            if let Some(knee) = angles.get("knee_flexion").filter(|_| at_range_end(profile.knee_flexion_range, position)) {
+                if *knee < profile.knee_flexion_range.0 {
+                    errors.push(FormIssue::new("insufficient_knee_flexion", Severity::Warning, format!(
+                        "Insufficient knee flexion ({}° < {}°)",
+                        knee.round(), profile.knee_flexion_range.0
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_flexion_range.0, profile.knee_flexion_range.1));
+                }
+                if *knee > profile.knee_flexion_range.1 {
+                    errors.push(FormIssue::new("excessive_knee_flexion", Severity::Warning, format!(
+                        "Excessive knee flexion ({}° > {}°)",
+                        knee.round(), profile.knee_flexion_range.1
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_flexion_range.0, profile.knee_flexion_range.1));
+                }
+            }
+            
+            // Check hip hinge range
+            if let Some(hip) = angles.get("hip_hinge").filter(|_| at_range_end(profile.hip_hinge_range, position)) {
+                if *hip < profile.hip_hinge_range.0 {
+                    errors.push(FormIssue::new("insufficient_hip_hinge", Severity::Warning, format!(
+                        "Insufficient hip hinge ({}° < {}°)",
+                        hip.round(), profile.hip_hinge_range.0
+                    ))
+                        .measured("hip_hinge", *hip)
+                        .target(profile.hip_hinge_range.0, profile.hip_hinge_range.1));
+                }
+                if *hip > profile.hip_hinge_range.1 {
+                    errors.push(FormIssue::new("hip_over_extension", Severity::Warning, format!(
+                        "Over-extension ({}° > {}°)",
+                        hip.round(), profile.hip_hinge_range.1
+                    ))
+                        .measured("hip_hinge", *hip)
+                        .target(profile.hip_hinge_range.0, profile.hip_hinge_range.1));
+                }
+            }
+            
+            // Check lumbar stability
+            if let Some(lumbar) = angles.get("lumbar_stability") {
+                let tolerance = (1.0 - profile.lumbar_stability_threshold) * 30.0;
+                if (lumbar - 180.0).abs() > tolerance {
+                    errors.push(FormIssue::new("lumbar_instability", Severity::Critical, "Lumbar instability detected")
+                        .measured("lumbar_stability", *lumbar)
+                        .target(180.0 - tolerance, 180.0 + tolerance));
+                }
+            }
+            
+            // Check muscle balance
+            if let Some(balance) = angles.get("muscle_balance") {
+                match profile.muscle_balance {
+                    HamstringBalance::BicepsFemoris if *balance < 160.0 => {
+                        errors.push(FormIssue::new("lateral_hamstring_cue", Severity::Info, "Focus on lateral hamstring engagement")
+                            .measured("muscle_balance", *balance)
+                            .at_least(160.0));
+                    },
+                    HamstringBalance::Semitendinosus if *balance > 200.0 => {
+                        errors.push(FormIssue::new("medial_hamstring_cue", Severity::Info, "Focus on medial hamstring engagement")
+                            .measured("muscle_balance", *balance)
+                            .at_most(200.0));
+                    },
+                    HamstringBalance::MedialLateral if *balance < 170.0 || *balance > 190.0 => {
+                        errors.push(FormIssue::new("hamstring_balance_cue", Severity::Info, "Maintain balanced hamstring activation")
+                            .measured("muscle_balance", *balance)
+                            .target(170.0, 190.0));
+                    },
+                    _ => {}
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_hamstring_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, is_eccentric: bool) -> f32 {
+        if let Some(profile) = self.hamstring_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Knee flexion component (40% weight)
+            if let Some(knee) = angles.get("knee_flexion") {
+                let knee_norm = (*knee - profile.knee_flexion_range.0) / 
+                               (profile.knee_flexion_range.1 - profile.knee_flexion_range.0);
+                score += 0.4 * knee_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.4;
+            }
+            
+            // Hip hinge component (30% weight)
+            if let Some(hip) = angles.get("hip_hinge") {
+                let hip_norm = (*hip - profile.hip_hinge_range.0) / 
+                              (profile.hip_hinge_range.1 - profile.hip_hinge_range.0);
+                score += 0.3 * hip_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Lumbar stability component (20% weight)
+            if let Some(lumbar) = angles.get("lumbar_stability") {
+                let lumbar_score = 1.0 - (lumbar - 180.0).abs() / 30.0;
+                score += 0.2 * lumbar_score.clamp(0.0, 1.0) * profile.lumbar_stability_threshold;
+            } else {
+                hidden_weight += 0.2;
+            }
+            
+            // Eccentric emphasis bonus (10% weight)
+            if is_eccentric {
+                score += 0.1 * profile.eccentric_emphasis;
+            }
+            
+            visible_score(score, hidden_weight).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("nordic-hamstring-curls", "uncontrolled_eccentric", r#"knee_flexion < 150 during phase=eccentric => "Control eccentric phase""#),
+    ("stiff-leg-deadlifts", "legs_not_straight", r#"hip_hinge < 170 => "Maintain straighter legs""#),
+];
+
+impl MuscleGroupAnalyzer for HamstringAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "hamstrings"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.hamstring_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_hamstring_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_hamstring_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> f32 {
+        self.calculate_hamstring_engagement(exercise_id, angles, position.is_eccentric())
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.hamstring_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+
+    #[test]
+    fn standing_tall_is_not_a_missed_hinge() {
+        let analyzer = HamstringAnalyzer::new();
+        let standing = angle_map(&[("knee_flexion", 176.0), ("hip_hinge", 178.0), ("lumbar_stability", 180.0)]);
+        assert!(analyzer.check_hamstring_form("romanian-deadlifts", &standing, at_rom(1.0)).is_empty());
+
+        let hinged = angle_map(&[("knee_flexion", 165.0), ("hip_hinge", 95.0), ("lumbar_stability", 180.0)]);
+        assert!(analyzer.check_hamstring_form("romanian-deadlifts", &hinged, at_rom(0.0)).is_empty());
+
+        let issues = analyzer.check_hamstring_form("romanian-deadlifts", &standing, at_rom(0.0));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "hip_over_extension");
+    }
+}
@@ -0,0 +1,435 @@
+// wasm/src/pose_detection/quads.rs
+use std::collections::HashMap;
+
+use super::{at_range_end, bilateral_angles, calculate_angle, calculate_plane_angle, insert_angle, is_visible, MovementPlane, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct QuadAnalyzer {
+    quad_exercises: HashMap<&'static str, QuadExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct QuadExerciseProfile {
+    knee_flexion_range: (f32, f32),      // Optimal knee angles
+    hip_depth_range: (f32, f32),         // Hip descent depth
+    torso_lean_range: (f32, f32),        // Acceptable torso lean
+    stance_width_factor: f32,            // 0=narrow, 1=wide stance
+    emphasis: QuadEmphasis,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum QuadEmphasis {
+    VastusLateralis,
+    VastusMedialis,
+    RectusFemoris,
+    All,
+}
+
+impl QuadAnalyzer {
+    pub fn new() -> Self {
+        let mut quad_exercises = HashMap::new();
+
+        // 1. Bulgarian Split Squats
+        quad_exercises.insert("bulgarian-splits", QuadExerciseProfile {
+            knee_flexion_range: (60.0, 120.0),
+            hip_depth_range: (80.0, 130.0),
+            torso_lean_range: (70.0, 90.0), // More upright
+            stance_width_factor: 0.3,
+            emphasis: QuadEmphasis::VastusMedialis,
+            symmetry_tolerance: None,
+        });
+
+        // 2. Bodyweight Lunges
+        quad_exercises.insert("bodyweight-lunges", QuadExerciseProfile {
+            knee_flexion_range: (80.0, 140.0),
+            hip_depth_range: (90.0, 150.0),
+            torso_lean_range: (60.0, 80.0),
+            stance_width_factor: 0.5,
+            emphasis: QuadEmphasis::RectusFemoris,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Goblet Squats
+        quad_exercises.insert("goblet-squats", QuadExerciseProfile {
+            knee_flexion_range: (90.0, 150.0),
+            hip_depth_range: (100.0, 160.0),
+            torso_lean_range: (65.0, 85.0),
+            stance_width_factor: 0.7,
+            emphasis: QuadEmphasis::All,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Weighted Lunges
+        quad_exercises.insert("weighted-lunges", QuadExerciseProfile {
+            knee_flexion_range: (85.0, 135.0),
+            hip_depth_range: (95.0, 145.0),
+            torso_lean_range: (65.0, 85.0),
+            stance_width_factor: 0.5,
+            emphasis: QuadEmphasis::VastusLateralis,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Side Squats
+        quad_exercises.insert("side-squats", QuadExerciseProfile {
+            knee_flexion_range: (70.0, 110.0),
+            hip_depth_range: (90.0, 140.0),
+            torso_lean_range: (75.0, 95.0), // More upright
+            stance_width_factor: 0.9,
+            emphasis: QuadEmphasis::VastusMedialis,
+            symmetry_tolerance: None,
+        });
+
+        // 6. Obstacle Overstep Touches
+        quad_exercises.insert("obstacle-overstep-touches", QuadExerciseProfile {
+            knee_flexion_range: (100.0, 160.0),
+            hip_depth_range: (120.0, 180.0),
+            torso_lean_range: (50.0, 70.0), // More forward lean
+            stance_width_factor: 0.8,
+            emphasis: QuadEmphasis::RectusFemoris,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Stand-ups
+        quad_exercises.insert("stand-ups", QuadExerciseProfile {
+            knee_flexion_range: (30.0, 180.0), // Full ROM
+            hip_depth_range: (60.0, 180.0),
+            torso_lean_range: (60.0, 90.0),
+            stance_width_factor: 0.4,
+            emphasis: QuadEmphasis::All,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 8. Squat Steps
+        quad_exercises.insert("squat-steps", QuadExerciseProfile {
+            knee_flexion_range: (90.0, 130.0),
+            hip_depth_range: (100.0, 150.0),
+            torso_lean_range: (70.0, 90.0),
+            stance_width_factor: 0.6,
+            emphasis: QuadEmphasis::VastusLateralis,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Front Squats
+        quad_exercises.insert("front-squats", QuadExerciseProfile {
+            knee_flexion_range: (95.0, 155.0),
+            hip_depth_range: (110.0, 170.0),
+            torso_lean_range: (80.0, 100.0), // Very upright
+            stance_width_factor: 0.5,
+            emphasis: QuadEmphasis::All,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 10. Leg Extensions
+        quad_exercises.insert("leg-extensions", QuadExerciseProfile {
+            knee_flexion_range: (30.0, 180.0), // Full ROM
+            hip_depth_range: (170.0, 180.0),   // Minimal hip movement
+            torso_lean_range: (85.0, 95.0),    // Fully upright
+            stance_width_factor: 0.1,
+            emphasis: QuadEmphasis::VastusMedialis,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 11. Squat
+        quad_exercises.insert("squat", QuadExerciseProfile {
+            knee_flexion_range: (90.0, 150.0),
+            hip_depth_range: (100.0, 160.0),
+            torso_lean_range: (65.0, 85.0),
+            stance_width_factor: 0.6,
+            emphasis: QuadEmphasis::All,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        QuadAnalyzer { quad_exercises }
+    }
+
+    pub fn calculate_quad_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_quad_side_angles(skeleton, side))
+    }
+
+    fn calculate_quad_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Knee flexion (hip-knee-ankle)
+        insert_angle(&mut angles, "knee_flexion", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side))
+        ));
+        
+        // Hip depth (shoulder-hip-knee)
+        insert_angle(&mut angles, "hip_depth", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side))
+        ));
+        
+        // Torso lean (hip-shoulder-neck)
+        insert_angle(&mut angles, "torso_lean", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::Nose)
+        ));
+        
+        // Stance width (hip-knee-ankle horizontal distance)
+        let (left_hip, right_hip) = (skeleton.point(Landmark::LeftHip), skeleton.point(Landmark::RightHip));
+        if is_visible(&[&left_hip, &right_hip]) {
+            angles.insert("stance_width".to_string(), (right_hip.x - left_hip.x).abs());
+        }
+        
+        // Knee alignment: deviation from a straight hip-knee-ankle line in the frontal plane.
+        // The right side is mirrored so a knee caving in reads the same sign on both legs, and
+        // the two sides of a symmetric squat match rather than cancel
+        let frontal_knee = calculate_plane_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftKnee.on_side(side)),
+            &skeleton.point(Landmark::LeftAnkle.on_side(side)),
+            MovementPlane::Frontal
+        );
+        if let Some(frontal_knee) = frontal_knee {
+            let knee_alignment = (180.0 - frontal_knee.abs()) * frontal_knee.signum();
+            angles.insert("knee_alignment".to_string(), match side {
+                Side::Left => knee_alignment,
+                Side::Right => -knee_alignment,
+            });
+        }
+        
+        angles
+    }
+
+    pub fn check_quad_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.quad_exercises.get(exercise_id) {
+            // Check knee flexion range
+            if let Some(knee) = angles.get("knee_flexion").filter(|_| at_range_end(profile.knee_flexion_range, position)) {
+                if *knee < profile.knee_flexion_range.0 {
+                    errors.push(FormIssue::new("insufficient_knee_bend", Severity::Warning, format!(
+                        "Insufficient knee bend ({}° < {}°)",
+                        knee.round(), profile.knee_flexion_range.0
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_flexion_range.0, profile.knee_flexion_range.1));
+                }
+                if *knee > profile.knee_flexion_range.1 {
+                    errors.push(FormIssue::new("excessive_knee_flexion", Severity::Warning, format!(
+                        "Excessive knee flexion ({}° > {}°)",
+                        knee.round(), profile.knee_flexion_range.1
+                    ))
+                        .measured("knee_flexion", *knee)
+                        .target(profile.knee_flexion_range.0, profile.knee_flexion_range.1));
+                }
+            }
+            
+            // Check hip depth
+            if let Some(hip) = angles.get("hip_depth").filter(|_| at_range_end(profile.hip_depth_range, position)) {
+                if *hip < profile.hip_depth_range.0 {
+                    errors.push(FormIssue::new("insufficient_depth", Severity::Warning, format!(
+                        "Insufficient depth ({}° < {}°)",
+                        hip.round(), profile.hip_depth_range.0
+                    ))
+                        .measured("hip_depth", *hip)
+                        .target(profile.hip_depth_range.0, profile.hip_depth_range.1));
+                }
+                if *hip > profile.hip_depth_range.1 {
+                    errors.push(FormIssue::new("over_depth", Severity::Warning, format!(
+                        "Over-depth ({}° > {}°)",
+                        hip.round(), profile.hip_depth_range.1
+                    ))
+                        .measured("hip_depth", *hip)
+                        .target(profile.hip_depth_range.0, profile.hip_depth_range.1));
+                }
+            }
+            
+            // Check torso lean, leaning too far is unsafe anywhere in the rep
+            if let Some(torso) = angles.get("torso_lean") {
+                if *torso < profile.torso_lean_range.0 {
+                    errors.push(FormIssue::new("excessive_forward_lean", Severity::Critical, format!(
+                        "Excessive forward lean ({}° < {}°)",
+                        torso.round(), profile.torso_lean_range.0
+                    ))
+                        .measured("torso_lean", *torso)
+                        .target(profile.torso_lean_range.0, profile.torso_lean_range.1));
+                }
+                if at_range_end(profile.torso_lean_range, position) && *torso > profile.torso_lean_range.1 {
+                    errors.push(FormIssue::new("overly_upright", Severity::Info, format!(
+                        "Overly upright ({}° > {}°)",
+                        torso.round(), profile.torso_lean_range.1
+                    ))
+                        .measured("torso_lean", *torso)
+                        .target(profile.torso_lean_range.0, profile.torso_lean_range.1));
+                }
+            }
+            
+            // Check knee alignment
+            if let Some(alignment) = angles.get("knee_alignment") {
+                if alignment.abs() > 15.0 {
+                    errors.push(FormIssue::new("knee_valgus", Severity::Critical, "Knee valgus/varus detected")
+                        .measured("knee_alignment", *alignment)
+                        .target(-15.0, 15.0));
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_quad_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.quad_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Knee flexion component (40% weight)
+            if let Some(knee) = angles.get("knee_flexion") {
+                let knee_norm = (*knee - profile.knee_flexion_range.0) / 
+                               (profile.knee_flexion_range.1 - profile.knee_flexion_range.0);
+                score += 0.4 * knee_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.4;
+            }
+            
+            // Hip depth component (30% weight)
+            if let Some(hip) = angles.get("hip_depth") {
+                let hip_norm = (*hip - profile.hip_depth_range.0) / 
+                              (profile.hip_depth_range.1 - profile.hip_depth_range.0);
+                score += 0.3 * hip_norm.clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Muscle emphasis component (20% weight)
+            score += match profile.emphasis {
+                QuadEmphasis::VastusLateralis => {
+                    if let Some(stance) = angles.get("stance_width") {
+                        0.2 * (stance * profile.stance_width_factor).clamp(0.0, 1.0)
+                    } else { hidden_weight += 0.2; 0.0 }
+                },
+                QuadEmphasis::VastusMedialis => {
+                    if let Some(alignment) = angles.get("knee_alignment") {
+                        0.2 * (1.0 - (alignment.abs() / 30.0)).clamp(0.0, 1.0)
+                    } else { hidden_weight += 0.2; 0.0 }
+                },
+                QuadEmphasis::RectusFemoris => {
+                    if let Some(torso) = angles.get("torso_lean") {
+                        0.2 * ((torso - 60.0) / 30.0).clamp(0.0, 1.0)
+                    } else { hidden_weight += 0.2; 0.0 }
+                },
+                QuadEmphasis::All => 0.2
+            };
+            
+            // Stance width component (10% weight)
+            if let Some(stance) = angles.get("stance_width") {
+                let stance_dev = (stance - profile.stance_width_factor).abs();
+                score += 0.1 * (1.0 - stance_dev).clamp(0.0, 1.0);
+            } else {
+                hidden_weight += 0.1;
+            }
+            
+            visible_score(score, hidden_weight).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("bulgarian-splits", "narrow_split_stance", r#"stance_width < 0.2 => "Maintain proper split stance width""#),
+    ("front-squats", "torso_not_upright", r#"torso_lean < 75 => "Maintain upright torso position""#),
+    ("side-squats", "insufficient_lateral_shift", r#"abs(knee_alignment) < 10 => info "Intentional lateral movement required""#),
+];
+
+impl MuscleGroupAnalyzer for QuadAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "quads"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.quad_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_quad_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_quad_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_quad_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.quad_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose_detection::{angle_map, at_rom};
+    use crate::skeleton::{Keypoint, KeypointLayout};
+    use crate::symmetry::SymmetryTracker;
+
+    fn codes(issues: Vec<FormIssue>) -> Vec<String> {
+        issues.into_iter().map(|issue| issue.code).collect()
+    }
+
+    #[test]
+    fn standing_between_squats_is_not_missed_depth() {
+        let analyzer = QuadAnalyzer::new();
+        let standing = angle_map(&[("knee_flexion", 178.0), ("hip_depth", 176.0), ("torso_lean", 170.0), ("knee_alignment", 2.0)]);
+        assert!(analyzer.check_quad_form("squat", &standing, at_rom(1.0)).is_empty());
+        // The same angles at the bottom are a squat that never went down
+        assert!(codes(analyzer.check_quad_form("squat", &standing, at_rom(0.0))).contains(&"over_depth".to_string()));
+
+        let parallel = angle_map(&[("knee_flexion", 100.0), ("hip_depth", 110.0), ("torso_lean", 75.0), ("knee_alignment", 3.0)]);
+        assert!(analyzer.check_quad_form("squat", &parallel, at_rom(0.0)).is_empty());
+
+        let folded = angle_map(&[("knee_flexion", 178.0), ("hip_depth", 176.0), ("torso_lean", 50.0)]);
+        assert_eq!(codes(analyzer.check_quad_form("squat", &folded, at_rom(1.0))), ["excessive_forward_lean"]);
+    }
+
+    // Front view of both legs, knees `cave` pixels inside the hip-ankle line
+    fn caving_squat(depth: f32, cave: f32) -> Skeleton {
+        Skeleton::from_landmarks(KeypointLayout::MoveNet, |landmark| {
+            let (offset, y) = match landmark {
+                Landmark::LeftHip | Landmark::RightHip => (20.0, 280.0 + 80.0 * depth),
+                Landmark::LeftKnee | Landmark::RightKnee => (25.0 - cave, 400.0),
+                Landmark::LeftAnkle | Landmark::RightAnkle => (22.0, 510.0),
+                Landmark::LeftShoulder | Landmark::RightShoulder => (30.0, 120.0 + 80.0 * depth),
+                _ => (0.0, 60.0 + 80.0 * depth),
+            };
+            // The lifter's left is on the right of the image
+            let x = if landmark.name().starts_with("right") { 200.0 - offset } else { 200.0 + offset };
+            Some(Keypoint { x, y, z: None, confidence: 0.9 })
+        })
+    }
+
+    #[test]
+    fn symmetric_valgus_reads_the_same_on_both_knees() {
+        let analyzer = QuadAnalyzer::new();
+        let angles = analyzer.calculate_quad_angles(&caving_squat(1.0, 20.0));
+        let (left, right) = (angles["left_knee_alignment"], angles["right_knee_alignment"]);
+        assert!(left > 5.0, "{}", left);
+        assert!((left - right).abs() < 1e-3, "left {} right {}", left, right);
+        assert!((angles["knee_alignment"] - left).abs() < 1e-3);
+
+        let mut tracker = SymmetryTracker::new();
+        for frame in 0..=60 {
+            let t = frame as f32 / 30.0;
+            let depth = 0.5 - 0.5 * (t * std::f32::consts::PI).cos();
+            tracker.update(&analyzer.calculate_quad_angles(&caving_squat(depth, 20.0 * depth)), t);
+        }
+        assert!(tracker.check_symmetry(0.1).is_empty(), "{:?}", tracker.check_symmetry(0.1));
+    }
+}
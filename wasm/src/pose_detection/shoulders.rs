@@ -0,0 +1,421 @@
+// wasm/src/pose_detection/shoulders.rs
+use std::collections::HashMap;
+
+use super::{bilateral_angles, calculate_angle, calculate_plane_angle, insert_angle, MovementPlane, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct ShoulderAnalyzer {
+    shoulder_exercises: HashMap<&'static str, ShoulderExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct ShoulderExerciseProfile {
+    plane_of_motion: MovementPlane,
+    rotation_type: RotationType,
+    rom_requirements: (f32, f32), // Degrees of movement
+    scapular_behavior: ScapularSetting,
+    stability_factor: f32,
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum RotationType {
+    Internal,
+    External,
+    Neutral,
+    Dynamic,
+}
+
+#[derive(Debug, Clone)]
+enum ScapularSetting {
+    Retracted,
+    Protracted,
+    Dynamic,
+    Elevated,
+}
+
+impl ShoulderAnalyzer {
+    pub fn new() -> Self {
+        let mut shoulder_exercises = HashMap::new();
+
+        // 1. Lateral Dumbbell Raises
+        shoulder_exercises.insert("lateral-dumbbell-raises", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Frontal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (60.0, 120.0),
+            scapular_behavior: ScapularSetting::Elevated,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 2. Frontal Dumbbell Raises
+        shoulder_exercises.insert("frontal-dumbbell-raises", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Sagittal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (70.0, 130.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            stability_factor: 1.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 3. Dumbbell Shrugs
+        shoulder_exercises.insert("dumbbell-shrugs", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Sagittal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (160.0, 190.0), // Near-vertical
+            scapular_behavior: ScapularSetting::Elevated,
+            stability_factor: 0.9,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Bench Dumbbell Raises
+        shoulder_exercises.insert("bench-dumbbell-raises", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Scapular,
+            rotation_type: RotationType::External,
+            rom_requirements: (90.0, 150.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Exterior Dumbbell Raises
+        shoulder_exercises.insert("exterior-dumbbell-raises", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Transverse,
+            rotation_type: RotationType::External,
+            rom_requirements: (80.0, 140.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            stability_factor: 1.4,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 6. Arnold Press
+        shoulder_exercises.insert("arnold-press", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Scapular,
+            rotation_type: RotationType::Dynamic,
+            rom_requirements: (90.0, 180.0),
+            scapular_behavior: ScapularSetting::Dynamic,
+            stability_factor: 1.5,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Military Press
+        shoulder_exercises.insert("military-press", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Sagittal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (90.0, 180.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            stability_factor: 1.6,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 8. Handstand Pushups
+        shoulder_exercises.insert("handstand-pushups", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Sagittal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (160.0, 220.0), // Hyperextension
+            scapular_behavior: ScapularSetting::Protracted,
+            stability_factor: 1.8,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Overhead Press
+        shoulder_exercises.insert("overhead-press", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Sagittal,
+            rotation_type: RotationType::Neutral,
+            rom_requirements: (90.0, 180.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            stability_factor: 1.6,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 10. Rear Delt Fly
+        shoulder_exercises.insert("rear-delt-fly", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Transverse,
+            rotation_type: RotationType::External,
+            rom_requirements: (80.0, 140.0),
+            scapular_behavior: ScapularSetting::Retracted,
+            stability_factor: 1.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Upright Rows
+        shoulder_exercises.insert("upright-rows", ShoulderExerciseProfile {
+            plane_of_motion: MovementPlane::Frontal,
+            rotation_type: RotationType::Internal,
+            rom_requirements: (60.0, 100.0), // Stop around shoulder height
+            scapular_behavior: ScapularSetting::Elevated,
+            stability_factor: 1.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        ShoulderAnalyzer { shoulder_exercises }
+    }
+
+    pub fn calculate_shoulder_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_shoulder_side_angles(skeleton, side))
+    }
+
+    fn calculate_shoulder_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Shoulder abduction (hip-shoulder-elbow)
+        insert_angle(&mut angles, "abduction", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Shoulder flexion (spine-shoulder-elbow)
+        insert_angle(&mut angles, "flexion", calculate_angle(
+            &skeleton.point(Landmark::Neck),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Scapular movement (shoulder-hip-opposite shoulder)
+        insert_angle(&mut angles, "scapular_movement", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Rotation (elbow-shoulder-wrist)
+        insert_angle(&mut angles, "rotation", calculate_angle(
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Stability (shoulder-elbow-wrist)
+        insert_angle(&mut angles, "stability", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Arm angle projected onto each plane of motion, only meaningful with depth.
+        // Transverse is measured against the shoulder line (horizontal abduction),
+        // the others against the torso
+        if skeleton.has_depth() {
+            for plane in MovementPlane::ALL {
+                let reference = match plane {
+                    MovementPlane::Transverse => Landmark::RightShoulder.on_side(side),
+                    _ => Landmark::LeftHip.on_side(side),
+                };
+                let angle = calculate_plane_angle(
+                    &skeleton.point(reference),
+                    &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+                    &skeleton.point(Landmark::LeftElbow.on_side(side)),
+                    plane
+                );
+                insert_angle(&mut angles, &format!("shoulder_{}", plane.name()), angle.map(f32::abs));
+            }
+        }
+        
+        angles
+    }
+
+    pub fn check_shoulder_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.shoulder_exercises.get(exercise_id) {
+            // Check primary plane of motion
+            if let Some(angle) = plane_angle(profile.plane_of_motion, angles) {
+                let (motion, overshoot) = match profile.plane_of_motion {
+                    MovementPlane::Frontal => ("abduction", "Over-abduction"),
+                    MovementPlane::Sagittal => ("flexion", "Over-flexion"),
+                    MovementPlane::Scapular => ("scaption", "Over-elevation"),
+                    MovementPlane::Transverse => ("horizontal abduction", "Over-extension"),
+                };
+                if angle < profile.rom_requirements.0 {
+                    errors.push(FormIssue::new(format!("insufficient_{}", motion.replace(' ', "_")), Severity::Warning, format!(
+                        "Insufficient {} ({}° < {}°)",
+                        motion, angle.round(), profile.rom_requirements.0
+                    ))
+                        .measured("shoulder", angle)
+                        .target(profile.rom_requirements.0, profile.rom_requirements.1));
+                }
+                if angle > profile.rom_requirements.1 {
+                    errors.push(FormIssue::new(overshoot.to_lowercase().replace('-', "_"), Severity::Warning, format!(
+                        "{} ({}° > {}°)",
+                        overshoot, angle.round(), profile.rom_requirements.1
+                    ))
+                        .measured("shoulder", angle)
+                        .target(profile.rom_requirements.0, profile.rom_requirements.1));
+                }
+            }
+            
+            // Check scapular behavior
+            match profile.scapular_behavior {
+                ScapularSetting::Retracted => {
+                    if let Some(scap) = angles.get("scapular_movement") {
+                        if *scap < 160.0 {
+                            errors.push(FormIssue::new("scapulae_not_retracted", Severity::Warning, "Shoulders should remain retracted")
+                                .measured("scapular_movement", *scap)
+                                .at_least(160.0));
+                        }
+                    }
+                },
+                ScapularSetting::Protracted => {
+                    if let Some(scap) = angles.get("scapular_movement") {
+                        if *scap > 200.0 {
+                            errors.push(FormIssue::new("excessive_protraction", Severity::Warning, "Control scapular protraction")
+                                .measured("scapular_movement", *scap)
+                                .at_most(200.0));
+                        }
+                    }
+                },
+                // Elevation is only held at the top, the arms pass through lower angles on every rep
+                ScapularSetting::Elevated if position.at_top() => {
+                    if let Some(abduction) = angles.get("abduction") {
+                        if *abduction < 150.0 {
+                            errors.push(FormIssue::new("shoulders_not_elevated", Severity::Warning, "Maintain shoulder elevation")
+                                .measured("abduction", *abduction)
+                                .at_least(150.0));
+                        }
+                    }
+                },
+                _ => {}
+            }
+            
+            // Check rotation
+            match profile.rotation_type {
+                RotationType::Internal => {
+                    if let Some(rot) = angles.get("rotation") {
+                        if *rot > 45.0 {
+                            errors.push(FormIssue::new("lost_internal_rotation", Severity::Warning, "Maintain internal rotation")
+                                .measured("rotation", *rot)
+                                .at_most(45.0));
+                        }
+                    }
+                },
+                RotationType::External => {
+                    if let Some(rot) = angles.get("rotation") {
+                        if *rot < 135.0 {
+                            errors.push(FormIssue::new("lost_external_rotation", Severity::Warning, "Maintain external rotation")
+                                .measured("rotation", *rot)
+                                .at_least(135.0));
+                        }
+                    }
+                },
+                RotationType::Dynamic => {
+                    if let Some(rot) = angles.get("rotation") {
+                        if *rot < 45.0 || *rot > 135.0 {
+                            errors.push(FormIssue::new("uncontrolled_rotation", Severity::Warning, "Control rotational movement")
+                                .measured("rotation", *rot)
+                                .target(45.0, 135.0));
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_shoulder_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.shoulder_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Primary movement component (50% weight)
+            score += match plane_angle(profile.plane_of_motion, angles) {
+                Some(angle) => 0.5 * ((angle - profile.rom_requirements.0) / 
+                        (profile.rom_requirements.1 - profile.rom_requirements.0)).clamp(0.0, 1.0),
+                None => 0.25 // Planes we can't see in 2D get base value
+            };
+            
+            // Scapular control component (30% weight)
+            score += match profile.scapular_behavior {
+                ScapularSetting::Retracted => {
+                    angles.get("scapular_movement")
+                        .map_or_else(|| { hidden_weight += 0.3; 0.0 }, |s| 0.3 * (1.0 - (s - 180.0).abs() / 30.0).clamp(0.0, 1.0))
+                },
+                ScapularSetting::Protracted => {
+                    angles.get("scapular_movement")
+                        .map_or_else(|| { hidden_weight += 0.3; 0.0 }, |s| 0.3 * (1.0 - (s - 190.0).abs() / 20.0).clamp(0.0, 1.0))
+                },
+                ScapularSetting::Elevated => {
+                    angles.get("abduction")
+                        .map_or_else(|| { hidden_weight += 0.3; 0.0 }, |a| 0.3 * ((a - 150.0) / 30.0).clamp(0.0, 1.0))
+                },
+                _ => 0.15 // Dynamic gets base value
+            };
+            
+            // Rotation component (20% weight)
+            score += match profile.rotation_type {
+                RotationType::Internal => {
+                    angles.get("rotation")
+                        .map_or_else(|| { hidden_weight += 0.2; 0.0 }, |r| 0.2 * (1.0 - r / 45.0).clamp(0.0, 1.0))
+                },
+                RotationType::External => {
+                    angles.get("rotation")
+                        .map_or_else(|| { hidden_weight += 0.2; 0.0 }, |r| 0.2 * ((r - 90.0) / 45.0).clamp(0.0, 1.0))
+                },
+                RotationType::Dynamic => {
+                    angles.get("rotation")
+                        .map_or_else(|| { hidden_weight += 0.2; 0.0 }, |r| 0.2 * (1.0 - (r - 90.0).abs() / 45.0).clamp(0.0, 1.0))
+                },
+                _ => 0.1 // Neutral gets base value
+            };
+            
+            (visible_score(score, hidden_weight) * profile.stability_factor).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("arnold-press", "incomplete_rotation", r#"rotation not in 45..135 => info "Complete rotational movement""#),
+    ("handstand-pushups", "incomplete_lockout", r#"flexion < 170 => "Press to full extension""#),
+];
+
+impl MuscleGroupAnalyzer for ShoulderAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "shoulders"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.shoulder_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_shoulder_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_shoulder_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_shoulder_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.shoulder_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
+
+// Arm angle in a plane of motion: the true projection when the pose model gave us
+// depth, otherwise the closest 2D measurement (none for scapular/transverse)
+fn plane_angle(plane: MovementPlane, angles: &HashMap<String, f32>) -> Option<f32> {
+    angles
+        .get(&format!("shoulder_{}", plane.name()))
+        .or_else(|| match plane {
+            MovementPlane::Frontal => angles.get("abduction"),
+            MovementPlane::Sagittal => angles.get("flexion"),
+            _ => None,
+        })
+        .copied()
+}
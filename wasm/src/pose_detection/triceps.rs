@@ -0,0 +1,337 @@
+// wasm/src/pose_detection/triceps.rs
+use std::collections::HashMap;
+
+use super::{bilateral_angles, calculate_angle, insert_angle, visible_score, MuscleGroupAnalyzer};
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepPosition;
+use crate::rules::{self, Rule};
+use crate::skeleton::{Landmark, Side, Skeleton};
+
+#[derive(Debug)]
+pub struct TricepsAnalyzer {
+    triceps_exercises: HashMap<&'static str, TricepsExerciseProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct TricepsExerciseProfile {
+    elbow_extension_range: (f32, f32),   // Optimal extension angles
+    shoulder_stabilization: f32,         // 0-1 how much shoulder should stay fixed
+    humeral_position: HumeralPosition,   // Upper arm orientation
+    lockout_requirement: f32,            // 0-1 how strict full extension is
+    compound_factor: f32,                // How much other muscles are involved
+    symmetry_tolerance: Option<f32>, // Allowed left/right ROM or timing divergence, None for one-sided movements
+}
+
+#[derive(Debug, Clone)]
+enum HumeralPosition {
+    Overhead,
+    Neutral,
+    Flexed(f32),  // Specific flexion angle
+    Extended(f32), // Specific extension angle
+}
+
+impl TricepsAnalyzer {
+    pub fn new() -> Self {
+        let mut triceps_exercises = HashMap::new();
+
+        // 1. Closed-Grip Barbell
+        triceps_exercises.insert("closed-grip-barbell", TricepsExerciseProfile {
+            elbow_extension_range: (30.0, 180.0),
+            shoulder_stabilization: 0.8,
+            humeral_position: HumeralPosition::Flexed(45.0),
+            lockout_requirement: 0.9,
+            compound_factor: 0.7,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 2. Lateral Barbell Extensions
+        triceps_exercises.insert("lateral-barbell-extensions", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.9,
+            humeral_position: HumeralPosition::Flexed(90.0),
+            lockout_requirement: 0.95,
+            compound_factor: 0.3,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 3. Diamond Push-ups
+        triceps_exercises.insert("diamond-push-ups", TricepsExerciseProfile {
+            elbow_extension_range: (60.0, 180.0),
+            shoulder_stabilization: 0.6,
+            humeral_position: HumeralPosition::Neutral,
+            lockout_requirement: 0.8,
+            compound_factor: 0.5,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 4. Dumbbell Dips
+        triceps_exercises.insert("dumbbell-dips", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.7,
+            humeral_position: HumeralPosition::Extended(20.0),
+            lockout_requirement: 0.85,
+            compound_factor: 0.6,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 5. Bench Dips
+        triceps_exercises.insert("bench-dips", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.5,
+            humeral_position: HumeralPosition::Extended(45.0),
+            lockout_requirement: 0.75,
+            compound_factor: 0.4,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 6. Lateral Dumbbell Raises (Triceps Variation)
+        triceps_exercises.insert("lateral-dumbbell-raises", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.8,
+            humeral_position: HumeralPosition::Flexed(90.0),
+            lockout_requirement: 0.9,
+            compound_factor: 0.3,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 7. Hammer Dumbbell Raises
+        triceps_exercises.insert("hammer-dumbbell-raises", TricepsExerciseProfile {
+            elbow_extension_range: (0.0, 180.0), // Full ROM
+            shoulder_stabilization: 0.85,
+            humeral_position: HumeralPosition::Overhead,
+            lockout_requirement: 0.95,
+            compound_factor: 0.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 8. Forearm Push-ups
+        triceps_exercises.insert("forearm-push-ups", TricepsExerciseProfile {
+            elbow_extension_range: (60.0, 180.0),
+            shoulder_stabilization: 0.9,
+            humeral_position: HumeralPosition::Neutral,
+            lockout_requirement: 0.7,
+            compound_factor: 0.8,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 9. Barbell Overhead
+        triceps_exercises.insert("barbell-overhead", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.95,
+            humeral_position: HumeralPosition::Overhead,
+            lockout_requirement: 1.0,
+            compound_factor: 0.5,
+            symmetry_tolerance: Some(0.1),
+        });
+
+        // 10. Tricep Rope Pushdown
+        triceps_exercises.insert("tricep-rope-pushdown", TricepsExerciseProfile {
+            elbow_extension_range: (90.0, 180.0),
+            shoulder_stabilization: 0.75,
+            humeral_position: HumeralPosition::Flexed(45.0),
+            lockout_requirement: 0.85,
+            compound_factor: 0.1,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 11. Skull Crushers
+        triceps_exercises.insert("skull-crushers", TricepsExerciseProfile {
+            elbow_extension_range: (60.0, 180.0),
+            shoulder_stabilization: 0.9,
+            humeral_position: HumeralPosition::Flexed(90.0),
+            lockout_requirement: 0.9,
+            compound_factor: 0.2,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        // 12. Close-Grip Bench Press
+        triceps_exercises.insert("close-grip-bench-press", TricepsExerciseProfile {
+            elbow_extension_range: (60.0, 180.0),
+            shoulder_stabilization: 0.8,
+            humeral_position: HumeralPosition::Flexed(45.0),
+            lockout_requirement: 0.9,
+            compound_factor: 0.7,
+            symmetry_tolerance: Some(0.15),
+        });
+
+        TricepsAnalyzer { triceps_exercises }
+    }
+
+    pub fn calculate_triceps_angles(&self, skeleton: &Skeleton) -> HashMap<String, f32> {
+        bilateral_angles(|side| self.calculate_triceps_side_angles(skeleton, side))
+    }
+
+    fn calculate_triceps_side_angles(&self, skeleton: &Skeleton, side: Side) -> HashMap<String, f32> {
+        let mut angles = HashMap::new();
+        
+        // Elbow extension (shoulder-elbow-wrist)
+        insert_angle(&mut angles, "elbow_extension", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side))
+        ));
+        
+        // Shoulder position (hip-shoulder-elbow)
+        insert_angle(&mut angles, "shoulder_position", calculate_angle(
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftElbow.on_side(side))
+        ));
+        
+        // Shoulder stability (shoulder-hip-opposite shoulder)
+        insert_angle(&mut angles, "shoulder_stability", calculate_angle(
+            &skeleton.point(Landmark::LeftShoulder.on_side(side)),
+            &skeleton.point(Landmark::LeftHip.on_side(side)),
+            &skeleton.point(Landmark::RightShoulder.on_side(side))
+        ));
+        
+        // Wrist angle (elbow-wrist-knuckle)
+        insert_angle(&mut angles, "wrist_angle", calculate_angle(
+            &skeleton.point(Landmark::LeftElbow.on_side(side)),
+            &skeleton.point(Landmark::LeftWrist.on_side(side)),
+            &skeleton.point(Landmark::LeftIndex.on_side(side))
+        ));
+        
+        angles
+    }
+
+    pub fn check_triceps_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        
+        if let Some(profile) = self.triceps_exercises.get(exercise_id) {
+            // Check elbow extension range
+            if let Some(extension) = angles.get("elbow_extension") {
+                if *extension < profile.elbow_extension_range.0 {
+                    errors.push(FormIssue::new("insufficient_extension", Severity::Warning, format!(
+                        "Insufficient extension ({}° < {}°)",
+                        extension.round(), profile.elbow_extension_range.0
+                    ))
+                        .measured("elbow_extension", *extension)
+                        .target(profile.elbow_extension_range.0, profile.elbow_extension_range.1));
+                }
+                if *extension > profile.elbow_extension_range.1 {
+                    errors.push(FormIssue::new("elbow_over_extension", Severity::Critical, format!(
+                        "Over-extension ({}° > {}°)",
+                        extension.round(), profile.elbow_extension_range.1
+                    ))
+                        .measured("elbow_extension", *extension)
+                        .target(profile.elbow_extension_range.0, profile.elbow_extension_range.1));
+                }
+                
+                // Check lockout requirement, only once the rep has reached its top
+                if position.at_top() && *extension < 170.0 && profile.lockout_requirement > 0.8 {
+                    errors.push(FormIssue::new("incomplete_lockout", Severity::Warning, "Incomplete lockout")
+                        .measured("elbow_extension", *extension)
+                        .at_least(170.0));
+                }
+            }
+            
+            // Check shoulder position
+            if let Some(shoulder_pos) = angles.get("shoulder_position") {
+                match profile.humeral_position {
+                    HumeralPosition::Overhead if *shoulder_pos < 160.0 => {
+                        errors.push(FormIssue::new("elbows_dropped_overhead", Severity::Warning, "Maintain overhead position")
+                            .measured("shoulder_position", *shoulder_pos)
+                            .at_least(160.0));
+                    },
+                    HumeralPosition::Neutral if (*shoulder_pos - 90.0).abs() > 30.0 => {
+                        errors.push(FormIssue::new("shoulders_not_neutral", Severity::Warning, "Keep shoulders neutral")
+                            .measured("shoulder_position", *shoulder_pos)
+                            .target(60.0, 120.0));
+                    },
+                    HumeralPosition::Flexed(target) if (*shoulder_pos - target).abs() > 15.0 => {
+                        errors.push(FormIssue::new("shoulder_flexion_off_target", Severity::Warning, format!("Maintain {}° shoulder flexion", target))
+                            .measured("shoulder_position", *shoulder_pos)
+                            .target(target - 15.0, target + 15.0));
+                    },
+                    HumeralPosition::Extended(target) if (*shoulder_pos - target).abs() > 15.0 => {
+                        errors.push(FormIssue::new("shoulder_extension_off_target", Severity::Warning, format!("Maintain {}° shoulder extension", target))
+                            .measured("shoulder_position", *shoulder_pos)
+                            .target(target - 15.0, target + 15.0));
+                    },
+                    _ => {}
+                }
+            }
+            
+            // Check shoulder stability
+            if let Some(stability) = angles.get("shoulder_stability") {
+                let tolerance = (1.0 - profile.shoulder_stabilization) * 30.0;
+                if (stability - 180.0).abs() > tolerance {
+                    errors.push(FormIssue::new("shoulder_swing", Severity::Warning, "Excessive shoulder movement")
+                        .measured("shoulder_stability", *stability)
+                        .target(180.0 - tolerance, 180.0 + tolerance));
+                }
+            }
+        }
+        
+        errors
+    }
+
+    pub fn calculate_triceps_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>) -> f32 {
+        if let Some(profile) = self.triceps_exercises.get(exercise_id) {
+            let mut score = 0.0;
+            let mut hidden_weight = 0.0;
+            
+            // Elbow extension component (60% weight)
+            if let Some(extension) = angles.get("elbow_extension") {
+                let ext_norm = (*extension - profile.elbow_extension_range.0) / 
+                              (profile.elbow_extension_range.1 - profile.elbow_extension_range.0);
+                score += 0.6 * ext_norm.clamp(0.0, 1.0) * profile.lockout_requirement;
+            } else {
+                hidden_weight += 0.6;
+            }
+            
+            // Shoulder stability component (30% weight)
+            if let Some(stability) = angles.get("shoulder_stability") {
+                let stab_score = 1.0 - (stability - 180.0).abs() / 30.0;
+                score += 0.3 * stab_score.clamp(0.0, 1.0) * profile.shoulder_stabilization;
+            } else {
+                hidden_weight += 0.3;
+            }
+            
+            // Compound factor adjustment (10% bonus)
+            score += 0.1 * (1.0 - profile.compound_factor);
+            
+            visible_score(score, hidden_weight).clamp(0.1, 1.0)
+        } else {
+            0.5
+        }
+    }
+}
+
+// Exercise-specific checks
+const FORM_RULES: &[(&str, &str, &str)] = &[
+    ("diamond-push-ups", "bent_wrists", r#"wrist_angle < 150 => "Maintain straight wrist position""#),
+    ("forearm-push-ups", "bent_wrists", r#"wrist_angle < 150 => "Maintain straight wrist position""#),
+    ("tricep-rope-pushdown", "partial_range", r#"elbow_extension < 100 => "Extend through full range""#),
+];
+
+impl MuscleGroupAnalyzer for TricepsAnalyzer {
+    fn muscle_group(&self) -> &str {
+        "triceps"
+    }
+
+    fn exercise_ids(&self) -> Vec<&str> {
+        self.triceps_exercises.keys().copied().collect()
+    }
+
+    fn calculate_angles(&self, _exercise_id: &str, skeleton: &Skeleton) -> HashMap<String, f32> {
+        self.calculate_triceps_angles(skeleton)
+    }
+
+    fn check_form(&self, exercise_id: &str, angles: &HashMap<String, f32>, position: RepPosition) -> Vec<FormIssue> {
+        self.check_triceps_form(exercise_id, angles, position)
+    }
+
+    fn calculate_engagement(&self, exercise_id: &str, angles: &HashMap<String, f32>, _position: RepPosition) -> f32 {
+        self.calculate_triceps_engagement(exercise_id, angles)
+    }
+
+    fn symmetry_tolerance(&self, exercise_id: &str) -> Option<f32> {
+        self.triceps_exercises.get(exercise_id).and_then(|profile| profile.symmetry_tolerance)
+    }
+
+    fn form_rules(&self, exercise_id: &str) -> Vec<Rule> {
+        rules::from_table(FORM_RULES, exercise_id)
+    }
+}
@@ -0,0 +1,383 @@
+// wasm/src/recognition.rs
+// Works out which supported exercise is being performed from the last few seconds of
+// movement, so the app can confirm the pick ("Looks like goblet squats") or flag the wrong
+// one. Every built-in exercise is scored on the trajectories of its own group's angles: how
+// far its primary joint travels inside the profile's range, whether it goes back and forth,
+// whether the joints it doesn't use stay still and whether the torso is oriented the way the
+// movement is done. Held exercises are scored on staying in their hold ranges, multi-stage
+// ones also on passing through their stages. Angles can't tell a goblet squat from a front
+// squat, so exercises scoring about as well as the best one are reported next to it
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::analyzer::SUPPORTED_EXERCISES;
+use crate::hold::HoldRange;
+use crate::pose_detection::PoseAnalyzer;
+use crate::rep_counter::{self, ExerciseProfile, MovementPattern, MovementPhase, RepPosition};
+use crate::rules::RuleContext;
+use crate::sequence::Stage;
+use crate::skeleton::Skeleton;
+
+// Movement looked at, in milliseconds
+const WINDOW_MS: f64 = 4000.0;
+// Anything shorter is too little to go on
+const MIN_WINDOW_MS: f64 = 1500.0;
+// Scoring every exercise each frame is wasted work, the answer can't change that fast
+const CLASSIFY_INTERVAL_MS: f64 = 500.0;
+// Frames the primary joint has to be visible in, as a share of the window
+const MIN_COVERAGE: f32 = 0.5;
+// Degrees of slack around profile ranges, the profiles are written for a clean side view
+const RANGE_SLACK: f32 = 15.0;
+// A joint moving less than this, in degrees, is holding still
+const MIN_MOTION: f32 = 15.0;
+// Share of the profile range a working joint has to cover
+const MIN_TRAVEL: f32 = 0.5;
+// Share of the best score an exercise needs to be listed as an alternative
+const ALTERNATIVE_SHARE: f32 = 0.8;
+const MAX_ALTERNATIVES: usize = 5;
+// Below this the recognition is a guess, not worth telling the user about
+pub const MIN_CONFIDENCE: f32 = 0.5;
+// Joints of the full body group every exercise is checked against for moving when it
+// shouldn't
+const REFERENCE_JOINTS: &[&str] = &["knee", "hip", "elbow", "shoulder"];
+// Torso against vertical (full body "torso_incline") for standing or seated movements and
+// for ones done lying or on all fours
+const UPRIGHT: (f32, f32) = (0.0, 40.0);
+const LYING: (f32, f32) = (50.0, 130.0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub exercise_id: String,
+    pub score: f32, // 0-1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recognition {
+    pub exercise_id: String,
+    pub muscle_group: String,
+    // Score of the best match, less half the score of the best one from another muscle group
+    pub confidence: f32,
+    pub alternatives: Vec<Candidate>, // Exercises it can't be told apart from, best first
+}
+
+#[derive(Debug)]
+struct Template {
+    exercise_id: &'static str,
+    group: usize, // Index into the per-group angles of a frame
+    profile: ExerciseProfile,
+    hold_ranges: Vec<HoldRange>,
+    stages: Vec<Stage>,
+}
+
+// Percentiles rather than extremes, a glitched frame shouldn't stretch the range
+#[derive(Debug, Default)]
+struct Trajectory {
+    coverage: f32,
+    low: f32,
+    high: f32,
+    reversals: u32,
+    samples: Vec<f32>,
+}
+
+impl Trajectory {
+    fn new(samples: Vec<f32>, frames: usize) -> Self {
+        if samples.is_empty() {
+            return Trajectory::default();
+        }
+        let mut sorted = samples.clone();
+        sorted.sort_by(f32::total_cmp);
+        let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+        let (low, high) = (percentile(0.1), percentile(0.9));
+
+        Trajectory {
+            coverage: samples.len() as f32 / frames as f32,
+            low,
+            high,
+            reversals: reversals(&samples, MIN_MOTION.max(0.3 * (high - low))),
+            samples,
+        }
+    }
+
+    fn amplitude(&self) -> f32 {
+        self.high - self.low
+    }
+
+    // Share of samples within [min, max] give or take RANGE_SLACK
+    fn fit(&self, min: f32, max: f32) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let inside = self.samples.iter().filter(|&&angle| angle >= min - RANGE_SLACK && angle <= max + RANGE_SLACK).count();
+        inside as f32 / self.samples.len() as f32
+    }
+}
+
+// Changes of direction by at least `threshold` degrees. The first movement away from the
+// start sets the direction without counting, so one down-and-up rep makes one reversal
+fn reversals(samples: &[f32], threshold: f32) -> u32 {
+    let Some((&first, rest)) = samples.split_first() else {
+        return 0;
+    };
+    let mut count = 0;
+    let mut rising: Option<bool> = None;
+    let mut extreme = first;
+    for &angle in rest {
+        match rising {
+            None if (angle - extreme).abs() >= threshold => {
+                rising = Some(angle > extreme);
+                extreme = angle;
+            }
+            None => {}
+            Some(true) if angle > extreme => extreme = angle,
+            Some(false) if angle < extreme => extreme = angle,
+            Some(direction) if (angle - extreme).abs() >= threshold => {
+                rising = Some(!direction);
+                extreme = angle;
+                count += 1;
+            }
+            Some(_) => {}
+        }
+    }
+    count
+}
+
+#[derive(Debug)]
+pub struct ExerciseRecognizer {
+    pose_analyzer: PoseAnalyzer,
+    templates: Vec<Template>,
+    reference_group: Option<usize>,
+    window: VecDeque<(f64, Vec<HashMap<String, f32>>)>, // Timestamp in milliseconds, every group's angles
+    last_classified: f64,
+    recognition: Option<Recognition>,
+    scores: HashMap<&'static str, f32>, // Every exercise's score at the last classification
+}
+
+impl ExerciseRecognizer {
+    pub fn new() -> Self {
+        let pose_analyzer = PoseAnalyzer::new();
+        let mut templates: Vec<Template> = Vec::new();
+        for &exercise_id in SUPPORTED_EXERCISES {
+            if templates.iter().any(|template| template.exercise_id == exercise_id) {
+                continue;
+            }
+            let (Some(group), Some(profile)) = (pose_analyzer.group_index(exercise_id), ExerciseProfile::lookup(exercise_id)) else {
+                continue;
+            };
+            let hold_ranges = pose_analyzer.hold_ranges(exercise_id);
+            let stages = pose_analyzer.stages(exercise_id);
+            templates.push(Template { exercise_id, group, profile, hold_ranges, stages });
+        }
+        let reference_group = pose_analyzer.group_named("full_body");
+
+        ExerciseRecognizer {
+            pose_analyzer,
+            templates,
+            reference_group,
+            window: VecDeque::new(),
+            last_classified: f64::NEG_INFINITY,
+            recognition: None,
+            scores: HashMap::new(),
+        }
+    }
+
+    // Timestamp in milliseconds. Returns the latest recognition, None until enough
+    // movement has been seen or when nothing fits
+    pub fn update(&mut self, skeleton: &Skeleton, timestamp: f64) -> Option<&Recognition> {
+        self.window.push_back((timestamp, self.pose_analyzer.group_angles(skeleton)));
+        while self.window.front().is_some_and(|(start, _)| timestamp - start > WINDOW_MS) {
+            self.window.pop_front();
+        }
+
+        let span = self.window.front().map_or(0.0, |(start, _)| timestamp - start);
+        if span >= MIN_WINDOW_MS && timestamp - self.last_classified >= CLASSIFY_INTERVAL_MS {
+            self.classify();
+            self.last_classified = timestamp;
+        }
+        self.recognition.as_ref()
+    }
+
+    // Exercises it can recognize, definitions loaded at runtime aren't among them
+    pub fn knows(&self, exercise_id: &str) -> bool {
+        self.templates.iter().any(|template| template.exercise_id == exercise_id)
+    }
+
+    // Whether the movement fits `exercise_id` about as well as the recognized exercise,
+    // true until there is a recognition
+    pub fn agrees_with(&self, exercise_id: &str) -> bool {
+        let Some(recognition) = &self.recognition else {
+            return true;
+        };
+        let best = self.scores.get(recognition.exercise_id.as_str()).copied().unwrap_or_default();
+        self.scores.get(exercise_id).is_some_and(|&score| score >= best * ALTERNATIVE_SHARE)
+    }
+
+    pub fn recognition(&self) -> Option<&Recognition> {
+        self.recognition.as_ref()
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.last_classified = f64::NEG_INFINITY;
+        self.recognition = None;
+        self.scores.clear();
+    }
+
+    fn classify(&mut self) {
+        let reference: Vec<(&str, Trajectory)> = match self.reference_group {
+            Some(group) => REFERENCE_JOINTS.iter().map(|&joint| (joint, self.trajectory(group, joint))).collect(),
+            None => Vec::new(),
+        };
+        let torso = self.reference_group.map(|group| self.trajectory(group, "torso_incline"));
+
+        let mut scored: Vec<(&Template, f32)> = self
+            .templates
+            .iter()
+            .map(|template| (template, self.score(template, &reference, torso.as_ref())))
+            .filter(|&(_, score)| score > 0.0)
+            .collect();
+        // On a tie the narrower profile is the more specific match, after that
+        // SUPPORTED_EXERCISES order
+        scored.sort_by(|a, b| {
+            let width = |template: &Template| span(&template.profile).1 - span(&template.profile).0;
+            b.1.total_cmp(&a.1).then_with(|| width(a.0).total_cmp(&width(b.0)))
+        });
+
+        let recognition = scored.first().map(|&(best, top)| {
+            let muscle_group = self.pose_analyzer.muscle_group(best.exercise_id).unwrap_or_default();
+            let rival = scored
+                .iter()
+                .find(|(template, _)| self.pose_analyzer.muscle_group(template.exercise_id) != Some(muscle_group))
+                .map_or(0.0, |&(_, score)| score);
+            let alternatives = scored[1..]
+                .iter()
+                .take_while(|&&(_, score)| score >= top * ALTERNATIVE_SHARE)
+                .take(MAX_ALTERNATIVES)
+                .map(|&(template, score)| Candidate { exercise_id: template.exercise_id.to_string(), score })
+                .collect();
+
+            Recognition {
+                exercise_id: best.exercise_id.to_string(),
+                muscle_group: muscle_group.to_string(),
+                confidence: (top - rival / 2.0).clamp(0.0, 1.0),
+                alternatives,
+            }
+        });
+
+        self.scores = scored.iter().map(|&(template, score)| (template.exercise_id, score)).collect();
+        self.recognition = recognition;
+    }
+
+    fn score(&self, template: &Template, reference: &[(&str, Trajectory)], torso: Option<&Trajectory>) -> f32 {
+        let profile = &template.profile;
+        let primary = self.trajectory(template.group, &profile.primary_joint);
+        if primary.coverage < MIN_COVERAGE {
+            return 0.0;
+        }
+        let (min, max) = span(profile);
+        let fit = primary.fit(min, max);
+        let posture = match (torso_range(profile.movement_pattern), torso) {
+            (Some((low, high)), Some(torso)) if !torso.samples.is_empty() => torso.fit(low, high),
+            _ => 1.0,
+        };
+
+        if profile.movement_pattern.is_isometric() {
+            let held = if template.hold_ranges.is_empty() { fit } else { self.held(template) };
+            let motion = reference.iter().map(|(_, joint)| joint.amplitude()).fold(primary.amplitude(), f32::max);
+            return held * posture * (1.0 - motion / (2.0 * MIN_MOTION)).clamp(0.0, 1.0);
+        }
+
+        let amplitude = primary.amplitude();
+        if amplitude < MIN_MOTION {
+            return 0.0;
+        }
+        let travel = (amplitude / (MIN_TRAVEL * (max - min))).min(1.0);
+        let cycles = primary.reversals.min(2) as f32 / 2.0;
+        // Joints the exercise doesn't work may follow along (the shoulder angle opens as the
+        // torso leans in a squat), but not as far as the one it does
+        let unused = reference
+            .iter()
+            .filter(|(joint, _)| *joint != profile.primary_joint && !profile.secondary_joints.iter().any(|secondary| secondary == joint))
+            .map(|(_, joint)| joint.amplitude())
+            .fold(0.0, f32::max);
+        let isolation = (1.5 - unused / amplitude).clamp(0.0, 1.0);
+
+        fit * travel * cycles * isolation * posture * self.stage_coverage(template)
+    }
+
+    // Share of a multi-stage exercise's stages seen anywhere in the window, 1 for the rest
+    fn stage_coverage(&self, template: &Template) -> f32 {
+        if template.stages.is_empty() {
+            return 1.0;
+        }
+        let seen = template
+            .stages
+            .iter()
+            .filter(|stage| {
+                self.window.iter().any(|(timestamp, groups)| {
+                    stage.matches(&RuleContext {
+                        angles: &groups[template.group],
+                        position: RepPosition { phase: MovementPhase::None, rom: None },
+                        reps: 0,
+                        phase_ms: 0.0,
+                        timestamp: *timestamp,
+                    })
+                })
+            })
+            .count();
+        seen as f32 / template.stages.len() as f32
+    }
+
+    // Share of frames with every hold range satisfied
+    fn held(&self, template: &Template) -> f32 {
+        let held = self
+            .window
+            .iter()
+            .filter(|(_, groups)| {
+                template.hold_ranges.iter().all(|range| {
+                    groups[template.group].get(&range.angle).is_some_and(|&angle| angle >= range.min && angle <= range.max)
+                })
+            })
+            .count();
+        held as f32 / self.window.len() as f32
+    }
+
+    fn trajectory(&self, group: usize, joint: &str) -> Trajectory {
+        let samples = self
+            .window
+            .iter()
+            .filter_map(|(_, groups)| rep_counter::joint_angle(joint, &groups[group]))
+            .collect();
+        Trajectory::new(samples, self.window.len())
+    }
+}
+
+// Angles the primary joint moves through, the profile range stretched to the lockout and
+// stretch angles where those lie outside it
+fn span(profile: &ExerciseProfile) -> (f32, f32) {
+    let min = profile.stretch_angle.map_or(profile.range_min, |stretch| stretch.min(profile.range_min));
+    let max = profile.lockout_angle.map_or(profile.range_max, |lockout| lockout.max(profile.range_max));
+    (min, max)
+}
+
+// How the torso is oriented through a movement pattern, None where it varies (hinges, rows,
+// core work, multi-stage movements)
+fn torso_range(pattern: MovementPattern) -> Option<(f32, f32)> {
+    use MovementPattern::*;
+    match pattern {
+        Squat | OlympicSquat | LateralSquat | SplitSquat | Lunge | WeightedLunge | LateralLunge | StepUp
+        | KneeExtension | IsolationExtension | SeatedCurl | BarbellCurl | DumbbellCurl | IsolationCurl | BandCurl
+        | ReverseCurl | ZottmanCurl | PartialCurl | ChinUp | LateralRaise | FrontRaise | Shrug | ExternalRotation
+        | OverheadPress | RotationalPress | OverheadExtension | Pushdown | Dip | BenchDip | StraightLegRaise
+        | WeightedRaise | SingleLegRaise | SeatedRaise | PartialRaise | Plyometric | Isometric => Some(UPRIGHT),
+        Pushup | TricepsPushup | CloseGripPress | SkullCrusher | BarbellPress | DumbbellPress | ExplosivePress
+        | HipThrust | GluteBridge | LegCurl | BallCurl | ReverseHyper | Crunch | Kickback | Static | IsometricHold
+        | AntiRotation => Some(LYING),
+        _ => None,
+    }
+}
+
+impl Default for ExerciseRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,453 @@
+// wasm/src/reference.rs
+// Reference reps: one good rep stored as the trajectories of a few named joint angles, each
+// user rep aligned against it with dynamic time warping. The alignment lets a slower
+// descent or a pause line up with the reference, so what is left over is a difference in
+// shape: how far the joints were from where the reference had them at the same point of
+// the movement. A reference comes from an exercise file ("reference") or is recorded from
+// a rep performed in front of the camera
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter;
+
+// Samples per angle in a recorded reference
+const REFERENCE_SAMPLES: usize = 30;
+// Longer reps are thinned out to this many frames before alignment, it costs
+// frames x reference samples
+const MAX_ALIGN_FRAMES: usize = 120;
+
+// The file format. Each angle (a joint such as "knee" or an angle key) maps to its values
+// over one rep, evenly spaced in time and all the same length
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceRep {
+    pub angles: HashMap<String, Vec<f32>>,
+    // Mean deviation in degrees at which similarity reaches 0
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f32,
+    // Reps scoring below this (0-100) raise an "off_reference" issue, none only reports
+    #[serde(default)]
+    pub min_similarity: Option<f32>,
+}
+
+fn default_tolerance() -> f32 {
+    15.0
+}
+
+impl ReferenceRep {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut lengths = self.angles.values().map(Vec::len);
+        let Some(length) = lengths.next() else {
+            return Err("reference has no angles".to_string());
+        };
+        if length < 2 || lengths.any(|other| other != length) {
+            return Err("reference angles need the same number of samples, at least two".to_string());
+        }
+        if self.tolerance <= 0.0 {
+            return Err("reference tolerance must be positive".to_string());
+        }
+        if self.min_similarity.is_some_and(|min| !(0.0..=100.0).contains(&min)) {
+            return Err("reference minSimilarity must be within 0-100".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Where the rep strayed furthest from the reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deviation {
+    pub angle: String,
+    pub progress: f32, // Through the reference rep, 0-1
+    pub time: f32,     // Seconds into the user's rep
+    pub measured: f32,
+    pub reference: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepComparison {
+    pub similarity: f32, // 0-100
+    pub mean_deviation: f32, // Degrees, averaged along the alignment
+    pub deviation: Option<Deviation>,
+}
+
+// Angle values of the rep in progress, one column per name. Missing values repeat the last
+// one seen, so an occluded frame doesn't read as a jump
+#[derive(Debug)]
+struct Trajectory {
+    names: Vec<String>,
+    frames: Vec<(f32, Vec<Option<f32>>)>, // (timestamp in seconds, value per name)
+    partial: bool, // Recording began partway through the rep in progress
+}
+
+impl Trajectory {
+    fn new(names: Vec<String>) -> Self {
+        Trajectory { names, frames: Vec::new(), partial: false }
+    }
+
+    fn push(&mut self, angles: &HashMap<String, f32>, timestamp: f32) {
+        let last = self.frames.last().map(|(_, values)| values.clone());
+        let values: Vec<Option<f32>> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                rep_counter::joint_angle(name, angles).or_else(|| last.as_ref().and_then(|last| last[index]))
+            })
+            .collect();
+        if values.iter().any(Option::is_some) {
+            self.frames.push((timestamp, values));
+        }
+    }
+
+    // The finished rep, empty if recording began partway through it. Its last frame stays
+    // behind as the first of the next, the way the rep counter splits reps
+    fn take(&mut self) -> Vec<(f32, Vec<Option<f32>>)> {
+        let next: Vec<_> = self.frames.last().cloned().into_iter().collect();
+        let mut frames = std::mem::replace(&mut self.frames, next);
+        if std::mem::replace(&mut self.partial, false) {
+            return Vec::new();
+        }
+
+        // Angles first seen partway through take that value from the start
+        for index in 0..self.names.len() {
+            if let Some(first) = frames.iter().find_map(|(_, values)| values[index]) {
+                for (_, values) in &mut frames {
+                    values[index].get_or_insert(first);
+                }
+            }
+        }
+        frames
+    }
+}
+
+// Compares every rep against a reference
+#[derive(Debug)]
+pub struct RepMatcher {
+    reference: ReferenceRep,
+    samples: Vec<Vec<f32>>, // Reference frames, values in trajectory.names order
+    trajectory: Trajectory,
+}
+
+impl RepMatcher {
+    // The reference should have passed validate
+    pub fn new(reference: ReferenceRep) -> Self {
+        let mut names: Vec<String> = reference.angles.keys().cloned().collect();
+        names.sort();
+        let length = reference.angles.values().map(Vec::len).min().unwrap_or_default();
+        let samples = (0..length).map(|sample| names.iter().map(|name| reference.angles[name][sample]).collect()).collect();
+
+        RepMatcher { reference, samples, trajectory: Trajectory::new(names) }
+    }
+
+    // For a reference set mid-session: the rep in progress isn't compared, only part of
+    // it would be recorded
+    pub fn from_next_rep(mut self) -> Self {
+        self.trajectory.partial = true;
+        self
+    }
+
+    pub fn reference(&self) -> &ReferenceRep {
+        &self.reference
+    }
+
+    // Timestamp in seconds, every frame including the one completing a rep
+    pub fn record(&mut self, angles: &HashMap<String, f32>, timestamp: f32) {
+        self.trajectory.push(angles, timestamp);
+    }
+
+    // Scores the rep that just completed; None when none of the reference angles were seen
+    pub fn finish_rep(&mut self) -> Option<RepComparison> {
+        let mut frames = self.trajectory.take();
+        if frames.len() > MAX_ALIGN_FRAMES {
+            let step = (frames.len() - 1) as f32 / (MAX_ALIGN_FRAMES - 1) as f32;
+            frames = (0..MAX_ALIGN_FRAMES).map(|index| frames[(index as f32 * step).round() as usize].clone()).collect();
+        }
+        if frames.is_empty() || self.samples.is_empty() {
+            return None;
+        }
+
+        // Averaged per reference sample, so each point of the reference weighs the same
+        // however many frames the user spent there: a long stay at the top can't dilute a
+        // bottom that was never reached
+        let path = align(&frames, &self.samples);
+        let mut per_sample = vec![(0.0, 0); self.samples.len()];
+        for &(_, sample, cost) in &path {
+            per_sample[sample].0 += cost;
+            per_sample[sample].1 += 1;
+        }
+        let mean_deviation = per_sample.iter().map(|&(sum, count)| sum / count.max(1) as f32).sum::<f32>()
+            / self.samples.len() as f32;
+        let deviation = path
+            .iter()
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .and_then(|&(frame, sample, _)| self.deviation(&frames, frame, sample));
+
+        Some(RepComparison {
+            similarity: (100.0 * (1.0 - mean_deviation / self.reference.tolerance)).clamp(0.0, 100.0),
+            mean_deviation,
+            deviation,
+        })
+    }
+
+    // The angle furthest off on the worst-aligned pair of frames
+    fn deviation(&self, frames: &[(f32, Vec<Option<f32>>)], frame: usize, sample: usize) -> Option<Deviation> {
+        let (timestamp, values) = &frames[frame];
+        let (index, measured) = values
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| value.map(|value| (index, value)))
+            .max_by(|a, b| {
+                let off = |&(index, value): &(usize, f32)| (value - self.samples[sample][index]).abs();
+                off(a).total_cmp(&off(b))
+            })?;
+
+        Some(Deviation {
+            angle: self.trajectory.names[index].clone(),
+            progress: sample as f32 / (self.samples.len() - 1).max(1) as f32,
+            time: timestamp - frames[0].0,
+            measured,
+            reference: self.samples[sample][index],
+        })
+    }
+
+    // The extra form check, for references with a minimum similarity
+    pub fn check(&self, comparison: &RepComparison) -> Option<FormIssue> {
+        let min = self.reference.min_similarity?;
+        if comparison.similarity >= min {
+            return None;
+        }
+        let message = match &comparison.deviation {
+            Some(deviation) => format!(
+                "Rep off the reference ({:.0}% similar), {} {:.0}° {} it {:.0}% into the rep",
+                comparison.similarity,
+                deviation.angle.replace('_', " "),
+                (deviation.measured - deviation.reference).abs(),
+                if deviation.measured > deviation.reference { "above" } else { "below" },
+                deviation.progress * 100.0
+            ),
+            None => format!("Rep off the reference ({:.0}% similar)", comparison.similarity),
+        };
+        let issue = FormIssue::new("off_reference", Severity::Warning, message);
+        Some(match &comparison.deviation {
+            Some(deviation) => issue.measured(&deviation.angle, deviation.measured),
+            None => issue,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.trajectory.frames.clear();
+    }
+}
+
+// Dynamic time warping of the user's frames against the reference samples. Returns the
+// warping path as (frame, sample, cost) from start to end, cost being the mean absolute
+// difference over the angles measured in that frame
+fn align(frames: &[(f32, Vec<Option<f32>>)], samples: &[Vec<f32>]) -> Vec<(usize, usize, f32)> {
+    let (n, m) = (frames.len(), samples.len());
+    let cost = |frame: usize, sample: usize| {
+        let (sum, count) = frames[frame]
+            .1
+            .iter()
+            .zip(&samples[sample])
+            .filter_map(|(value, reference)| value.map(|value| (value - reference).abs()))
+            .fold((0.0, 0), |(sum, count), off| (sum + off, count + 1));
+        if count > 0 { sum / count as f32 } else { 0.0 }
+    };
+
+    // total[i][j]: cheapest alignment of frames ..=i with samples ..=j
+    let mut total = vec![vec![f32::INFINITY; m]; n];
+    for i in 0..n {
+        for j in 0..m {
+            let previous = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => total[0][j - 1],
+                (_, 0) => total[i - 1][0],
+                _ => total[i - 1][j - 1].min(total[i - 1][j]).min(total[i][j - 1]),
+            };
+            total[i][j] = previous + cost(i, j);
+        }
+    }
+
+    let (mut i, mut j) = (n - 1, m - 1);
+    let mut path = vec![(i, j, cost(i, j))];
+    while i > 0 || j > 0 {
+        (i, j) = match (i, j) {
+            (0, _) => (0, j - 1),
+            (_, 0) => (i - 1, 0),
+            _ => [(i - 1, j - 1), (i - 1, j), (i, j - 1)]
+                .into_iter()
+                .min_by(|a, b| total[a.0][a.1].total_cmp(&total[b.0][b.1]))
+                .unwrap_or((i - 1, j - 1)),
+        };
+        path.push((i, j, cost(i, j)));
+    }
+    path.reverse();
+    path
+}
+
+// Records the next full rep as a reference. The rep in progress when recording starts is
+// skipped, it may be half done
+#[derive(Debug)]
+pub struct RepRecorder {
+    trajectory: Trajectory,
+}
+
+impl RepRecorder {
+    pub fn new(joints: Vec<String>) -> Self {
+        RepRecorder { trajectory: Trajectory { partial: true, ..Trajectory::new(joints) } }
+    }
+
+    pub fn record(&mut self, angles: &HashMap<String, f32>, timestamp: f32) {
+        self.trajectory.push(angles, timestamp);
+    }
+
+    // Called when a rep completes. Returns the reference once a whole rep is recorded
+    pub fn finish_rep(&mut self) -> Option<ReferenceRep> {
+        let frames = self.trajectory.take();
+        if frames.len() < 2 {
+            return None;
+        }
+
+        // Resampled evenly in time, so a recording at any frame rate stores the same way
+        let (start, end) = (frames[0].0, frames[frames.len() - 1].0);
+        let mut angles = HashMap::new();
+        for (index, name) in self.trajectory.names.iter().enumerate() {
+            let values: Vec<(f32, f32)> =
+                frames.iter().filter_map(|(timestamp, values)| values[index].map(|value| (*timestamp, value))).collect();
+            if values.is_empty() {
+                continue;
+            }
+            let series = (0..REFERENCE_SAMPLES)
+                .map(|sample| {
+                    let at = start + (end - start) * sample as f32 / (REFERENCE_SAMPLES - 1) as f32;
+                    interpolate(&values, at)
+                })
+                .collect();
+            angles.insert(name.clone(), series);
+        }
+
+        (!angles.is_empty()).then(|| ReferenceRep { angles, tolerance: default_tolerance(), min_similarity: None })
+    }
+}
+
+// Linear interpolation in (timestamp, value) pairs sorted by time
+fn interpolate(values: &[(f32, f32)], at: f32) -> f32 {
+    let after = values.partition_point(|&(timestamp, _)| timestamp < at);
+    match (after.checked_sub(1).map(|before| values[before]), values.get(after)) {
+        (Some((t0, v0)), Some(&(t1, v1))) if t1 > t0 => v0 + (v1 - v0) * (at - t0) / (t1 - t0),
+        (_, Some(&(_, value))) | (Some((_, value)), None) => value,
+        (None, None) => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Knee angle `t` of the way through a squat from 170 down to `bottom` and back
+    fn squat(t: f32, bottom: f32) -> f32 {
+        bottom + (170.0 - bottom) * (0.5 + 0.5 * (t * std::f32::consts::TAU).cos())
+    }
+
+    fn reference(min_similarity: Option<f32>) -> ReferenceRep {
+        let knee = (0..30).map(|sample| squat(sample as f32 / 29.0, 90.0)).collect();
+        ReferenceRep { angles: HashMap::from([("knee".to_string(), knee)]), tolerance: 15.0, min_similarity }
+    }
+
+    // One rep of `seconds` at 30 fps starting at `start`, ending on the frame that completes it
+    fn rep(matcher: &mut RepMatcher, start: f32, seconds: f32, bottom: f32) -> Option<RepComparison> {
+        let frames = (seconds * 30.0) as usize;
+        for frame in 1..=frames {
+            let t = frame as f32 / frames as f32;
+            matcher.record(&HashMap::from([("knee".to_string(), squat(t, bottom))]), start + t * seconds);
+        }
+        matcher.finish_rep()
+    }
+
+    fn matcher(min_similarity: Option<f32>) -> RepMatcher {
+        let mut matcher = RepMatcher::new(reference(min_similarity));
+        matcher.record(&HashMap::from([("knee".to_string(), 170.0)]), 0.0);
+        matcher
+    }
+
+    #[test]
+    fn an_identical_rep_scores_100() {
+        let mut matcher = RepMatcher::new(reference(None));
+        for (sample, &knee) in reference(None).angles["knee"].iter().enumerate() {
+            matcher.record(&HashMap::from([("knee".to_string(), knee)]), sample as f32 / 30.0);
+        }
+        let comparison = matcher.finish_rep().unwrap();
+        assert_eq!(comparison.similarity, 100.0);
+        assert_eq!(comparison.mean_deviation, 0.0);
+    }
+
+    #[test]
+    fn tempo_alone_costs_little() {
+        let mut matcher = matcher(None);
+        let slow = rep(&mut matcher, 0.0, 4.0, 90.0).unwrap();
+        let fast = rep(&mut matcher, 4.0, 1.0, 90.0).unwrap();
+        assert!(slow.similarity > 90.0, "{:?}", slow);
+        assert!(fast.similarity > 90.0, "{:?}", fast);
+    }
+
+    #[test]
+    fn a_shallow_rep_scores_lower_and_says_where() {
+        let mut matcher = matcher(Some(70.0));
+        let good = rep(&mut matcher, 0.0, 2.0, 90.0).unwrap();
+        let shallow = rep(&mut matcher, 2.0, 2.0, 130.0).unwrap();
+        assert!(shallow.similarity < good.similarity - 30.0, "{:?} vs {:?}", shallow, good);
+
+        let deviation = shallow.deviation.as_ref().unwrap();
+        assert_eq!(deviation.angle, "knee");
+        assert!((deviation.progress - 0.5).abs() < 0.1, "{:?}", deviation);
+        assert!(deviation.measured > deviation.reference);
+
+        assert!(matcher.check(&good).is_none());
+        let issue = matcher.check(&shallow).unwrap();
+        assert_eq!(issue.code, "off_reference");
+        assert!(issue.message.contains("above"), "{}", issue.message);
+    }
+
+    #[test]
+    fn a_matcher_set_mid_rep_skips_that_rep() {
+        let mut matcher = RepMatcher::new(reference(None)).from_next_rep();
+        assert!(rep(&mut matcher, 0.0, 2.0, 90.0).is_none());
+        assert!(rep(&mut matcher, 2.0, 2.0, 90.0).is_some());
+    }
+
+    #[test]
+    fn records_the_next_full_rep() {
+        let mut recorder = RepRecorder::new(vec!["knee".to_string()]);
+        let mut record = |start: f32, bottom: f32| {
+            for frame in 1..=60 {
+                let t = frame as f32 / 60.0;
+                recorder.record(&HashMap::from([("knee".to_string(), squat(t, bottom))]), start + t * 2.0);
+            }
+            recorder.finish_rep()
+        };
+        // The rep in progress when recording starts is skipped
+        assert!(record(0.0, 120.0).is_none());
+        let recorded = record(2.0, 90.0).unwrap();
+        assert!(recorded.validate().is_ok());
+        assert_eq!(recorded.angles["knee"].len(), REFERENCE_SAMPLES);
+
+        let mut matcher = RepMatcher::new(recorded);
+        matcher.record(&HashMap::from([("knee".to_string(), 170.0)]), 0.0);
+        assert!(rep(&mut matcher, 0.0, 2.0, 90.0).unwrap().similarity > 90.0);
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        let with = |angles: &[(&str, Vec<f32>)]| ReferenceRep {
+            angles: angles.iter().map(|(name, values)| (name.to_string(), values.clone())).collect(),
+            ..reference(None)
+        };
+        assert!(with(&[]).validate().is_err());
+        assert!(with(&[("knee", vec![170.0])]).validate().is_err());
+        assert!(with(&[("knee", vec![170.0, 90.0]), ("hip", vec![170.0])]).validate().is_err());
+        assert!(ReferenceRep { tolerance: 0.0, ..reference(None) }.validate().is_err());
+        assert!(reference(Some(120.0)).validate().is_err());
+        assert!(reference(Some(80.0)).validate().is_ok());
+    }
+}
@@ -1,7 +1,16 @@
 // wasm/src/rep_counter.rs
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::definition::RepDefinition;
+use crate::form::{FormIssue, Severity};
+use crate::reference::RepComparison;
+use crate::segmentation::{self, Extreme, PeakDetector, RepDetector, Recount};
+use crate::skeleton::Side;
+use crate::tempo::Tempo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MovementPhase {
     Concentric,
     Eccentric,
@@ -9,2413 +18,724 @@ pub enum MovementPhase {
     None,
 }
 
-#[derive(Debug)]
-pub struct RepCounter {
-    count: u32,
-    current_phase: MovementPhase,
-    last_angles: HashMap<String, f32>,
-    last_timestamp: f32,
-    exercise_profile: ExerciseProfile,
-    velocity_window: Vec<f32>,
-    rom_window: Vec<f32>,
-}
-
-#[derive(Debug)]
-struct ExerciseProfile {
-    primary_joint: String,
-    secondary_joints: Vec<String>,
-    range_min: f32,
-    range_max: f32,
-    velocity_threshold: f32,
-    min_rom_percentage: f32,
-    lockout_angle: Option<f32>,
-    stretch_angle: Option<f32>,
-    movement_pattern: MovementPattern,
-}
+// Share of the rep range counted as its top or bottom end
+const RANGE_END: f32 = 0.2;
+// With the peak detector counting, phases only tell moving from still. Degrees per second
+const STILL_VELOCITY: f32 = 15.0;
+// Primary-joint samples kept for recount, ten minutes at 30 fps
+const MAX_TRACE: usize = 18_000;
 
-#[derive(Debug)]
-enum MovementPattern {
-    Crunch,
-    LegRaise,
-    Rotation,
-    Static,
-    HipFlexion,
-    SpinalFlexion,
-    AntiRotation,
-    CompoundCore,
+// Where the lifter is within the current rep, so form checks can apply only at one end
+// of the range or in one direction of travel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepPosition {
+    pub phase: MovementPhase,
+    pub rom: Option<f32>, // Smoothed, 0 at range_min and 1 at range_max; None until the primary joint is seen
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Triceps Exercises
-            "closed-grip-barbell" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::CloseGripPress,
-            },
-            "lateral-barbell-extensions" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::SkullCrusher,
-            },
-            "diamond-push-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::TricepsPushup,
-            },
-            "dumbbell-dips" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::Dip,
-            },
-            "bench-dips" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::BenchDip,
-            },
-            "lateral-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::Kickback,
-            },
-            "hammer-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::OverheadExtension,
-            },
-            "forearm-push-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::TricepsPushup,
-            },
-            "barbell-overhead" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::OverheadPress,
-            },
-            "tricep-rope-pushdown" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["wrist".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::Pushdown,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
+impl RepPosition {
+    // High-angle end of the range (lockout for presses and extensions)
+    pub fn at_top(&self) -> bool {
+        self.rom.is_some_and(|rom| rom >= 1.0 - RANGE_END)
     }
 
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::CloseGripPress | MovementPattern::Dip => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::SkullCrusher | MovementPattern::OverheadExtension => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Kickback => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Pushdown => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::TricepsPushup => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
+    pub fn at_bottom(&self) -> bool {
+        self.rom.is_some_and(|rom| rom <= RANGE_END)
     }
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::CloseGripPress | MovementPattern::Dip => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::SkullCrusher | MovementPattern::OverheadExtension => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Kickback => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::Pushdown => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.85 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::TricepsPushup => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.75 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            _ => {}
-        }
-        
-        completed
+    pub fn is_eccentric(&self) -> bool {
+        self.phase == MovementPhase::Eccentric
     }
-}
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Shoulders Exercises
-            "lateral-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::LateralRaise,
-            },
-            "frontal-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::FrontRaise,
-            },
-            "dumbbell-shrugs" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Shrug,
-            },
-            "bench-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.75,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::RearDeltRaise,
-            },
-            "exterior-dumbbell-raises" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::ExternalRotation,
-            },
-            "arnold-press" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: None,
-                movement_pattern: MovementPattern::RotationalPress,
-            },
-            "military-press" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: None,
-                movement_pattern: MovementPattern::OverheadPress,
-            },
-            "handstand-pushups" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: None,
-                movement_pattern: MovementPattern::VerticalPress,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
-    }
-
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::LateralRaise | MovementPattern::FrontRaise => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::OverheadPress | MovementPattern::VerticalPress => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::RotationalPress => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Shrug => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::RearDeltRaise | MovementPattern::ExternalRotation => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
-    }
-
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::OverheadPress | MovementPattern::VerticalPress => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::RotationalPress => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::LateralRaise | MovementPattern::FrontRaise => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.7 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::Shrug => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.6 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::RearDeltRaise | MovementPattern::ExternalRotation => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.75 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
-        }
-        
-        completed
+    pub fn is_concentric(&self) -> bool {
+        self.phase == MovementPhase::Concentric
     }
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Quadriceps Exercises
-            "bulgarian-splits" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 80.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(80.0),
-                movement_pattern: MovementPattern::SplitSquat,
-            },
-            "bodyweight-lunges" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(175.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::Lunge,
-            },
-            "goblet-squats" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::Squat,
-            },
-            "weighted-lunges" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(175.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::WeightedLunge,
-            },
-            "side-squats" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::LateralSquat,
-            },
-            "obstacle-overstep-touches" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 45.0,
-                range_max: 180.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.65,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(45.0),
-                movement_pattern: MovementPattern::StepUp,
-            },
-            "stand-ups" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::KneeExtension,
-            },
-            "squat-steps" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::StepUp,
-            },
-            "front-squats" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "ankle".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.95,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::OlympicSquat,
-            },
-            "leg-extensions" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["ankle".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(175.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::IsolationExtension,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
-    }
-
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::Squat | MovementPattern::OlympicSquat => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Lunge | MovementPattern::WeightedLunge => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::IsolationExtension => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::StepUp => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::KneeExtension => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
-    }
-
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::Squat | MovementPattern::OlympicSquat => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Lunge | MovementPattern::WeightedLunge => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::IsolationExtension => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.95 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::StepUp => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::KneeExtension => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
-        }
-        
-        completed
-    }
+// One completed rep, measured on the primary joint. Times are in seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepReport {
+    pub number: u32,
+    pub min_angle: f32,
+    pub max_angle: f32,
+    pub rom_percentage: f32, // Share of range_min..range_max the rep covered, 0-1
+    pub duration: f32,
+    pub concentric_time: f32,
+    pub eccentric_time: f32,
+    pub bottom_pause_time: f32, // Holds and still frames after the eccentric
+    pub top_pause_time: f32,    // and after the concentric
+    pub peak_velocity: f32,     // Degrees per second, either direction
+    pub form_issues: Vec<FormIssue>, // Raised during the rep, most severe first
+    pub quality: f32,                // 0-100
+    pub tempo_adherence: Option<f32>, // 0-100 against the prescribed tempo, if there is one
+    pub reference: Option<RepComparison>, // Against the exercise's reference rep, see reference.rs
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Hamstrings Exercises
-            "romanian-deadlifts" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipHinge,
-            },
-            "hamstring-curls" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::LegCurl,
-            },
-            "kettlebell-good-morning" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipHinge,
-            },
-            "glute-ham-raises" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::GluteHamRaise,
-            },
-            "single-leg-deadlifts" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::SingleLegHinge,
-            },
-            "seated-leg-curls" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::SeatedCurl,
-            },
-            "nordic-hamstring-curls" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::EccentricCurl,
-            },
-            "stiff-leg-deadlifts" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::StiffLegHinge,
-            },
-            "swiss-ball-hamstring-curls" => ExerciseProfile {
-                primary_joint: "knee".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.75,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::BallCurl,
-            },
-            "reverse-hyperextensions" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::ReverseHyper,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
-    }
-
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::HipHinge | MovementPattern::StiffLegHinge => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::LegCurl | MovementPattern::SeatedCurl => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::GluteHamRaise => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::EccentricCurl => {
-                if velocity < -self.exercise_profile.velocity_threshold * 0.5 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::BallCurl => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::ReverseHyper => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
+impl RepReport {
+    pub fn with_form_issues(mut self, form_issues: Vec<FormIssue>) -> Self {
+        self.form_issues = form_issues;
+        self.score();
+        self
     }
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::HipHinge | MovementPattern::StiffLegHinge => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::LegCurl | MovementPattern::SeatedCurl => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::GluteHamRaise => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::EccentricCurl => {
-                if *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.7 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::BallCurl => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.75 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::ReverseHyper => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
+    // Missing range costs up to 40 points, every distinct issue a fixed amount by severity
+    fn score(&mut self) {
+        let mut quality = 100.0 - 40.0 * (1.0 - self.rom_percentage);
+        for issue in &self.form_issues {
+            quality -= match issue.severity {
+                Severity::Info => 2.0,
+                Severity::Warning => 10.0,
+                Severity::Critical => 25.0,
+            };
         }
-        
-        completed
+        self.quality = quality.clamp(0.0, 100.0);
     }
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Glutes Exercises
-            "superman" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 30.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.6,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::IsometricHold,
-            },
-            "good-morning" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string(), "knee".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipHinge,
-            },
-            "yoga-ball-glute-raises" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::GluteBridge,
-            },
-            "donkey-kick" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::Kickback,
-            },
-            "inverse-kick-back" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 60.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.75,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::ReverseKickback,
-            },
-            "barbell-bench-touches" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipThrust,
-            },
-            "barbell-hip-thrust" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipThrust,
-            },
-            "curtsy-lunges" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "ankle".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::LateralLunge,
-            },
-            "cable-pull-through" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipHinge,
-            },
-            "frog-pumps" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::GluteBridge,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
-    }
-
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::HipThrust | MovementPattern::GluteBridge => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::HipHinge => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Kickback | MovementPattern::ReverseKickback => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::LateralLunge => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::IsometricHold => {
-                if rom > 0.5 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
-    }
-
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::HipThrust => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::GluteBridge => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::HipHinge => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Kickback => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::LateralLunge => {
-                if let Some(stretch) = self.exercise_profile.stretch_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle <= stretch && 
-                           *new_phase == MovementPhase::Concentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::IsometricHold => {
-                if *new_phase == MovementPhase::StaticHold &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
-        }
-        
-        completed
-    }
+// Measurements of the rep in progress
+#[derive(Debug, Clone, Copy, Default)]
+struct RepProgress {
+    started_at: Option<f32>,
+    min_angle: Option<f32>,
+    max_angle: Option<f32>,
+    peak_velocity: f32,
+    concentric_time: f32,
+    eccentric_time: f32,
+    bottom_pause_time: f32,
+    top_pause_time: f32,
+    last_moving: Option<MovementPhase>, // Which end a pause is at, carried over between reps
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Chest Exercises
-            "inner-push-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::CloseGripPress,
-            },
-            "superman-push-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "spine".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::ExplosivePress,
-            },
-            "butterfly" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.8,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::Fly,
-            },
-            "dumbbell-overhead" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::VerticalPress,
-            },
-            "military-press" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::StrictPress,
-            },
-            "bench-press-dumbbell" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::DumbbellPress,
-            },
-            "bench-press-barbell" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::BarbellPress,
-            },
-            "bench-butterfly" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.75,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::MachineFly,
-            },
-            "dumbbell-rows" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::ChestSupportedRow,
-            },
-            "open-butterfly" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 120.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::CableFly,
-            },
-
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
+impl RepProgress {
+    fn starting_at(timestamp: f32, angle: f32, last_moving: Option<MovementPhase>) -> Self {
+        RepProgress {
+            started_at: Some(timestamp),
+            min_angle: Some(angle),
+            max_angle: Some(angle),
+            last_moving,
+            ..RepProgress::default()
         }
     }
 
-    // ... (keep all existing methods)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::BarbellPress | MovementPattern::DumbbellPress => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Fly | MovementPattern::CableFly => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::ExplosivePress => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.5 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::VerticalPress => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
+    fn record(&mut self, timestamp: f32, angle: f32, velocity: f32, phase: MovementPhase, dt: f32) {
+        self.started_at.get_or_insert(timestamp);
+        self.min_angle = Some(self.min_angle.map_or(angle, |min| min.min(angle)));
+        self.max_angle = Some(self.max_angle.map_or(angle, |max| max.max(angle)));
+        self.peak_velocity = self.peak_velocity.max(velocity.abs());
+        match (phase, self.last_moving) {
+            (MovementPhase::Concentric, _) => self.concentric_time += dt,
+            (MovementPhase::Eccentric, _) => self.eccentric_time += dt,
+            (_, Some(MovementPhase::Eccentric)) => self.bottom_pause_time += dt,
+            (_, Some(MovementPhase::Concentric)) => self.top_pause_time += dt,
+            _ => {} // Waiting before the first movement
         }
-    }
-
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::BarbellPress | MovementPattern::DumbbellPress => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Fly | MovementPattern::CableFly => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::ExplosivePress => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.7 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::VerticalPress => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            _ => {}
+        if matches!(phase, MovementPhase::Concentric | MovementPhase::Eccentric) {
+            self.last_moving = Some(phase);
         }
-        
-        completed
     }
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Calves Exercises
-            "bench-calf-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::StraightLegRaise,
-            },
-            "plate-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::WeightedRaise,
-            },
-            "bulgarian-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string(), "hip".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::SingleLegRaise,
-            },
-            "barbell-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.55,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::WeightedRaise,
-            },
-            "jump-rope" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.8,
-                min_rom_percentage: 0.5,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Plyometric,
-            },
-            "donkey-calf-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string(), "hip".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::BentLegRaise,
-            },
-            "seated-calf-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::SeatedRaise,
-            },
-            "stair-calf-raises" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::PartialRaise,
-            },
-            "farmer-walk-on-toes" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 135.0,
-                range_max: 180.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isometric,
-            },
-            "pogo-jumps" => ExerciseProfile {
-                primary_joint: "ankle".to_string(),
-                secondary_joints: vec!["knee".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 1.0,
-                min_rom_percentage: 0.4,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Plyometric,
-            },
+#[derive(Debug)]
+pub struct RepCounter {
+    count: u32,
+    current_phase: MovementPhase,
+    last_angles: HashMap<String, f32>,
+    last_timestamp: f32,
+    exercise_profile: ExerciseProfile,
+    strategy: PatternStrategy,
+    velocity_window: Vec<f32>,
+    rom_window: Vec<f32>,
+    progress: RepProgress,
+    peaks: Option<PeakDetector>, // Counts instead of the phase changes when the profile picks it
+    trace: Vec<(f32, f32)>,      // (timestamp, primary angle) since start_trace
+    trace_count: u32,            // Count when the trace started
+}
 
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
+#[derive(Debug)]
+pub(crate) struct ExerciseProfile {
+    pub(crate) primary_joint: String,
+    pub(crate) secondary_joints: Vec<String>,
+    pub(crate) range_min: f32,
+    pub(crate) range_max: f32,
+    velocity_threshold: f32,
+    min_rom_percentage: f32,
+    pub(crate) lockout_angle: Option<f32>,
+    pub(crate) stretch_angle: Option<f32>,
+    pub(crate) movement_pattern: MovementPattern,
+    tempo: Option<Tempo>,
+    detector: RepDetector,
+    prominence: Option<f32>, // Degrees, see segmentation.rs for both
+    hysteresis: Option<f32>,
+}
 
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
-    }
+// Named in definition files in kebab-case, e.g. "squat", "lateral-raise"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MovementPattern {
+    // Presses and extensions
+    CloseGripPress,
+    SkullCrusher,
+    TricepsPushup,
+    Pushup,
+    Dip,
+    BenchDip,
+    Kickback,
+    OverheadExtension,
+    OverheadPress,
+    VerticalPress,
+    RotationalPress,
+    Pushdown,
+    BarbellPress,
+    DumbbellPress,
+    ExplosivePress,
+    // Raises and flyes
+    LateralRaise,
+    FrontRaise,
+    Shrug,
+    RearDeltRaise,
+    ExternalRotation,
+    Fly,
+    MachineFly,
+    Isolation,
+    // Squats and lunges
+    Squat,
+    OlympicSquat,
+    LateralSquat,
+    SplitSquat,
+    Lunge,
+    WeightedLunge,
+    LateralLunge,
+    StepUp,
+    KneeExtension,
+    IsolationExtension,
+    // Hinges and curls of the posterior chain
+    HipHinge,
+    StiffLegHinge,
+    SingleLegHinge,
+    LegCurl,
+    SeatedCurl,
+    EccentricCurl,
+    BallCurl,
+    GluteHamRaise,
+    ReverseHyper,
+    HipThrust,
+    GluteBridge,
+    ReverseKickback,
+    IsometricHold,
+    // Calf raises
+    StraightLegRaise,
+    WeightedRaise,
+    SingleLegRaise,
+    BentLegRaise,
+    SeatedRaise,
+    PartialRaise,
+    Plyometric,
+    Isometric,
+    // Arm curls
+    BarbellCurl,
+    DumbbellCurl,
+    IsolationCurl,
+    BandCurl,
+    ReverseCurl,
+    ZottmanCurl,
+    PartialCurl,
+    ChinUp,
+    // Pulls
+    VerticalPull,
+    HorizontalPull,
+    RearDeltPull,
+    // Core
+    Crunch,
+    SpinalFlexion,
+    LegRaise,
+    HipFlexion,
+    Rotation,
+    AntiRotation,
+    CompoundCore,
+    Static,
+    // Multi-stage full-body movements, reps come from the stage sequence (sequence.rs)
+    FullBody,
+}
 
-    // ... (keep all existing methods)
+// When a pattern switches to a static hold, judged on the smoothed ROM percentage
+#[derive(Debug, Clone, Copy)]
+enum HoldRule {
+    Never,
+    AboveRom(f32),
+    BelowRom(f32),
+}
 
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::StraightLegRaise | MovementPattern::WeightedRaise => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Plyometric => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.5 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.5 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::SeatedRaise => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Isometric => {
-                if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
-    }
+// What has to happen on the phase change for it to count as a rep
+#[derive(Debug, Clone, Copy)]
+enum RepTrigger {
+    Lockout,       // Leaving the lockout angle, the angle starting to fall
+    Stretch,       // Leaving the stretch angle, the angle starting to rise
+    Reversal,      // A rising angle turning to fall
+    EccentricOnly, // Starting a controlled eccentric
+    Hold,          // Reaching the static hold
+    Never,         // Timed holds have no reps
+    Stages,        // Counted by the caller's stage sequence, see complete_rep
+}
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::StraightLegRaise | MovementPattern::WeightedRaise => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Plyometric => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::SeatedRaise => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Isometric => {
-                if *new_phase == MovementPhase::StaticHold &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
+#[derive(Debug, Clone, Copy)]
+struct PatternStrategy {
+    concentric_factor: Option<f32>, // Multiples of the profile's velocity threshold
+    eccentric_factor: Option<f32>,
+    hold: HoldRule,
+    trigger: RepTrigger,
+    rom_factor: f32, // Multiple of min_rom_percentage the rep needs
+    closing: bool,   // The concentric closes the joint, so its angle falls (curls, pulls, crunches, leg raises)
+}
+
+impl PatternStrategy {
+    const fn new(concentric: f32, eccentric: f32, hold: HoldRule, trigger: RepTrigger, rom_factor: f32) -> Self {
+        PatternStrategy {
+            concentric_factor: Some(concentric),
+            eccentric_factor: Some(eccentric),
+            hold,
+            trigger,
+            rom_factor,
+            closing: false,
+        }
+    }
+
+    const fn hold_only(hold: HoldRule, trigger: RepTrigger) -> Self {
+        PatternStrategy {
+            concentric_factor: None,
+            eccentric_factor: None,
+            hold,
+            trigger,
+            rom_factor: 1.0,
+            closing: false,
         }
-        
-        completed
     }
 }
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Biceps Exercises
-            "isolated-dumbbell-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::IsolationCurl,
-            },
-            "barbell-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::BarbellCurl,
-            },
-            "dumbbell-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::DumbbellCurl,
-            },
-            "open-grip-pull-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::ChinUp,
-            },
-            "lateral-push-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 60.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(60.0),
-                movement_pattern: MovementPattern::Pushup,
-            },
-            "half-rep-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 45.0,
-                range_max: 90.0,
-                velocity_threshold: 0.25,
-                min_rom_percentage: 0.9,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::PartialCurl,
-            },
-            "resistance-bands-pull" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::BandCurl,
-            },
-            "outward-dumbbell-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "wrist".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::ReverseCurl,
-            },
-            "concentration-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.25,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::IsolationCurl,
-            },
-            "zottman-curls" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["wrist".to_string(), "shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 135.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::ZottmanCurl,
-            },
+impl MovementPattern {
+    // Held positions, timed by hold.rs rather than judged on movement
+    pub fn is_isometric(self) -> bool {
+        matches!(self, MovementPattern::IsometricHold | MovementPattern::Isometric | MovementPattern::Static)
+    }
 
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
+    fn strategy(self) -> PatternStrategy {
+        use HoldRule::{AboveRom, BelowRom};
+        use MovementPattern::*;
+
+        let strategy = match self {
+            // Full ROM strength lifts: lockout ends the rep
+            CloseGripPress | Dip | BenchDip | OverheadPress | VerticalPress | BarbellPress | DumbbellPress
+            | Squat | OlympicSquat | LateralSquat | StiffLegHinge | HipThrust | StraightLegRaise
+            | WeightedRaise | SingleLegRaise | BentLegRaise | PartialRaise | BarbellCurl | DumbbellCurl
+            | IsolationCurl | BandCurl | ReverseCurl | VerticalPull | HorizontalPull => {
+                PatternStrategy::new(1.0, 1.0, AboveRom(0.9), RepTrigger::Lockout, 1.0)
+            },
+            SkullCrusher | OverheadExtension | SeatedRaise => {
+                PatternStrategy::new(0.8, 0.8, HoldRule::Never, RepTrigger::Lockout, 0.9)
+            },
+            IsolationExtension => PatternStrategy::new(0.8, 0.8, HoldRule::Never, RepTrigger::Lockout, 0.95),
+            Pushdown => PatternStrategy::new(0.7, 0.7, HoldRule::Never, RepTrigger::Lockout, 0.85),
+            TricepsPushup | Pushup => PatternStrategy::new(1.2, 0.8, HoldRule::Never, RepTrigger::Lockout, 0.75),
+            ExplosivePress => PatternStrategy::new(1.5, 0.7, HoldRule::Never, RepTrigger::Lockout, 0.7),
+            Lunge | WeightedLunge | SplitSquat | HipHinge | SingleLegHinge | ChinUp => {
+                PatternStrategy::new(1.2, 0.8, HoldRule::Never, RepTrigger::Lockout, 0.9)
+            },
+            GluteHamRaise => PatternStrategy::new(1.2, 0.8, HoldRule::Never, RepTrigger::Lockout, 0.8),
+            StepUp => PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Lockout, 0.8),
+            LegCurl | SeatedCurl => PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Lockout, 0.9),
+
+            // Isolation and ballistic movements: the turnaround ends the rep
+            Kickback | ReverseKickback | RotationalPress | Fly | MachineFly | Isolation | ZottmanCurl | RearDeltPull => {
+                PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Reversal, 0.8)
+            },
+            LateralRaise | FrontRaise => PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Reversal, 0.7),
+            Shrug => PatternStrategy::new(1.2, 0.8, HoldRule::Never, RepTrigger::Reversal, 0.6),
+            RearDeltRaise | ExternalRotation | BallCurl => {
+                PatternStrategy::new(0.7, 0.7, HoldRule::Never, RepTrigger::Reversal, 0.75)
+            },
+            KneeExtension | ReverseHyper => PatternStrategy::new(0.7, 0.7, HoldRule::Never, RepTrigger::Reversal, 1.0),
+            PartialCurl => PatternStrategy::new(0.7, 0.7, HoldRule::Never, RepTrigger::Reversal, 0.6),
+            Plyometric => PatternStrategy::new(1.5, 0.5, HoldRule::Never, RepTrigger::Reversal, 1.0),
+            GluteBridge => PatternStrategy::new(1.0, 1.0, AboveRom(0.9), RepTrigger::Reversal, 1.0),
+            Crunch | SpinalFlexion | Rotation => {
+                PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Reversal, 1.0)
+            },
+            AntiRotation => PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Reversal, 0.5),
+            CompoundCore => PatternStrategy::new(1.5, 1.5, HoldRule::Never, RepTrigger::Reversal, 0.8),
+
+            // Stretch-driven movements: returning from the stretch ends the rep
+            LateralLunge => PatternStrategy::new(0.7, 0.7, HoldRule::Never, RepTrigger::Stretch, 1.0),
+            LegRaise | HipFlexion => PatternStrategy::new(1.0, 1.0, BelowRom(0.1), RepTrigger::Stretch, 1.0),
+
+            // Nordic curls only count the lowering
+            EccentricCurl => PatternStrategy {
+                concentric_factor: None,
+                eccentric_factor: Some(0.5),
+                hold: HoldRule::Never,
+                trigger: RepTrigger::EccentricOnly,
+                rom_factor: 0.7,
+                closing: false,
+            },
+
+            // Isometrics
+            IsometricHold => PatternStrategy::hold_only(AboveRom(0.5), RepTrigger::Hold),
+            Isometric => PatternStrategy::hold_only(AboveRom(0.9), RepTrigger::Hold),
+            Static => PatternStrategy::hold_only(AboveRom(0.9), RepTrigger::Never),
+
+            // The primary joint goes through several cycles per rep, phases are only tracked
+            // for the report
+            FullBody => PatternStrategy::new(1.0, 1.0, HoldRule::Never, RepTrigger::Stages, 0.0),
         };
-
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
-        }
+        let closing = matches!(
+            self,
+            BarbellCurl | DumbbellCurl | IsolationCurl | BandCurl | ReverseCurl | ZottmanCurl | PartialCurl
+                | ChinUp | VerticalPull | HorizontalPull | LegCurl | SeatedCurl | EccentricCurl | BallCurl
+                | GluteHamRaise | Crunch | LegRaise | HipFlexion
+        );
+        PatternStrategy { closing, ..strategy }
     }
+}
 
-    // ... (keep all existing methods)
+// Angle keys each muscle group analyzer reports for a joint, in lookup order
+const JOINT_ANGLE_KEYS: &[(&str, &[&str])] = &[
+    ("elbow", &["elbow", "elbow_extension", "elbow_flexion", "stability"]),
+    ("shoulder", &["shoulder", "shoulder_flexion", "abduction", "flexion", "shoulder_position", "shoulder_stability"]),
+    ("hip", &["hip", "hip_hinge", "hip_extension", "hip_depth"]),
+    ("knee", &["knee", "knee_flexion", "knee_angle"]),
+    ("ankle", &["ankle", "plantar_flexion"]),
+    ("spine", &["spine", "torso_rotation", "torso_lateral", "torso"]),
+    ("wrist", &["wrist", "wrist_angle", "wrist_pronation"]),
+];
+
+pub(crate) fn joint_angle(joint: &str, angles: &HashMap<String, f32>) -> Option<f32> {
+    joint_keys(joint).find_map(|key| angles.get(key).copied())
+}
 
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::BarbellCurl | MovementPattern::DumbbellCurl => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::ChinUp => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::ZottmanCurl => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::PartialCurl => {
-                if velocity > self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.7 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
+// The key that reading comes from, e.g. "knee_flexion" for "knee"
+pub(crate) fn joint_angle_key(joint: &str, angles: &HashMap<String, f32>) -> Option<String> {
+    joint_keys(joint).find(|key| angles.contains_key(*key)).map(str::to_string)
+}
+
+// The same on one side, e.g. "left_knee_flexion" for the left knee
+pub(crate) fn side_joint_angle(joint: &str, side: Side, angles: &HashMap<String, f32>) -> Option<f32> {
+    side_joint_angle_key(joint, side, angles).map(|key| angles[&key])
+}
+
+pub(crate) fn side_joint_angle_key(joint: &str, side: Side, angles: &HashMap<String, f32>) -> Option<String> {
+    joint_keys(joint).map(|key| format!("{}_{}", side.prefix(), key)).find(|key| angles.contains_key(key))
+}
+
+fn joint_keys(joint: &str) -> impl Iterator<Item = &str> {
+    JOINT_ANGLE_KEYS
+        .iter()
+        .find(|(name, _)| *name == joint)
+        .map_or(&[][..], |(_, keys)| *keys)
+        .iter()
+        .copied()
+        .chain([joint])
+}
+
+// exercise id, primary joint, secondary joints, range min, range max, velocity threshold,
+// min ROM %, lockout angle, stretch angle, movement pattern. The range is where a full rep
+// of good depth turns, e.g. a parallel squat, and min ROM the share of it a rep has to travel
+type ProfileRow = (
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+    f32,
+    f32,
+    f32,
+    f32,
+    Option<f32>,
+    Option<f32>,
+    MovementPattern,
+);
+
+// Ids shared between muscle groups use the same group PoseAnalyzer routes them to
+const EXERCISE_PROFILES: &[ProfileRow] = {
+    use MovementPattern::*;
+    &[
+        // Triceps
+        ("closed-grip-barbell", "elbow", &["shoulder"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), CloseGripPress),
+        ("lateral-barbell-extensions", "elbow", &["shoulder"], 90.0, 180.0, 0.4, 0.85, Some(170.0), Some(90.0), SkullCrusher),
+        ("diamond-push-ups", "elbow", &["shoulder", "wrist"], 60.0, 180.0, 0.45, 0.75, Some(170.0), Some(60.0), TricepsPushup),
+        ("dumbbell-dips", "elbow", &["shoulder"], 90.0, 180.0, 0.55, 0.9, Some(170.0), Some(90.0), Dip),
+        ("bench-dips", "elbow", &["shoulder"], 90.0, 180.0, 0.4, 0.8, Some(170.0), Some(90.0), BenchDip),
+        ("hammer-dumbbell-raises", "elbow", &["shoulder"], 0.0, 180.0, 0.4, 0.85, Some(170.0), Some(0.0), OverheadExtension),
+        ("forearm-push-ups", "elbow", &["shoulder"], 60.0, 180.0, 0.6, 0.7, Some(170.0), Some(60.0), TricepsPushup),
+        ("barbell-overhead", "elbow", &["shoulder"], 90.0, 180.0, 0.45, 0.9, Some(170.0), Some(90.0), OverheadPress),
+        ("tricep-rope-pushdown", "elbow", &["wrist"], 90.0, 180.0, 0.35, 0.8, Some(170.0), Some(90.0), Pushdown),
+        ("skull-crushers", "elbow", &["shoulder"], 90.0, 180.0, 0.4, 0.85, Some(170.0), Some(90.0), SkullCrusher),
+        ("close-grip-bench-press", "elbow", &["shoulder"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), CloseGripPress),
+        // Shoulders
+        ("lateral-dumbbell-raises", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.7, None, None, LateralRaise),
+        ("frontal-dumbbell-raises", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.7, None, None, FrontRaise),
+        ("dumbbell-shrugs", "shoulder", &["elbow"], 0.0, 45.0, 0.4, 0.8, None, None, Shrug),
+        ("bench-dumbbell-raises", "shoulder", &["elbow"], 0.0, 90.0, 0.35, 0.75, None, None, RearDeltRaise),
+        ("exterior-dumbbell-raises", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.7, None, None, ExternalRotation),
+        ("arnold-press", "shoulder", &["elbow"], 0.0, 180.0, 0.4, 0.8, Some(170.0), None, RotationalPress),
+        ("military-press", "shoulder", &["elbow"], 0.0, 180.0, 0.45, 0.9, Some(170.0), None, OverheadPress),
+        ("handstand-pushups", "shoulder", &["elbow"], 0.0, 180.0, 0.6, 0.8, Some(170.0), None, VerticalPress),
+        ("overhead-press", "shoulder", &["elbow"], 0.0, 180.0, 0.45, 0.9, Some(170.0), None, OverheadPress),
+        ("rear-delt-fly", "shoulder", &["elbow"], 0.0, 90.0, 0.35, 0.75, None, None, RearDeltRaise),
+        ("upright-rows", "shoulder", &["elbow"], 0.0, 90.0, 0.35, 0.75, None, None, LateralRaise),
+        // Quadriceps
+        ("bulgarian-splits", "knee", &["hip", "ankle"], 80.0, 180.0, 0.4, 0.8, Some(170.0), Some(80.0), SplitSquat),
+        ("bodyweight-lunges", "knee", &["hip"], 90.0, 180.0, 0.5, 0.75, Some(175.0), Some(90.0), Lunge),
+        ("goblet-squats", "knee", &["hip", "ankle"], 70.0, 180.0, 0.5, 0.75, Some(170.0), Some(70.0), Squat),
+        ("weighted-lunges", "knee", &["hip"], 90.0, 180.0, 0.6, 0.85, Some(175.0), Some(90.0), WeightedLunge),
+        ("side-squats", "knee", &["hip", "ankle"], 60.0, 180.0, 0.45, 0.7, Some(170.0), Some(60.0), LateralSquat),
+        ("obstacle-overstep-touches", "knee", &["hip", "ankle"], 45.0, 180.0, 0.55, 0.65, Some(170.0), Some(45.0), StepUp),
+        ("stand-ups", "knee", &["hip"], 0.0, 180.0, 0.5, 0.9, Some(170.0), Some(0.0), KneeExtension),
+        ("squat-steps", "knee", &["hip", "ankle"], 60.0, 180.0, 0.6, 0.75, Some(170.0), Some(60.0), StepUp),
+        ("front-squats", "knee", &["hip", "ankle"], 90.0, 180.0, 0.55, 0.85, Some(170.0), Some(90.0), OlympicSquat),
+        ("leg-extensions", "knee", &["ankle"], 90.0, 180.0, 0.4, 0.9, Some(175.0), Some(90.0), IsolationExtension),
+        ("squat", "knee", &["hip", "ankle"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), Squat),
+        // Hamstrings
+        ("romanian-deadlifts", "hip", &["knee", "spine"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), HipHinge),
+        ("hamstring-curls", "knee", &["hip"], 0.0, 135.0, 0.4, 0.85, Some(170.0), Some(0.0), LegCurl),
+        ("kettlebell-good-morning", "hip", &["knee", "spine"], 90.0, 180.0, 0.45, 0.75, Some(170.0), Some(90.0), HipHinge),
+        ("glute-ham-raises", "knee", &["hip", "spine"], 0.0, 180.0, 0.6, 0.9, Some(170.0), Some(0.0), GluteHamRaise),
+        ("single-leg-deadlifts", "hip", &["knee", "spine"], 90.0, 180.0, 0.4, 0.7, Some(170.0), Some(90.0), SingleLegHinge),
+        ("seated-leg-curls", "knee", &[], 0.0, 135.0, 0.35, 0.8, Some(170.0), Some(0.0), SeatedCurl),
+        ("nordic-hamstring-curls", "knee", &["hip"], 0.0, 90.0, 0.5, 0.7, None, Some(0.0), EccentricCurl),
+        ("stiff-leg-deadlifts", "hip", &["knee", "spine"], 90.0, 180.0, 0.55, 0.85, Some(170.0), Some(90.0), StiffLegHinge),
+        ("swiss-ball-hamstring-curls", "knee", &["hip"], 0.0, 90.0, 0.4, 0.75, None, Some(0.0), BallCurl),
+        ("reverse-hyperextensions", "hip", &["spine"], 100.0, 180.0, 0.3, 0.7, None, Some(100.0), ReverseHyper),
+        ("sliding-leg-curls", "knee", &["hip"], 0.0, 90.0, 0.4, 0.75, None, Some(0.0), BallCurl),
+        // Glutes
+        ("superman", "hip", &["spine"], 0.0, 30.0, 0.3, 0.6, None, Some(0.0), IsometricHold),
+        ("good-morning", "hip", &["spine", "knee"], 90.0, 180.0, 0.4, 0.7, Some(170.0), Some(90.0), HipHinge),
+        ("yoga-ball-glute-raises", "hip", &["knee"], 130.0, 180.0, 0.35, 0.8, None, Some(130.0), GluteBridge),
+        ("donkey-kick", "hip", &["knee"], 90.0, 180.0, 0.3, 0.7, None, Some(90.0), Kickback),
+        ("inverse-kick-back", "hip", &["knee"], 120.0, 180.0, 0.4, 0.75, None, Some(120.0), ReverseKickback),
+        ("barbell-bench-touches", "hip", &["knee"], 100.0, 180.0, 0.5, 0.8, Some(170.0), Some(100.0), HipThrust),
+        ("barbell-hip-thrust", "hip", &["knee"], 100.0, 180.0, 0.45, 0.9, Some(170.0), Some(100.0), HipThrust),
+        ("curtsy-lunges", "hip", &["knee", "ankle"], 60.0, 180.0, 0.4, 0.75, Some(170.0), Some(60.0), LateralLunge),
+        ("cable-pull-through", "hip", &["knee"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), HipHinge),
+        ("frog-pumps", "hip", &["knee"], 130.0, 180.0, 0.3, 0.7, None, Some(130.0), GluteBridge),
+        ("glute-bridge", "hip", &["knee"], 130.0, 180.0, 0.35, 0.8, None, Some(130.0), GluteBridge),
+        ("single-leg-hip-thrust", "hip", &["knee"], 100.0, 180.0, 0.45, 0.85, Some(170.0), Some(100.0), HipThrust),
+        ("clamshells", "hip", &["knee"], 0.0, 45.0, 0.3, 0.7, None, Some(0.0), Kickback),
+        // Chest
+        ("inner-push-ups", "elbow", &["shoulder", "wrist"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), CloseGripPress),
+        ("superman-push-ups", "elbow", &["shoulder", "spine"], 60.0, 180.0, 0.6, 0.7, Some(170.0), Some(60.0), ExplosivePress),
+        ("butterfly", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.8, None, Some(0.0), Fly),
+        ("dumbbell-overhead", "shoulder", &["elbow"], 0.0, 180.0, 0.4, 0.85, Some(170.0), Some(0.0), VerticalPress),
+        ("bench-press-dumbbell", "elbow", &["shoulder"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), DumbbellPress),
+        ("bench-press-barbell", "elbow", &["shoulder"], 80.0, 180.0, 0.55, 0.8, Some(170.0), Some(80.0), BarbellPress),
+        ("bench-butterfly", "shoulder", &["elbow"], 0.0, 90.0, 0.35, 0.75, None, Some(0.0), MachineFly),
+        ("pushup", "elbow", &["shoulder", "wrist"], 60.0, 180.0, 0.5, 0.75, Some(170.0), Some(60.0), Pushup),
+        ("dumbbell-fly", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.8, None, Some(0.0), Fly),
+        ("chest-dips", "elbow", &["shoulder"], 90.0, 180.0, 0.55, 0.9, Some(170.0), Some(90.0), Dip),
+        ("incline-bench-press", "elbow", &["shoulder"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), BarbellPress),
+        ("decline-bench-press", "elbow", &["shoulder"], 80.0, 180.0, 0.5, 0.8, Some(170.0), Some(80.0), BarbellPress),
+        // Calves
+        ("bench-calf-raises", "ankle", &["knee"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), StraightLegRaise),
+        ("plate-raises", "ankle", &["knee"], 90.0, 180.0, 0.45, 0.75, Some(170.0), Some(90.0), WeightedRaise),
+        ("bulgarian-raises", "ankle", &["knee", "hip"], 90.0, 180.0, 0.6, 0.85, Some(170.0), Some(90.0), SingleLegRaise),
+        ("barbell-raises", "ankle", &["knee"], 90.0, 180.0, 0.55, 0.9, Some(170.0), Some(90.0), WeightedRaise),
+        ("jump-rope", "ankle", &["knee"], 90.0, 180.0, 0.8, 0.5, None, None, Plyometric),
+        ("donkey-calf-raises", "ankle", &["knee", "hip"], 90.0, 180.0, 0.4, 0.8, Some(170.0), Some(90.0), BentLegRaise),
+        ("seated-calf-raises", "ankle", &["knee"], 90.0, 180.0, 0.35, 0.85, Some(170.0), Some(90.0), SeatedRaise),
+        ("stair-calf-raises", "ankle", &["knee"], 90.0, 180.0, 0.5, 0.7, Some(170.0), Some(90.0), PartialRaise),
+        ("farmer-walk-on-toes", "ankle", &["knee"], 135.0, 180.0, 0.3, 0.9, Some(170.0), None, Isometric),
+        ("pogo-jumps", "ankle", &["knee"], 90.0, 180.0, 1.0, 0.4, None, None, Plyometric),
+        ("standing-calf-raises", "ankle", &["knee"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), StraightLegRaise),
+        // Biceps
+        ("isolated-dumbbell-curls", "elbow", &["shoulder", "wrist"], 40.0, 170.0, 0.3, 0.85, Some(170.0), Some(40.0), IsolationCurl),
+        ("barbell-curls", "elbow", &["shoulder"], 40.0, 170.0, 0.35, 0.8, Some(170.0), Some(40.0), BarbellCurl),
+        ("dumbbell-curls", "elbow", &["shoulder", "wrist"], 40.0, 170.0, 0.3, 0.8, Some(170.0), Some(40.0), DumbbellCurl),
+        ("open-grip-pull-ups", "elbow", &["shoulder"], 0.0, 180.0, 0.5, 0.75, Some(170.0), Some(0.0), ChinUp),
+        ("lateral-push-ups", "elbow", &["shoulder", "wrist"], 60.0, 180.0, 0.4, 0.7, Some(170.0), Some(60.0), Pushup),
+        ("half-rep-curls", "elbow", &["shoulder"], 45.0, 90.0, 0.25, 0.9, None, None, PartialCurl),
+        ("resistance-bands-pull", "elbow", &["shoulder"], 40.0, 170.0, 0.35, 0.8, Some(170.0), Some(40.0), BandCurl),
+        ("outward-dumbbell-curls", "elbow", &["shoulder", "wrist"], 40.0, 170.0, 0.3, 0.8, Some(170.0), Some(40.0), ReverseCurl),
+        ("concentration-curls", "elbow", &["shoulder"], 40.0, 170.0, 0.25, 0.9, Some(170.0), Some(40.0), IsolationCurl),
+        ("zottman-curls", "elbow", &["wrist", "shoulder"], 40.0, 170.0, 0.3, 0.85, Some(170.0), Some(40.0), ZottmanCurl),
+        ("hammer-curls", "elbow", &["shoulder", "wrist"], 40.0, 170.0, 0.3, 0.8, Some(170.0), Some(40.0), DumbbellCurl),
+        ("preacher-curls", "elbow", &["shoulder"], 40.0, 170.0, 0.25, 0.9, Some(170.0), Some(40.0), IsolationCurl),
+        // Back
+        ("dumbbell-rows", "elbow", &["shoulder", "spine"], 90.0, 180.0, 0.4, 0.7, Some(170.0), Some(90.0), VerticalPull),
+        ("barbell-rows", "elbow", &["shoulder", "spine"], 90.0, 180.0, 0.5, 0.8, Some(170.0), Some(90.0), HorizontalPull),
+        ("seated-dumbbell-rows", "elbow", &["shoulder"], 90.0, 180.0, 0.35, 0.75, Some(170.0), Some(90.0), HorizontalPull),
+        ("chin-up-pull-ups", "elbow", &["shoulder"], 0.0, 180.0, 0.6, 0.85, Some(170.0), Some(0.0), VerticalPull),
+        ("open-butterfly", "shoulder", &["elbow", "spine"], 0.0, 90.0, 0.3, 0.7, None, Some(0.0), Isolation),
+        ("lateral-russian-roulette", "spine", &["shoulder"], -30.0, 30.0, 0.4, 0.6, None, None, Rotation),
+        ("deadlifts", "hip", &["knee", "spine"], 70.0, 180.0, 0.7, 0.9, Some(170.0), Some(70.0), HipHinge),
+        ("pull-ups", "elbow", &["shoulder"], 0.0, 180.0, 0.5, 0.8, Some(170.0), Some(0.0), VerticalPull),
+        ("face-pulls", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.7, None, Some(0.0), RearDeltPull),
+        ("t-bar-rows", "elbow", &["shoulder", "spine"], 90.0, 180.0, 0.45, 0.75, Some(170.0), Some(90.0), HorizontalPull),
+        ("lat-pulldown", "elbow", &["shoulder"], 0.0, 180.0, 0.5, 0.8, Some(170.0), Some(0.0), VerticalPull),
+        ("reverse-fly", "shoulder", &["elbow"], 0.0, 90.0, 0.3, 0.7, None, Some(0.0), RearDeltPull),
+        ("hyperextensions", "hip", &["spine"], 100.0, 180.0, 0.3, 0.7, None, Some(100.0), ReverseHyper),
+        // Abs
+        ("jack-knife", "hip", &["spine"], 60.0, 180.0, 0.3, 0.7, None, Some(60.0), HipFlexion),
+        ("back_arch", "spine", &["hip"], 0.0, 45.0, 0.4, 0.6, None, Some(0.0), SpinalFlexion),
+        ("lateral-leg-raises", "hip", &["spine"], 130.0, 180.0, 0.35, 0.65, None, Some(130.0), LegRaise),
+        ("lateral-sit-ups", "spine", &["hip"], 0.0, 60.0, 0.4, 0.7, None, Some(0.0), Rotation),
+        ("dumbbell-leg-raises", "hip", &["spine"], 90.0, 180.0, 0.3, 0.75, None, Some(90.0), LegRaise),
+        ("russian-roulette", "spine", &["hip"], -45.0, 45.0, 0.5, 0.8, None, None, Rotation),
+        ("hanging-leg-raises", "hip", &["spine"], 90.0, 180.0, 0.4, 0.8, None, Some(90.0), LegRaise),
+        ("ab-wheel-rollout", "spine", &["hip", "shoulder"], 0.0, 60.0, 0.5, 0.7, None, Some(0.0), CompoundCore),
+        ("reverse-crunch", "hip", &["spine"], 50.0, 110.0, 0.35, 0.7, None, Some(50.0), HipFlexion),
+        ("scissor-kicks", "hip", &["spine"], 135.0, 180.0, 0.6, 0.5, None, None, LegRaise),
+        ("plank-hip-dips", "spine", &["hip"], -30.0, 30.0, 0.4, 0.6, None, None, AntiRotation),
+        ("situp", "spine", &["hip"], 0.0, 60.0, 0.4, 0.7, None, Some(0.0), Crunch),
+        ("plank", "hip", &["spine", "shoulder"], 150.0, 180.0, 0.3, 0.9, None, None, Static),
+        ("russian-twist", "spine", &["hip"], -45.0, 45.0, 0.5, 0.8, None, None, Rotation),
+        ("bicycle-crunches", "spine", &["hip"], -30.0, 30.0, 0.5, 0.6, None, None, Rotation),
+        ("toe-touches", "hip", &["shoulder"], 60.0, 100.0, 0.35, 0.7, None, Some(60.0), Crunch),
+        // Full body
+        ("burpees", "hip", &["knee", "elbow"], 60.0, 180.0, 0.8, 0.7, Some(160.0), None, FullBody),
+        ("kettlebell-swings", "hip", &["knee", "shoulder"], 70.0, 180.0, 0.8, 0.8, Some(165.0), None, FullBody),
+        ("thrusters", "knee", &["hip", "elbow", "shoulder"], 70.0, 180.0, 0.6, 0.8, Some(165.0), None, FullBody),
+        ("clean-and-jerk", "hip", &["knee", "elbow", "shoulder"], 60.0, 180.0, 0.6, 0.8, Some(165.0), None, FullBody),
+        ("snatch", "hip", &["knee", "shoulder"], 60.0, 180.0, 0.6, 0.8, Some(165.0), None, FullBody),
+        ("box-jumps", "knee", &["hip"], 70.0, 180.0, 1.0, 0.7, Some(160.0), None, FullBody),
+        ("jumping-jacks", "shoulder", &["hip"], 20.0, 170.0, 1.0, 0.7, None, None, FullBody),
+        ("mountain-climbers", "hip", &["knee"], 70.0, 180.0, 1.0, 0.6, None, None, FullBody),
+    ]
+};
+
+impl ExerciseProfile {
+    pub(crate) fn lookup(exercise_id: &str) -> Option<Self> {
+        EXERCISE_PROFILES
+            .iter()
+            .find(|row| row.0 == exercise_id)
+            .map(|&(_, primary, secondary, range_min, range_max, velocity, min_rom, lockout, stretch, pattern)| {
+                ExerciseProfile {
+                    primary_joint: primary.to_string(),
+                    secondary_joints: secondary.iter().map(|joint| joint.to_string()).collect(),
+                    range_min,
+                    range_max,
+                    velocity_threshold: velocity,
+                    min_rom_percentage: min_rom,
+                    lockout_angle: lockout,
+                    stretch_angle: stretch,
+                    movement_pattern: pattern,
+                    tempo: None,
+                    detector: RepDetector::Velocity,
+                    prominence: None,
+                    hysteresis: None,
+                }
+            })
+    }
+}
+
+impl From<&RepDefinition> for ExerciseProfile {
+    fn from(rep: &RepDefinition) -> Self {
+        ExerciseProfile {
+            primary_joint: rep.joint.clone(),
+            secondary_joints: rep.secondary_joints.clone(),
+            range_min: rep.range.0,
+            range_max: rep.range.1,
+            velocity_threshold: rep.velocity_threshold,
+            min_rom_percentage: rep.min_rom,
+            lockout_angle: rep.lockout_angle,
+            stretch_angle: rep.stretch_angle,
+            movement_pattern: rep.pattern,
+            tempo: rep.tempo,
+            detector: rep.detector,
+            prominence: rep.prominence,
+            hysteresis: rep.hysteresis,
         }
     }
+}
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::BarbellCurl | MovementPattern::DumbbellCurl => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::ChinUp => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::ZottmanCurl => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::PartialCurl => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.6 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
+impl Default for ExerciseProfile {
+    // Unknown exercises never count reps
+    fn default() -> Self {
+        ExerciseProfile {
+            primary_joint: String::new(),
+            secondary_joints: Vec::new(),
+            range_min: 0.0,
+            range_max: 0.0,
+            velocity_threshold: 0.0,
+            min_rom_percentage: 0.0,
+            lockout_angle: None,
+            stretch_angle: None,
+            movement_pattern: MovementPattern::Static,
+            tempo: None,
+            detector: RepDetector::Velocity,
+            prominence: None,
+            hysteresis: None,
         }
-        
-        completed
     }
 }
 
 impl RepCounter {
     pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Back Exercises
-            "dumbbell-rows" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "spine".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::VerticalPull,
-            },
-            "barbell-rows" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "spine".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::HorizontalPull,
-            },
-            "seated-dumbbell-rows" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::HorizontalPull,
-            },
-            "chin-up-pull-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.85,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::VerticalPull,
-            },
-            "open-butterfly" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::Isolation,
-            },
-            "lateral-russian-roulette" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: -30.0,
-                range_max: 30.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.6,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Rotation,
-            },
-            "deadlifts" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["knee".to_string(), "spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.7,
-                min_rom_percentage: 0.9,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipHinge,
-            },
-            "pull-ups" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 180.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::VerticalPull,
-            },
-            "face-pulls" => ExerciseProfile {
-                primary_joint: "shoulder".to_string(),
-                secondary_joints: vec!["elbow".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::RearDeltPull,
-            },
-            "t-bar-rows" => ExerciseProfile {
-                primary_joint: "elbow".to_string(),
-                secondary_joints: vec!["shoulder".to_string(), "spine".to_string()],
-                range_min: 90.0,
-                range_max: 180.0,
-                velocity_threshold: 0.45,
-                min_rom_percentage: 0.75,
-                lockout_angle: Some(170.0),
-                stretch_angle: Some(90.0),
-                movement_pattern: MovementPattern::HorizontalPull,
-            },
+        Self::with_profile(ExerciseProfile::lookup(exercise_id).unwrap_or_default())
+    }
 
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Isolation,
-            },
-        };
+    pub fn from_definition(rep: &RepDefinition) -> Self {
+        Self::with_profile(ExerciseProfile::from(rep))
+    }
 
-        RepCounter {
+    fn with_profile(exercise_profile: ExerciseProfile) -> Self {
+        let strategy = exercise_profile.movement_pattern.strategy();
+
+        let mut counter = RepCounter {
             count: 0,
             current_phase: MovementPhase::None,
             last_angles: HashMap::new(),
             last_timestamp: 0.0,
             exercise_profile,
+            strategy,
             velocity_window: Vec::with_capacity(5),
             rom_window: Vec::with_capacity(3),
-        }
+            progress: RepProgress::default(),
+            peaks: None,
+            trace: Vec::new(),
+            trace_count: 0,
+        };
+        counter.set_detector(None);
+        counter
     }
 
-    // ... (keep all the existing methods from previous implementation)
-
-    fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::VerticalPull | MovementPattern::HorizontalPull => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom > 0.9 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::HipHinge => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.2 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 0.8 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::RearDeltPull => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Rotation => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold * 0.7 {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            _ => MovementPhase::None,
-        }
+    // Switches how reps are counted, None goes back to the exercise's detector. The rep
+    // in progress carries over
+    pub fn set_detector(&mut self, detector: Option<RepDetector>) {
+        let detector = detector.unwrap_or(self.exercise_profile.detector);
+        self.peaks = (detector == RepDetector::Peaks).then(|| PeakDetector::new(self.prominence(), self.hysteresis()));
     }
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::VerticalPull | MovementPattern::HorizontalPull => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::HipHinge => {
-                if let Some(lockout) = self.exercise_profile.lockout_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle >= lockout && 
-                           *new_phase == MovementPhase::Eccentric &&
-                           rom >= self.exercise_profile.min_rom_percentage * 0.9 {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::RearDeltPull => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::Rotation => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom.abs() >= self.exercise_profile.min_rom_percentage * 0.6 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
-        }
-        
-        completed
+    pub fn detector(&self) -> RepDetector {
+        if self.peaks.is_some() { RepDetector::Peaks } else { RepDetector::Velocity }
     }
-}
 
-impl RepCounter {
-    pub fn new(exercise_id: &str) -> Self {
-        let exercise_profile = match exercise_id {
-            // Abs Exercises
-            "jack-knife" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipFlexion,
-            },
-            "back_arch" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.6,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::SpinalFlexion,
-            },
-            "lateral-leg-raises" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 75.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.65,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::LegRaise,
-            },
-            "lateral-sit-ups" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: 0.0,
-                range_max: 60.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::Rotation,
-            },
-            "dumbbell-leg-raises" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.3,
-                min_rom_percentage: 0.75,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::LegRaise,
-            },
-            "russian-roulette" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: -45.0,
-                range_max: 45.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.8,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Rotation,
-            },
-            "hanging-leg-raises" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.8,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::LegRaise,
-            },
-            "ab-wheel-rollout" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["hip".to_string(), "shoulder".to_string()],
-                range_min: 0.0,
-                range_max: 60.0,
-                velocity_threshold: 0.5,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::CompoundCore,
-            },
-            "reverse-crunch" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 90.0,
-                velocity_threshold: 0.35,
-                min_rom_percentage: 0.7,
-                lockout_angle: None,
-                stretch_angle: Some(0.0),
-                movement_pattern: MovementPattern::HipFlexion,
-            },
-            "scissor-kicks" => ExerciseProfile {
-                primary_joint: "hip".to_string(),
-                secondary_joints: vec!["spine".to_string()],
-                range_min: 0.0,
-                range_max: 45.0,
-                velocity_threshold: 0.6,
-                min_rom_percentage: 0.5,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::LegRaise,
-            },
-            "plank-hip-dips" => ExerciseProfile {
-                primary_joint: "spine".to_string(),
-                secondary_joints: vec!["hip".to_string()],
-                range_min: -30.0,
-                range_max: 30.0,
-                velocity_threshold: 0.4,
-                min_rom_percentage: 0.6,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::AntiRotation,
-            },
+    fn prominence(&self) -> f32 {
+        let profile = &self.exercise_profile;
+        profile
+            .prominence
+            .unwrap_or(segmentation::DEFAULT_PROMINENCE_SHARE * (profile.range_max - profile.range_min))
+    }
 
-            _ => ExerciseProfile {
-                primary_joint: "".to_string(),
-                secondary_joints: vec![],
-                range_min: 0.0,
-                range_max: 0.0,
-                velocity_threshold: 0.0,
-                min_rom_percentage: 0.0,
-                lockout_angle: None,
-                stretch_angle: None,
-                movement_pattern: MovementPattern::Static,
-            },
-        };
+    fn hysteresis(&self) -> f32 {
+        self.exercise_profile.hysteresis.unwrap_or(segmentation::DEFAULT_HYSTERESIS)
+    }
 
-        RepCounter {
-            count: 0,
-            current_phase: MovementPhase::None,
-            last_angles: HashMap::new(),
-            last_timestamp: 0.0,
-            exercise_profile,
-            velocity_window: Vec::with_capacity(5),
-            rom_window: Vec::with_capacity(3),
+    // The extreme a rep ends on for the peak detector: the top for lifts that finish at
+    // lockout or turn there, the bottom for those finishing on the stretch. Patterns
+    // without moving reps have none
+    fn rep_end(&self) -> Option<Extreme> {
+        match self.strategy.trigger {
+            RepTrigger::Lockout | RepTrigger::Reversal => Some(Extreme::Peak),
+            RepTrigger::Stretch => Some(Extreme::Valley),
+            // The eccentric ends where the angle stops, opposite ends for closing lifts
+            RepTrigger::EccentricOnly if self.strategy.closing => Some(Extreme::Peak),
+            RepTrigger::EccentricOnly => Some(Extreme::Valley),
+            RepTrigger::Hold | RepTrigger::Never | RepTrigger::Stages => None,
         }
     }
 
-    pub fn update(&mut self, angles: &HashMap<String, f32>, timestamp: f32) -> Option<(u32, MovementPhase)> {
-        if self.exercise_profile.primary_joint.is_empty() {
-            return None;
+    // Timestamp in seconds. Returns the report of a rep completed on this frame
+    pub fn update(&mut self, angles: &HashMap<String, f32>, timestamp: f32) -> Option<RepReport> {
+        // An occluded primary joint says nothing about the rep, so the frame is skipped
+        // rather than read as a drop to zero
+        let angle = joint_angle(&self.exercise_profile.primary_joint, angles)?;
+        if self.trace.len() < MAX_TRACE {
+            self.trace.push((timestamp, angle));
         }
 
         // Calculate current ROM percentage
@@ -2437,132 +757,218 @@ impl RepCounter {
         // Detect phase changes based on movement pattern
         let new_phase = self.detect_phase(avg_velocity, avg_rom);
 
+        let dt = if joint_angle(&self.exercise_profile.primary_joint, &self.last_angles).is_some() {
+            timestamp - self.last_timestamp
+        } else {
+            0.0
+        };
+        let last_moving = self.progress.last_moving;
+        self.progress.record(timestamp, angle, avg_velocity, new_phase, dt);
+
         // Check for completed rep with pattern-specific logic
-        let rep_detected = self.check_rep_completion(&new_phase, avg_rom);
+        let rep_detected = if self.peaks.is_some() {
+            self.check_peak_completion(angle, timestamp)
+        } else {
+            self.check_rep_completion(&new_phase, last_moving, avg_rom)
+        };
 
         self.current_phase = new_phase;
         self.last_angles = angles.clone();
         self.last_timestamp = timestamp;
 
         if rep_detected {
-            Some((self.count, self.current_phase))
+            // The frame that completes one rep starts the next
+            let next = RepProgress::starting_at(timestamp, angle, self.progress.last_moving);
+            let progress = std::mem::replace(&mut self.progress, next);
+            Some(self.report(progress, timestamp))
         } else {
             None
         }
     }
 
+    // Ends the rep in progress for patterns whose reps are counted by a stage sequence,
+    // called after update on the frame the sequence completed
+    pub fn complete_rep(&mut self) -> RepReport {
+        self.count += 1;
+        let progress = self.restart_progress();
+        self.report(progress, self.last_timestamp)
+    }
+
+    // The set is over, so the extreme the peak detector is still waiting to confirm counts
+    // without the hysteresis, the way recount counts it. Returns the rep it completes, so
+    // the live count ends the set level with the recount
+    pub fn finish_set(&mut self) -> Option<RepReport> {
+        let end = self.rep_end();
+        let detector = self.peaks.as_mut()?;
+        let started = detector.last_turn().is_some();
+        let turn = detector.flush()?;
+        if !started || Some(turn.extreme) != end {
+            return None;
+        }
+
+        self.count += 1;
+        let progress = self.restart_progress();
+        Some(self.report(progress, turn.timestamp))
+    }
+
+    // Starts the next rep from the last frame, returning the progress of the one it ends
+    fn restart_progress(&mut self) -> RepProgress {
+        let next = match joint_angle(&self.exercise_profile.primary_joint, &self.last_angles) {
+            Some(angle) => RepProgress::starting_at(self.last_timestamp, angle, self.progress.last_moving),
+            None => RepProgress { last_moving: self.progress.last_moving, ..RepProgress::default() },
+        };
+        std::mem::replace(&mut self.progress, next)
+    }
+
+    fn report(&self, progress: RepProgress, timestamp: f32) -> RepReport {
+        let profile = &self.exercise_profile;
+        let min_angle = progress.min_angle.unwrap_or_default();
+        let max_angle = progress.max_angle.unwrap_or_default();
+        let range = profile.range_max - profile.range_min;
+        let covered = max_angle.min(profile.range_max) - min_angle.max(profile.range_min);
+        let rom_percentage = if range > 0.0 { (covered / range).clamp(0.0, 1.0) } else { 0.0 };
+
+        let mut report = RepReport {
+            number: self.count,
+            min_angle,
+            max_angle,
+            rom_percentage,
+            duration: timestamp - progress.started_at.unwrap_or(timestamp),
+            concentric_time: progress.concentric_time,
+            eccentric_time: progress.eccentric_time,
+            bottom_pause_time: progress.bottom_pause_time,
+            top_pause_time: progress.top_pause_time,
+            peak_velocity: progress.peak_velocity,
+            form_issues: Vec::new(),
+            quality: 0.0,
+            tempo_adherence: None,
+            reference: None,
+        };
+        report.score();
+        report
+    }
+
     fn detect_phase(&self, velocity: f32, rom: f32) -> MovementPhase {
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::Crunch | MovementPattern::SpinalFlexion => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::LegRaise | MovementPattern::HipFlexion => {
-                if velocity > self.exercise_profile.velocity_threshold {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold {
-                    MovementPhase::Eccentric
-                } else if rom < 0.1 {
-                    MovementPhase::StaticHold
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::Rotation | MovementPattern::AntiRotation => {
-                if velocity.abs() > self.exercise_profile.velocity_threshold {
-                    if velocity > 0.0 { MovementPhase::Concentric }
-                    else { MovementPhase::Eccentric }
-                } else {
-                    MovementPhase::None
-                }
-            },
-            MovementPattern::CompoundCore => {
-                if velocity > self.exercise_profile.velocity_threshold * 1.5 {
-                    MovementPhase::Concentric
-                } else if velocity < -self.exercise_profile.velocity_threshold * 1.5 {
-                    MovementPhase::Eccentric
-                } else {
-                    MovementPhase::None
-                }
-            },
+        let threshold = if self.peaks.is_some() { STILL_VELOCITY } else { self.exercise_profile.velocity_threshold };
+        let velocity = if self.strategy.closing { -velocity } else { velocity };
+
+        if let Some(factor) = self.strategy.concentric_factor {
+            if velocity > threshold * factor {
+                return MovementPhase::Concentric;
+            }
+        }
+        if let Some(factor) = self.strategy.eccentric_factor {
+            if velocity < -threshold * factor {
+                return MovementPhase::Eccentric;
+            }
+        }
+
+        match self.strategy.hold {
+            HoldRule::AboveRom(limit) if rom > limit => MovementPhase::StaticHold,
+            HoldRule::BelowRom(limit) if rom < limit => MovementPhase::StaticHold,
             _ => MovementPhase::None,
         }
     }
 
-    fn check_rep_completion(&mut self, new_phase: &MovementPhase, rom: f32) -> bool {
-        let mut completed = false;
-        
-        match self.exercise_profile.movement_pattern {
-            MovementPattern::Crunch | MovementPattern::SpinalFlexion => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::LegRaise | MovementPattern::HipFlexion => {
-                if let Some(stretch) = self.exercise_profile.stretch_angle {
-                    if let Some(angle) = self.last_angles.get(&self.exercise_profile.primary_joint) {
-                        if *angle <= stretch && 
-                           *new_phase == MovementPhase::Concentric &&
-                           rom >= self.exercise_profile.min_rom_percentage {
-                            self.count += 1;
-                            completed = true;
-                        }
-                    }
-                }
-            },
-            MovementPattern::Rotation => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom.abs() >= self.exercise_profile.min_rom_percentage {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::AntiRotation => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom.abs() >= self.exercise_profile.min_rom_percentage * 0.5 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            MovementPattern::CompoundCore => {
-                if self.current_phase == MovementPhase::Concentric && 
-                   *new_phase == MovementPhase::Eccentric &&
-                   rom >= self.exercise_profile.min_rom_percentage * 0.8 {
-                    self.count += 1;
-                    completed = true;
-                }
-            },
-            _ => {}
+    // `last_moving` is the direction of travel before this frame, so a still frame at the
+    // turnaround doesn't hide a reversal
+    fn check_rep_completion(&mut self, new_phase: &MovementPhase, last_moving: Option<MovementPhase>, rom: f32) -> bool {
+        // Reps are only counted on a phase change, never while a phase persists
+        if *new_phase == self.current_phase {
+            return false;
+        }
+        // Moving reps need enough travel since the last one, wherever they end. Holds have
+        // no travel to speak of, they need to be deep enough into the range
+        let required = self.exercise_profile.min_rom_percentage * self.strategy.rom_factor;
+        let reached = match self.strategy.trigger {
+            RepTrigger::Hold => rom,
+            _ => self.excursion(),
+        };
+        if reached < required {
+            return false;
+        }
+
+        // The lockout, stretch and reversal are ends of the angle's range, whichever phase
+        // moves towards them
+        let (rising, falling) = if self.strategy.closing {
+            (MovementPhase::Eccentric, MovementPhase::Concentric)
+        } else {
+            (MovementPhase::Concentric, MovementPhase::Eccentric)
+        };
+        let last_angle = joint_angle(&self.exercise_profile.primary_joint, &self.last_angles);
+        let completed = match self.strategy.trigger {
+            RepTrigger::Lockout => {
+                *new_phase == falling && last_angle.is_some_and(|angle| angle >= self.lockout_threshold())
+            },
+            RepTrigger::Stretch => {
+                *new_phase == rising && last_angle.is_some_and(|angle| angle <= self.stretch_threshold())
+            },
+            RepTrigger::Reversal => last_moving == Some(rising) && *new_phase == falling,
+            RepTrigger::EccentricOnly => *new_phase == MovementPhase::Eccentric,
+            RepTrigger::Hold => *new_phase == MovementPhase::StaticHold,
+            RepTrigger::Never | RepTrigger::Stages => false,
+        };
+
+        if completed {
+            self.count += 1;
+        }
+        completed
+    }
+
+    // Share of the profile range the primary joint covered since the last rep, 0-1
+    fn excursion(&self) -> f32 {
+        let range = self.exercise_profile.range_max - self.exercise_profile.range_min;
+        match (self.progress.min_angle, self.progress.max_angle) {
+            (Some(min), Some(max)) if range > 0.0 => ((max - min) / range).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+
+    // The lockout angle, or the top end of the range when there is none or it lies at or
+    // past range_max: the phase only turns once the joint has come back from the end, so
+    // a threshold there is never seen
+    fn lockout_threshold(&self) -> f32 {
+        let profile = &self.exercise_profile;
+        let top = profile.range_max - RANGE_END * (profile.range_max - profile.range_min);
+        profile.lockout_angle.filter(|&lockout| lockout < profile.range_max).unwrap_or(top)
+    }
+
+    // Likewise the stretch angle against range_min
+    fn stretch_threshold(&self) -> f32 {
+        let profile = &self.exercise_profile;
+        let bottom = profile.range_min + RANGE_END * (profile.range_max - profile.range_min);
+        profile.stretch_angle.filter(|&stretch| stretch > profile.range_min).unwrap_or(bottom)
+    }
+
+    // A rep is a swing into the rep's end extreme; the first turn of a set only marks
+    // where it started
+    fn check_peak_completion(&mut self, angle: f32, timestamp: f32) -> bool {
+        let end = self.rep_end();
+        let Some(detector) = &mut self.peaks else {
+            return false;
+        };
+        let started = detector.last_turn().is_some();
+        let completed = detector.push(angle, timestamp).is_some_and(|turn| started && Some(turn.extreme) == end);
+
+        if completed {
+            self.count += 1;
         }
-        
         completed
     }
 
     fn calculate_rom_percentage(&self, angles: &HashMap<String, f32>) -> f32 {
-        if let Some(angle) = angles.get(&self.exercise_profile.primary_joint) {
-            let normalized = (angle - self.exercise_profile.range_min) / 
-                         (self.exercise_profile.range_max - self.exercise_profile.range_min);
-            normalized.max(0.0).min(1.0)
-        } else {
-            0.0
+        let range = self.exercise_profile.range_max - self.exercise_profile.range_min;
+        match joint_angle(&self.exercise_profile.primary_joint, angles) {
+            Some(angle) if range > 0.0 => ((angle - self.exercise_profile.range_min) / range).clamp(0.0, 1.0),
+            _ => 0.0,
         }
     }
 
     fn calculate_velocity(&self, angles: &HashMap<String, f32>, timestamp: f32) -> f32 {
-        if let (Some(current_angle), Some(last_angle)) = (
-            angles.get(&self.exercise_profile.primary_joint),
-            self.last_angles.get(&self.exercise_profile.primary_joint)
-        ) {
+        let primary_joint = &self.exercise_profile.primary_joint;
+        if let (Some(current_angle), Some(last_angle)) =
+            (joint_angle(primary_joint, angles), joint_angle(primary_joint, &self.last_angles))
+        {
             let dt = timestamp - self.last_timestamp;
             if dt > 0.0 {
                 (current_angle - last_angle) / dt
@@ -2582,11 +988,237 @@ impl RepCounter {
         self.current_phase
     }
 
+    pub fn position(&self) -> RepPosition {
+        let rom = (!self.rom_window.is_empty())
+            .then(|| self.rom_window.iter().sum::<f32>() / self.rom_window.len() as f32);
+        RepPosition { phase: self.current_phase, rom }
+    }
+
+    // The exercise's own prescription
+    pub fn tempo(&self) -> Option<Tempo> {
+        self.exercise_profile.tempo
+    }
+
+    // False for unknown exercises, timed holds and patterns left to a stage sequence
+    pub fn counts_reps(&self) -> bool {
+        !self.exercise_profile.primary_joint.is_empty()
+            && !matches!(self.strategy.trigger, RepTrigger::Never | RepTrigger::Stages)
+    }
+
+    pub fn is_isometric(&self) -> bool {
+        self.exercise_profile.movement_pattern.is_isometric()
+    }
+
+    pub fn primary_joint(&self) -> &str {
+        &self.exercise_profile.primary_joint
+    }
+
+    pub fn secondary_joints(&self) -> &[String] {
+        &self.exercise_profile.secondary_joints
+    }
+
+    // Begins the recording recount goes over, e.g. at the start of a set
+    pub fn start_trace(&mut self) {
+        self.trace.clear();
+        self.trace_count = self.count;
+    }
+
+    // Counts the recorded primary-joint signal again with the peak detector, whichever
+    // detector counted live, so a set can be checked afterwards. None for patterns
+    // without moving reps
+    pub fn recount(&self) -> Option<Recount> {
+        let reps = segmentation::recount(&self.trace, self.prominence(), self.hysteresis(), self.rep_end()?);
+        Some(Recount {
+            live: self.count - self.trace_count,
+            recounted: reps.len() as u32,
+            reps,
+        })
+    }
+
     pub fn reset(&mut self) {
         self.count = 0;
         self.current_phase = MovementPhase::None;
         self.last_angles.clear();
         self.velocity_window.clear();
         self.rom_window.clear();
+        self.progress = RepProgress::default();
+        if let Some(detector) = &mut self.peaks {
+            detector.reset();
+        }
+        self.start_trace();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Drives the primary joint through `cycles` cosine cycles over `low..high` at 30 fps,
+    // starting at `high`, and returns the count
+    fn count_cycles(counter: &mut RepCounter, low: f32, high: f32, cycles: usize) -> u32 {
+        let joint = counter.primary_joint().to_string();
+        let period = 2.0;
+        let frames = (cycles as f32 * period * 30.0) as usize;
+        for frame in 0..=frames {
+            let t = frame as f32 / 30.0;
+            let angle = low + (high - low) * (0.5 + 0.5 * (t / period * std::f32::consts::TAU).cos());
+            counter.update(&HashMap::from([(joint.clone(), angle)]), t);
+        }
+        counter.count()
+    }
+
+    fn count_full_range(exercise_id: &str, cycles: usize) -> u32 {
+        let mut counter = RepCounter::new(exercise_id);
+        let profile = ExerciseProfile::lookup(exercise_id).unwrap();
+        count_cycles(&mut counter, profile.range_min, profile.range_max, cycles)
+    }
+
+    // The first departure from the start only begins a rep
+    fn assert_counts(exercise_id: &str) {
+        let count = count_full_range(exercise_id, 6);
+        assert!((5..=6).contains(&count), "{} counted {} reps in 6 cycles", exercise_id, count);
+    }
+
+    #[test]
+    fn lockout_counts_leaving_the_top() {
+        assert_counts("squat");
+        assert_counts("barbell-curls"); // Lockout angle past the range
+        assert_counts("romanian-deadlifts");
+    }
+
+    #[test]
+    fn stretch_counts_leaving_the_bottom() {
+        assert_counts("hanging-leg-raises");
+        assert_counts("jack-knife");
+        assert_counts("curtsy-lunges");
+        assert_counts("scissor-kicks"); // No stretch angle
+    }
+
+    #[test]
+    fn reversal_counts_turning_at_the_top() {
+        assert_counts("lateral-dumbbell-raises");
+        assert_counts("glute-bridge");
+    }
+
+    #[test]
+    fn eccentric_only_counts_the_lowering() {
+        assert_counts("nordic-hamstring-curls");
+    }
+
+    #[test]
+    fn hold_counts_reaching_the_hold() {
+        let mut counter = RepCounter::new("farmer-walk-on-toes");
+        let joint = counter.primary_joint().to_string();
+        for frame in 0..=90 {
+            let t = frame as f32 / 30.0;
+            let angle = if t < 1.0 { 135.0 + 45.0 * t } else { 178.0 };
+            counter.update(&HashMap::from([(joint.clone(), angle)]), t);
+        }
+        assert_eq!(counter.count(), 1);
+    }
+
+    #[test]
+    fn timed_and_staged_patterns_never_count() {
+        assert_eq!(count_full_range("plank", 6), 0);
+        assert_eq!(count_full_range("burpees", 6), 0);
+    }
+
+    #[test]
+    fn every_moving_profile_counts_full_cycles() {
+        for row in EXERCISE_PROFILES {
+            let trigger = row.9.strategy().trigger;
+            if !matches!(trigger, RepTrigger::Hold | RepTrigger::Never | RepTrigger::Stages) {
+                assert_counts(row.0);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_reps_are_not_counted() {
+        let mut counter = RepCounter::new("squat");
+        assert_eq!(count_cycles(&mut counter, 150.0, 180.0, 6), 0);
+    }
+
+    // Real reps stop short of full extension and go no deeper than they have to
+    #[test]
+    fn parallel_squats_count() {
+        for exercise_id in ["squat", "front-squats", "goblet-squats"] {
+            let count = count_cycles(&mut RepCounter::new(exercise_id), 90.0, 175.0, 6);
+            assert!((5..=6).contains(&count), "{} counted {} parallel squats", exercise_id, count);
+        }
+        assert_eq!(count_cycles(&mut RepCounter::new("squat"), 125.0, 175.0, 6), 0);
+    }
+
+    // The hip is measured shoulder-hip-knee, so standing tall reads near 180
+    #[test]
+    fn hinges_count_on_the_interior_hip_angle() {
+        // Deadlifts start from the floor with the hips well below the shoulders
+        for (exercise_id, low) in [("romanian-deadlifts", 95.0), ("good-morning", 95.0), ("deadlifts", 75.0)] {
+            let count = count_cycles(&mut RepCounter::new(exercise_id), low, 175.0, 6);
+            assert!((5..=6).contains(&count), "{} counted {} hinges", exercise_id, count);
+        }
+        let bridges = count_cycles(&mut RepCounter::new("glute-bridge"), 135.0, 178.0, 6);
+        assert!((5..=6).contains(&bridges), "counted {} bridges", bridges);
+        assert_eq!(count_cycles(&mut RepCounter::new("romanian-deadlifts"), 140.0, 175.0, 6), 0);
+    }
+
+    // Raising the legs closes the hip from a straight hang
+    #[test]
+    fn leg_raises_count_on_the_interior_hip_angle() {
+        let count = count_cycles(&mut RepCounter::new("hanging-leg-raises"), 95.0, 178.0, 6);
+        assert!((5..=6).contains(&count), "counted {} leg raises", count);
+        assert_eq!(count_cycles(&mut RepCounter::new("hanging-leg-raises"), 150.0, 178.0, 6), 0);
+    }
+
+    #[test]
+    fn curls_need_most_of_the_range() {
+        let full = count_cycles(&mut RepCounter::new("barbell-curls"), 50.0, 165.0, 6);
+        assert!((5..=6).contains(&full), "counted {} full curls", full);
+        // Stopping at a right angle is half a curl
+        assert_eq!(count_cycles(&mut RepCounter::new("barbell-curls"), 95.0, 165.0, 6), 0);
+    }
+
+    #[test]
+    fn curls_concentric_is_the_closing_elbow() {
+        // 3-0-1-0: lowered over three seconds from 50 to 165, curled back up in one
+        let mut counter = RepCounter::new("barbell-curls");
+        let mut reports = Vec::new();
+        for frame in 0..=(4 * 30 * 4) {
+            let t = frame as f32 / 30.0;
+            let cycle = t % 4.0;
+            let angle = if cycle < 3.0 { 50.0 + 115.0 * cycle / 3.0 } else { 165.0 - 115.0 * (cycle - 3.0) };
+            reports.extend(counter.update(&HashMap::from([("elbow_flexion".to_string(), angle)]), t));
+        }
+        // The first rep is the lowering from where the set started
+        assert_eq!(reports.len(), 4);
+        let tempo = Tempo::parse("3-0-1-0").unwrap();
+        for report in &reports[1..] {
+            assert!((report.eccentric_time - 3.0).abs() < 0.3, "{:?}", report);
+            assert!((report.concentric_time - 1.0).abs() < 0.3, "{:?}", report);
+            assert!(tempo.check(report).is_empty(), "{:?}", tempo.check(report));
+        }
+    }
+
+    #[test]
+    fn bench_presses_count_touching_the_chest() {
+        let count = count_cycles(&mut RepCounter::new("bench-press-barbell"), 85.0, 172.0, 6);
+        assert!((5..=6).contains(&count), "counted {} presses", count);
+    }
+
+    #[test]
+    fn peak_detector_counts_noisy_reps_once() {
+        let mut counter = RepCounter::new("squat");
+        counter.set_detector(Some(RepDetector::Peaks));
+        for frame in 0..=360 {
+            let t = frame as f32 / 30.0;
+            let jitter = if frame % 2 == 0 { 4.0 } else { -4.0 };
+            let angle = 120.0 + 55.0 * (t / 2.0 * std::f32::consts::TAU).cos() + jitter;
+            counter.update(&HashMap::from([("knee".to_string(), angle)]), t);
+        }
+        // The last top is still within the hysteresis until the set ends
+        assert_eq!(counter.count(), 5);
+        assert_eq!(counter.finish_set().map(|report| report.number), Some(6));
+        assert_eq!(counter.count(), counter.recount().unwrap().recounted);
+        assert!(counter.finish_set().is_none());
     }
 }
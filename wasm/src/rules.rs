@@ -0,0 +1,947 @@
+// wasm/src/rules.rs
+// Form rules written as one-line expressions and evaluated per frame, e.g.
+//   knee_flexion < 75 during phase=bottom for 200ms => "Go deeper"
+//   abs(hip - knee) > 30 => critical "Hip-knee synchronization off"
+//   rotation not in 45..135 => info "Complete rotational movement"
+//
+//   rule      := condition ["during" "phase" "=" phase {"|" phase}] ["for" duration] "=>" [severity] string
+//   condition := and {"or" and}
+//   and       := not {"and" not}
+//   not       := "not" not | compare
+//   compare   := sum [("<" | "<=" | ">" | ">=" | "==" | "!=") sum | ["not"] "in" sum ".." sum]
+//   sum       := product {("+" | "-") product}
+//   product   := unary {("*" | "/") unary}
+//   unary     := "-" unary | number | name | ("abs" | "min" | "max") "(" sum {"," sum} ")" | "(" condition ")"
+//
+// Names are angle keys from the calculate_*_angles maps, plus `rom` (0-1 through the rep
+// range), `reps` and `phase_ms` (time spent in the current phase). A rule reading an angle
+// that isn't visible this frame doesn't fire. Phases are the MovementPhase names, "up",
+// "down" and "hold", or "bottom"/"top" for the low/high end of the rep range.
+// Durations take "ms" or "s"; the condition has to hold that long before the rule fires
+use std::collections::HashMap;
+
+use crate::form::{FormIssue, Severity, TargetRange};
+use crate::logging;
+use crate::rep_counter::{MovementPhase, RepPosition};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f32),
+    Name(String),
+    Str(String),
+    Op(&'static str),
+}
+
+// Longest first so "<=" isn't read as "<" "="
+const OPERATORS: [&str; 17] = ["=>", "<=", ">=", "==", "!=", "..", "<", ">", "=", "+", "-", "*", "/", "(", ")", ",", "|"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse().map_err(|_| format!("bad number '{}'", text))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if c == '"' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some('"') => break,
+                    Some('\\') if chars.get(i + 1).is_some() => {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+            i += 1;
+            tokens.push(Token::Str(text));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .ok_or_else(|| format!("unexpected '{}'", c))?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Var {
+    Rom,
+    Reps,
+    PhaseMs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Abs,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CmpOp {
+    fn from_op(op: &str) -> Option<CmpOp> {
+        match op {
+            "<" => Some(CmpOp::Lt),
+            "<=" => Some(CmpOp::Le),
+            ">" => Some(CmpOp::Gt),
+            ">=" => Some(CmpOp::Ge),
+            "==" => Some(CmpOp::Eq),
+            "!=" => Some(CmpOp::Ne),
+            _ => None,
+        }
+    }
+
+    // The same comparison with its operands swapped
+    fn flipped(self) -> CmpOp {
+        match self {
+            CmpOp::Lt => CmpOp::Gt,
+            CmpOp::Le => CmpOp::Ge,
+            CmpOp::Gt => CmpOp::Lt,
+            CmpOp::Ge => CmpOp::Le,
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f32),
+    Angle(String),
+    Var(Var),
+    Neg(Box<Expr>),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+    Compare(CmpOp, Box<Expr>, Box<Expr>),
+    InRange { value: Box<Expr>, min: Box<Expr>, max: Box<Expr>, negated: bool },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn is_condition(&self) -> bool {
+        matches!(self, Expr::Compare(..) | Expr::InRange { .. } | Expr::Not(_) | Expr::And(..) | Expr::Or(..))
+    }
+
+    fn number(&self, context: &RuleContext) -> Option<f32> {
+        match self {
+            Expr::Number(value) => Some(*value),
+            Expr::Angle(key) => context.angles.get(key).copied(),
+            Expr::Var(Var::Rom) => context.position.rom,
+            Expr::Var(Var::Reps) => Some(context.reps as f32),
+            Expr::Var(Var::PhaseMs) => Some(context.phase_ms as f32),
+            Expr::Neg(inner) => inner.number(context).map(|value| -value),
+            Expr::Arith(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.number(context)?, rhs.number(context)?);
+                match op {
+                    ArithOp::Add => Some(lhs + rhs),
+                    ArithOp::Sub => Some(lhs - rhs),
+                    ArithOp::Mul => Some(lhs * rhs),
+                    ArithOp::Div if rhs != 0.0 => Some(lhs / rhs),
+                    ArithOp::Div => None,
+                }
+            }
+            Expr::Call(func, args) => {
+                let values = args.iter().map(|arg| arg.number(context)).collect::<Option<Vec<f32>>>()?;
+                match func {
+                    Func::Abs => values.first().map(|value| value.abs()),
+                    Func::Min => values.into_iter().reduce(f32::min),
+                    Func::Max => values.into_iter().reduce(f32::max),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // None when an angle involved isn't visible, so the answer is unknown
+    fn truth(&self, context: &RuleContext) -> Option<bool> {
+        match self {
+            Expr::Compare(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.number(context)?, rhs.number(context)?);
+                Some(match op {
+                    CmpOp::Lt => lhs < rhs,
+                    CmpOp::Le => lhs <= rhs,
+                    CmpOp::Gt => lhs > rhs,
+                    CmpOp::Ge => lhs >= rhs,
+                    CmpOp::Eq => lhs == rhs,
+                    CmpOp::Ne => lhs != rhs,
+                })
+            }
+            Expr::InRange { value, min, max, negated } => {
+                let value = value.number(context)?;
+                let inside = value >= min.number(context)? && value <= max.number(context)?;
+                Some(inside != *negated)
+            }
+            Expr::Not(inner) => inner.truth(context).map(|value| !value),
+            Expr::And(lhs, rhs) => match (lhs.truth(context), rhs.truth(context)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Expr::Or(lhs, rhs) => match (lhs.truth(context), rhs.truth(context)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn collect_angles<'a>(&'a self, keys: &mut Vec<&'a str>) {
+        match self {
+            Expr::Angle(key) => {
+                if !keys.contains(&key.as_str()) {
+                    keys.push(key);
+                }
+            }
+            Expr::Neg(inner) | Expr::Not(inner) => inner.collect_angles(keys),
+            Expr::Arith(_, lhs, rhs) | Expr::Compare(_, lhs, rhs) | Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.collect_angles(keys);
+                rhs.collect_angles(keys);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|arg| arg.collect_angles(keys)),
+            Expr::InRange { value, min, max, .. } => {
+                value.collect_angles(keys);
+                min.collect_angles(keys);
+                max.collect_angles(keys);
+            }
+            Expr::Number(_) | Expr::Var(_) => {}
+        }
+    }
+}
+
+// Deepest nesting of parentheses, calls and prefix operators a rule may use. Each level
+// is a recursive call, so without a limit a long enough rule would overflow the stack
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Token::Op(found)) if *found == op)
+    }
+
+    fn at_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(found)) if found == name)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = self.at_op(op);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_name(&mut self, name: &str) -> bool {
+        let found = self.at_name(name);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), String> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' {}", op, self.position()))
+        }
+    }
+
+    // Parses a nested part of the rule one level deeper
+    fn nested(&mut self, parse: fn(&mut Parser) -> Result<Expr, String>) -> Result<Expr, String> {
+        if self.depth == MAX_DEPTH {
+            return Err("rule nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn position(&self) -> String {
+        match self.peek() {
+            Some(Token::Number(value)) => format!("before {}", value),
+            Some(Token::Name(name)) => format!("before '{}'", name),
+            Some(Token::Str(text)) => format!("before \"{}\"", text),
+            Some(Token::Op(op)) => format!("before '{}'", op),
+            None => "at end of rule".to_string(),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.conjunction()?;
+        while self.eat_name("or") {
+            let rhs = self.conjunction()?;
+            lhs = Expr::Or(Box::new(as_condition(lhs)?), Box::new(as_condition(rhs)?));
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.negation()?;
+        while self.eat_name("and") {
+            let rhs = self.negation()?;
+            lhs = Expr::And(Box::new(as_condition(lhs)?), Box::new(as_condition(rhs)?));
+        }
+        Ok(lhs)
+    }
+
+    fn negation(&mut self) -> Result<Expr, String> {
+        if self.eat_name("not") {
+            let inner = self.nested(Parser::negation)?;
+            return Ok(Expr::Not(Box::new(as_condition(inner)?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.sum()?;
+
+        if let Some(Token::Op(op)) = self.peek() {
+            if let Some(cmp) = CmpOp::from_op(op) {
+                self.pos += 1;
+                let rhs = self.sum()?;
+                return Ok(Expr::Compare(cmp, Box::new(as_number(lhs)?), Box::new(as_number(rhs)?)));
+            }
+        }
+
+        let negated = self.at_name("not") && matches!(self.tokens.get(self.pos + 1), Some(Token::Name(name)) if name == "in");
+        if negated {
+            self.pos += 1;
+        }
+        if self.eat_name("in") {
+            let min = self.sum()?;
+            self.expect_op("..")?;
+            let max = self.sum()?;
+            return Ok(Expr::InRange {
+                value: Box::new(as_number(lhs)?),
+                min: Box::new(as_number(min)?),
+                max: Box::new(as_number(max)?),
+                negated,
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.product()?;
+        loop {
+            let op = if self.eat_op("+") {
+                ArithOp::Add
+            } else if self.eat_op("-") {
+                ArithOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.product()?;
+            lhs = Expr::Arith(op, Box::new(as_number(lhs)?), Box::new(as_number(rhs)?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat_op("*") {
+                ArithOp::Mul
+            } else if self.eat_op("/") {
+                ArithOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.unary()?;
+            lhs = Expr::Arith(op, Box::new(as_number(lhs)?), Box::new(as_number(rhs)?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_op("-") {
+            let inner = self.nested(Parser::unary)?;
+            return Ok(Expr::Neg(Box::new(as_number(inner)?)));
+        }
+        if self.eat_op("(") {
+            let inner = self.nested(Parser::condition)?;
+            self.expect_op(")")?;
+            return Ok(inner);
+        }
+
+        let position = self.position();
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Name(name)) => {
+                let func = match name.as_str() {
+                    "abs" => Some(Func::Abs),
+                    "min" => Some(Func::Min),
+                    "max" => Some(Func::Max),
+                    _ => None,
+                };
+                if let (Some(func), true) = (func, self.at_op("(")) {
+                    return self.call(func);
+                }
+
+                match name.as_str() {
+                    "rom" => Ok(Expr::Var(Var::Rom)),
+                    "reps" => Ok(Expr::Var(Var::Reps)),
+                    "phase_ms" => Ok(Expr::Var(Var::PhaseMs)),
+                    "and" | "or" | "not" | "in" | "during" | "for" | "phase" => {
+                        Err(format!("expected a value {}", position))
+                    }
+                    _ => Ok(Expr::Angle(name)),
+                }
+            }
+            _ => Err(format!("expected a value {}", position)),
+        }
+    }
+
+    fn call(&mut self, func: Func) -> Result<Expr, String> {
+        self.expect_op("(")?;
+        let mut args = vec![as_number(self.nested(Parser::sum)?)?];
+        while self.eat_op(",") {
+            args.push(as_number(self.nested(Parser::sum)?)?);
+        }
+        self.expect_op(")")?;
+
+        if func == Func::Abs && args.len() != 1 {
+            return Err(format!("abs takes one argument, got {}", args.len()));
+        }
+        Ok(Expr::Call(func, args))
+    }
+
+    fn phases(&mut self) -> Result<Vec<PhaseFilter>, String> {
+        if !self.eat_name("phase") {
+            return Err(format!("expected 'phase' {}", self.position()));
+        }
+        if !self.eat_op("=") {
+            self.expect_op("==")?;
+        }
+
+        let mut phases = Vec::new();
+        loop {
+            let position = self.position();
+            match self.next() {
+                Some(Token::Name(name)) => {
+                    phases.push(PhaseFilter::from_name(&name).ok_or_else(|| format!("unknown phase '{}'", name))?);
+                }
+                _ => return Err(format!("expected a phase name {}", position)),
+            }
+            if !self.eat_op("|") {
+                return Ok(phases);
+            }
+        }
+    }
+
+    fn duration(&mut self) -> Result<f64, String> {
+        let position = self.position();
+        let Some(Token::Number(value)) = self.next() else {
+            return Err(format!("expected a duration {}", position));
+        };
+        let scale = match self.next() {
+            Some(Token::Name(unit)) if unit == "ms" => 1.0,
+            Some(Token::Name(unit)) if unit == "s" => 1000.0,
+            _ => return Err(format!("duration {} needs a unit, ms or s", value)),
+        };
+        Ok(value as f64 * scale)
+    }
+}
+
+fn as_number(expr: Expr) -> Result<Expr, String> {
+    if expr.is_condition() {
+        Err("expected a number, found a comparison".to_string())
+    } else {
+        Ok(expr)
+    }
+}
+
+fn as_condition(expr: Expr) -> Result<Expr, String> {
+    if expr.is_condition() {
+        Ok(expr)
+    } else {
+        Err("expected a comparison, found a number".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PhaseFilter {
+    Movement(MovementPhase),
+    Bottom,
+    Top,
+}
+
+impl PhaseFilter {
+    fn from_name(name: &str) -> Option<PhaseFilter> {
+        match name {
+            "concentric" | "up" => Some(PhaseFilter::Movement(MovementPhase::Concentric)),
+            "eccentric" | "down" => Some(PhaseFilter::Movement(MovementPhase::Eccentric)),
+            "static_hold" | "hold" => Some(PhaseFilter::Movement(MovementPhase::StaticHold)),
+            "none" => Some(PhaseFilter::Movement(MovementPhase::None)),
+            "bottom" => Some(PhaseFilter::Bottom),
+            "top" => Some(PhaseFilter::Top),
+            _ => None,
+        }
+    }
+
+    fn matches(self, context: &RuleContext) -> bool {
+        match self {
+            PhaseFilter::Movement(phase) => context.position.phase == phase,
+            PhaseFilter::Bottom => context.position.at_bottom(),
+            PhaseFilter::Top => context.position.at_top(),
+        }
+    }
+}
+
+// What a rule can see on one frame
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    pub angles: &'a HashMap<String, f32>,
+    pub position: RepPosition,
+    pub reps: u32,
+    pub phase_ms: f64,  // Time spent in the current phase
+    pub timestamp: f64, // Frame time in milliseconds
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    code: String,
+    condition: Expr,
+    phases: Vec<PhaseFilter>, // Empty matches any phase
+    hold_ms: f64,
+    severity: Severity,
+    cue: String,
+}
+
+impl Rule {
+    // `default_severity` applies when the rule doesn't name one after "=>"
+    pub fn parse(code: &str, source: &str, default_severity: Severity) -> Result<Rule, String> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, depth: 0 };
+
+        let condition = as_condition(parser.condition()?)?;
+        let phases = if parser.eat_name("during") { parser.phases()? } else { Vec::new() };
+        let hold_ms = if parser.eat_name("for") { parser.duration()? } else { 0.0 };
+        parser.expect_op("=>")?;
+
+        let severity = match parser.peek() {
+            Some(Token::Name(name)) => {
+                let severity = match name.as_str() {
+                    "info" => Severity::Info,
+                    "warning" => Severity::Warning,
+                    "critical" => Severity::Critical,
+                    _ => return Err(format!("unknown severity '{}'", name)),
+                };
+                parser.pos += 1;
+                severity
+            }
+            _ => default_severity,
+        };
+
+        let position = parser.position();
+        let Some(Token::Str(cue)) = parser.next() else {
+            return Err(format!("expected the cue in quotes {}", position));
+        };
+        if parser.peek().is_some() {
+            return Err(format!("unexpected text {}", parser.position()));
+        }
+
+        Ok(Rule { code: code.to_string(), condition, phases, hold_ms, severity, cue })
+    }
+
+    // Plain range check: fires when the angle leaves [min, max]
+    pub fn range(code: &str, angle: &str, min: Option<f32>, max: Option<f32>, severity: Severity, cue: &str) -> Rule {
+        let value = || Box::new(Expr::Angle(angle.to_string()));
+        let condition = match (min, max) {
+            (Some(min), Some(max)) => Expr::InRange {
+                value: value(),
+                min: Box::new(Expr::Number(min)),
+                max: Box::new(Expr::Number(max)),
+                negated: true,
+            },
+            (Some(min), None) => Expr::Compare(CmpOp::Lt, value(), Box::new(Expr::Number(min))),
+            (None, Some(max)) => Expr::Compare(CmpOp::Gt, value(), Box::new(Expr::Number(max))),
+            // Nothing to check against
+            (None, None) => Expr::InRange {
+                value: value(),
+                min: Box::new(Expr::Number(f32::NEG_INFINITY)),
+                max: Box::new(Expr::Number(f32::INFINITY)),
+                negated: true,
+            },
+        };
+
+        Rule {
+            code: code.to_string(),
+            condition,
+            phases: Vec::new(),
+            hold_ms: 0.0,
+            severity,
+            cue: cue.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    // Angle keys the rule reads
+    pub fn angles(&self) -> Vec<&str> {
+        let mut keys = Vec::new();
+        self.condition.collect_angles(&mut keys);
+        keys
+    }
+
+    fn in_phase(&self, context: &RuleContext) -> bool {
+        self.phases.is_empty() || self.phases.iter().any(|phase| phase.matches(context))
+    }
+
+    // A single "angle <op> limit" or "angle not in a..b" condition also fills in the
+    // measurement and target, anything more involved only names the joint
+    fn issue(&self, context: &RuleContext) -> FormIssue {
+        let issue = FormIssue::new(self.code.as_str(), self.severity, self.cue.as_str());
+
+        let limit = match &self.condition {
+            Expr::Compare(op, lhs, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+                (Expr::Angle(key), Expr::Number(limit)) => Some((key, *op, *limit)),
+                (Expr::Number(limit), Expr::Angle(key)) => Some((key, op.flipped(), *limit)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((key, op, limit)) = limit {
+            let issue = match context.angles.get(key) {
+                Some(&value) => issue.measured(key, value),
+                None => issue.on_joint(key),
+            };
+            return match op {
+                CmpOp::Lt | CmpOp::Le => issue.at_least(limit),
+                CmpOp::Gt | CmpOp::Ge => issue.at_most(limit),
+                CmpOp::Eq | CmpOp::Ne => issue,
+            };
+        }
+
+        if let Expr::InRange { value, min, max, negated: true } = &self.condition {
+            if let (Expr::Angle(key), Expr::Number(min), Expr::Number(max)) = (value.as_ref(), min.as_ref(), max.as_ref()) {
+                let issue = match context.angles.get(key) {
+                    Some(&value) => issue.measured(key, value),
+                    None => issue.on_joint(key),
+                };
+                return issue.within(TargetRange {
+                    min: min.is_finite().then_some(*min),
+                    max: max.is_finite().then_some(*max),
+                });
+            }
+        }
+
+        match self.angles().as_slice() {
+            [key] => issue.on_joint(key),
+            _ => issue,
+        }
+    }
+}
+
+// Rules for one exercise plus how long each has been holding
+#[derive(Debug, Default)]
+pub struct RuleSet {
+    rules: Vec<(Rule, Option<f64>)>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        RuleSet { rules: rules.into_iter().map(|rule| (rule, None)).collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn evaluate(&mut self, context: &RuleContext) -> Vec<FormIssue> {
+        let mut issues = Vec::new();
+
+        for (rule, since) in &mut self.rules {
+            let holds = rule.in_phase(context) && rule.condition.truth(context) == Some(true);
+            if !holds {
+                *since = None;
+                continue;
+            }
+
+            let start = *since.get_or_insert(context.timestamp);
+            if context.timestamp - start >= rule.hold_ms {
+                issues.push(rule.issue(context));
+            }
+        }
+
+        issues
+    }
+
+    pub fn reset(&mut self) {
+        for (_, since) in &mut self.rules {
+            *since = None;
+        }
+    }
+}
+
+// A bare condition, the part of a rule before "during"/"for"/"=>", for callers that only
+// need to know whether a pose matches (see sequence.rs)
+#[derive(Debug, Clone)]
+pub struct Condition(Expr);
+
+impl Condition {
+    pub fn parse(source: &str) -> Result<Condition, String> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, depth: 0 };
+        let condition = as_condition(parser.condition()?)?;
+        if parser.peek().is_some() {
+            return Err(format!("unexpected text {}", parser.position()));
+        }
+        Ok(Condition(condition))
+    }
+
+    // None when an angle involved isn't visible
+    pub fn holds(&self, context: &RuleContext) -> Option<bool> {
+        self.0.truth(context)
+    }
+
+    pub fn angles(&self) -> Vec<&str> {
+        let mut keys = Vec::new();
+        self.0.collect_angles(&mut keys);
+        keys
+    }
+}
+
+// Built-in rule tables: (exercise id, issue code, rule). A broken row is logged and
+// skipped rather than taking the exercise down
+pub fn from_table(table: &[(&str, &str, &str)], exercise_id: &str) -> Vec<Rule> {
+    table
+        .iter()
+        .filter(|(id, _, _)| *id == exercise_id)
+        .filter_map(|(_, code, source)| match Rule::parse(code, source, Severity::Warning) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                logging::error("rules", &format!("Rule '{}' for {}: {}", code, exercise_id, e));
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn angles(pairs: &[(&str, f32)]) -> HashMap<String, f32> {
+        pairs.iter().map(|&(key, value)| (key.to_string(), value)).collect()
+    }
+
+    fn context(angles: &HashMap<String, f32>, rom: f32, timestamp: f64) -> RuleContext<'_> {
+        RuleContext {
+            angles,
+            position: RepPosition { phase: MovementPhase::Eccentric, rom: Some(rom) },
+            reps: 3,
+            phase_ms: 400.0,
+            timestamp,
+        }
+    }
+
+    fn holds(source: &str, angles: &HashMap<String, f32>) -> Option<bool> {
+        Condition::parse(source).unwrap().holds(&context(angles, 0.5, 0.0))
+    }
+
+    #[test]
+    fn tokenizes_operators_longest_first() {
+        let tokens = tokenize("a<=1.5=>\"x \\\"y\\\"\"").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Token::Name("a".to_string()),
+                Token::Op("<="),
+                Token::Number(1.5),
+                Token::Op("=>"),
+                Token::Str("x \"y\"".to_string()),
+            ]
+        );
+        assert_eq!(tokenize("45..135").unwrap(), [Token::Number(45.0), Token::Op(".."), Token::Number(135.0)]);
+    }
+
+    #[test]
+    fn parses_the_documented_examples() {
+        let rule = Rule::parse("depth", "knee_flexion < 75 during phase=bottom for 200ms => \"Go deeper\"", Severity::Warning).unwrap();
+        assert_eq!((rule.code(), rule.angles(), rule.hold_ms), ("depth", vec!["knee_flexion"], 200.0));
+        assert_eq!(rule.phases, [PhaseFilter::Bottom]);
+        assert_eq!(rule.severity, Severity::Warning);
+
+        let rule = Rule::parse("sync", "abs(hip - knee) > 30 => critical \"Hip-knee synchronization off\"", Severity::Warning).unwrap();
+        assert_eq!(rule.severity, Severity::Critical);
+        assert_eq!(rule.angles(), ["hip", "knee"]);
+
+        let rule = Rule::parse("rotation", "rotation not in 45..135 => info \"Complete rotational movement\"", Severity::Warning).unwrap();
+        assert_eq!(rule.severity, Severity::Info);
+        assert_eq!(Rule::parse("x", "a > 1 for 1.5s => \"x\"", Severity::Info).unwrap().hold_ms, 1500.0);
+    }
+
+    #[test]
+    fn evaluates_arithmetic_and_logic() {
+        let angles = angles(&[("hip", 100.0), ("knee", 60.0)]);
+        assert_eq!(holds("knee + 10 * 2 == 80", &angles), Some(true));
+        assert_eq!(holds("(knee + 10) * 2 == 140", &angles), Some(true));
+        assert_eq!(holds("-knee < -50", &angles), Some(true));
+        assert_eq!(holds("abs(knee - hip) >= 40 and max(hip, knee, 120) == 120", &angles), Some(true));
+        assert_eq!(holds("min(hip, knee) > 70 or not hip in 90..110", &angles), Some(false));
+        assert_eq!(holds("knee not in 70..90", &angles), Some(true));
+        assert_eq!(holds("rom == 0.5 and reps == 3 and phase_ms >= 400", &angles), Some(true));
+    }
+
+    #[test]
+    fn missing_angles_never_fire() {
+        let angles = angles(&[("knee", 60.0)]);
+        assert_eq!(holds("ankle < 90", &angles), None);
+        let mut rules = RuleSet::new(vec![Rule::parse("x", "ankle < 90 => \"x\"", Severity::Warning).unwrap()]);
+        assert!(rules.evaluate(&context(&angles, 0.5, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn phase_filters_gate_rules() {
+        let angles = angles(&[("knee", 60.0)]);
+        let rule = Rule::parse("x", "knee < 75 during phase=bottom|top => \"x\"", Severity::Warning).unwrap();
+        let mut rules = RuleSet::new(vec![rule]);
+        assert_eq!(rules.evaluate(&context(&angles, 0.1, 0.0)).len(), 1);
+        assert!(rules.evaluate(&context(&angles, 0.5, 0.0)).is_empty());
+        assert_eq!(rules.evaluate(&context(&angles, 0.9, 0.0)).len(), 1);
+
+        let rule = Rule::parse("x", "knee < 75 during phase=up => \"x\"", Severity::Warning).unwrap();
+        assert!(RuleSet::new(vec![rule]).evaluate(&context(&angles, 0.5, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn for_waits_until_the_condition_has_held_long_enough() {
+        let rule = Rule::parse("x", "knee < 75 for 200ms => \"x\"", Severity::Warning).unwrap();
+        let mut rules = RuleSet::new(vec![rule]);
+        let (bent, straight) = (angles(&[("knee", 60.0)]), angles(&[("knee", 90.0)]));
+
+        assert!(rules.evaluate(&context(&bent, 0.5, 1000.0)).is_empty());
+        assert!(rules.evaluate(&context(&bent, 0.5, 1150.0)).is_empty());
+        assert_eq!(rules.evaluate(&context(&bent, 0.5, 1200.0)).len(), 1);
+
+        // Breaking the condition starts the wait over
+        assert!(rules.evaluate(&context(&straight, 0.5, 1233.0)).is_empty());
+        assert!(rules.evaluate(&context(&bent, 0.5, 1266.0)).is_empty());
+        assert!(rules.evaluate(&context(&bent, 0.5, 1400.0)).is_empty());
+        assert_eq!(rules.evaluate(&context(&bent, 0.5, 1466.0)).len(), 1);
+
+        rules.reset();
+        assert!(rules.evaluate(&context(&bent, 0.5, 1500.0)).is_empty());
+    }
+
+    #[test]
+    fn issues_carry_the_measurement_and_target() {
+        let angles = angles(&[("knee", 60.0), ("rotation", 30.0)]);
+        let rule = Rule::parse("depth", "knee < 75 => \"Go deeper\"", Severity::Warning).unwrap();
+        let issue = rule.issue(&context(&angles, 0.5, 0.0));
+        assert_eq!((issue.joint.as_deref(), issue.measured), (Some("knee"), Some(60.0)));
+        assert_eq!(issue.target, TargetRange { min: Some(75.0), max: None });
+        assert_eq!(issue.message, "Go deeper");
+
+        let rule = Rule::parse("rotation", "rotation not in 45..135 => \"x\"", Severity::Warning).unwrap();
+        let issue = rule.issue(&context(&angles, 0.5, 0.0));
+        assert_eq!(issue.target, TargetRange { min: Some(45.0), max: Some(135.0) });
+
+        let issue = Rule::range("knee_high", "knee", None, Some(50.0), Severity::Info, "x").issue(&context(&angles, 0.5, 0.0));
+        assert_eq!(issue.target, TargetRange { min: None, max: Some(50.0) });
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        let error = |source: &str| Rule::parse("x", source, Severity::Warning).unwrap_err();
+        assert!(error("knee < 75").contains("=>"));
+        assert!(error("knee < 75 =>").contains("cue"));
+        assert!(error("knee < 75 => severe \"x\"").contains("unknown severity"));
+        assert!(error("knee + 75 => \"x\"").contains("comparison"));
+        assert!(error("knee < 75 during phase=sideways => \"x\"").contains("unknown phase"));
+        assert!(error("knee < 75 for 200 => \"x\"").contains("unit"));
+        assert!(error("knee < 75 => \"x").contains("unterminated"));
+        assert!(error("knee < 75 => \"x\" extra").contains("unexpected"));
+        assert!(error("knee # 75 => \"x\"").contains("unexpected '#'"));
+        assert!(Condition::parse("knee < 75 and").is_err());
+        assert!(Condition::parse("knee").is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_stack_overflow() {
+        let nested = |open: &str, close: &str, depth: usize| format!("{}knee{} < 75", open.repeat(depth), close.repeat(depth));
+        assert!(Condition::parse(&nested("(", ")", 60)).is_ok());
+        for source in [nested("(", ")", 1000), nested("abs(", ")", 1000), nested("-", "", 1000), format!("{}knee < 75", "not ".repeat(1000))] {
+            assert_eq!(Condition::parse(&source).unwrap_err(), "rule nested too deeply");
+        }
+        let rule = format!("{}knee{} < 75 => \"x\"", "(".repeat(1000), ")".repeat(1000));
+        assert_eq!(Rule::parse("x", &rule, Severity::Warning).unwrap_err(), "rule nested too deeply");
+    }
+
+    #[test]
+    fn broken_table_rows_are_skipped() {
+        let table = [
+            ("squat", "depth", "knee < 75 => \"Go deeper\""),
+            ("squat", "broken", "knee < => \"x\""),
+            ("lunge", "depth", "knee < 80 => \"x\""),
+        ];
+        let rules = from_table(&table, "squat");
+        assert_eq!(rules.iter().map(Rule::code).collect::<Vec<_>>(), ["depth"]);
+    }
+}
@@ -0,0 +1,277 @@
+// wasm/src/segmentation.rs
+// Rep counting from the shape of the primary joint's angle signal rather than its velocity.
+// The angle zigzags between peaks and valleys; a turn is confirmed once the angle has come
+// back `hysteresis` degrees from its extreme, and only if the extreme stands `prominence`
+// degrees clear of the previous turn, so jitter and half reps never count. Runs live frame
+// by frame (PeakDetector) or over a whole recorded set afterwards (recount)
+use serde::{Deserialize, Serialize};
+
+// Degrees the angle has to come back from an extreme before it counts as a turn, above
+// the jitter of the pose models
+pub const DEFAULT_HYSTERESIS: f32 = 8.0;
+// Without an explicit prominence a rep has to cover this share of the profile range
+pub const DEFAULT_PROMINENCE_SHARE: f32 = 0.5;
+
+// Named in definition files in kebab-case
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RepDetector {
+    #[default]
+    Velocity, // Phase changes on the smoothed velocity, see RepCounter::detect_phase
+    Peaks,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Extreme {
+    Peak,
+    Valley,
+}
+
+impl Extreme {
+    fn opposite(self) -> Self {
+        match self {
+            Extreme::Peak => Extreme::Valley,
+            Extreme::Valley => Extreme::Peak,
+        }
+    }
+}
+
+// A confirmed peak or valley, timestamp in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    pub extreme: Extreme,
+    pub angle: f32,
+    pub timestamp: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeakDetector {
+    prominence: f32,
+    hysteresis: f32,
+    anchor: Option<Turn>,    // Last confirmed turn
+    low: Option<(f32, f32)>, // Lowest (angle, timestamp) since the anchor
+    high: Option<(f32, f32)>, // and highest
+}
+
+impl PeakDetector {
+    pub fn new(prominence: f32, hysteresis: f32) -> Self {
+        PeakDetector {
+            prominence,
+            hysteresis,
+            anchor: None,
+            low: None,
+            high: None,
+        }
+    }
+
+    // Timestamp in seconds. Returns the turn confirmed by this sample
+    pub fn push(&mut self, angle: f32, timestamp: f32) -> Option<Turn> {
+        let low = *self.low.get_or_insert((angle, timestamp));
+        let high = *self.high.get_or_insert((angle, timestamp));
+        if angle < low.0 {
+            self.low = Some((angle, timestamp));
+        }
+        if angle > high.0 {
+            self.high = Some((angle, timestamp));
+        }
+        let (low, high) = (self.low?, self.high?);
+
+        let turn = match self.anchor {
+            // The first turn is whichever end came first once the two are far enough apart
+            None if high.0 - low.0 >= self.prominence => {
+                let (extreme, (angle, timestamp)) = if low.1 < high.1 { (Extreme::Valley, low) } else { (Extreme::Peak, high) };
+                Turn { extreme, angle, timestamp }
+            }
+            None => return None,
+            // Seeking a peak. Dropping below the valley means it wasn't the bottom yet
+            Some(valley) if valley.extreme == Extreme::Valley => {
+                if low.0 < valley.angle {
+                    self.anchor = Some(Turn { angle: low.0, timestamp: low.1, ..valley });
+                    self.high = Some(low);
+                    return None;
+                }
+                if high.0 - angle < self.hysteresis || high.0 - valley.angle < self.prominence {
+                    return None;
+                }
+                Turn { extreme: Extreme::Peak, angle: high.0, timestamp: high.1 }
+            }
+            Some(peak) => {
+                if high.0 > peak.angle {
+                    self.anchor = Some(Turn { angle: high.0, timestamp: high.1, ..peak });
+                    self.low = Some(high);
+                    return None;
+                }
+                if angle - low.0 < self.hysteresis || peak.angle - low.0 < self.prominence {
+                    return None;
+                }
+                Turn { extreme: Extreme::Valley, angle: low.0, timestamp: low.1 }
+            }
+        };
+
+        self.anchor = Some(turn);
+        // The next extreme is sought from the turn on
+        self.low = Some((turn.angle.min(angle), if angle < turn.angle { timestamp } else { turn.timestamp }));
+        self.high = Some((turn.angle.max(angle), if angle > turn.angle { timestamp } else { turn.timestamp }));
+        Some(turn)
+    }
+
+    // Ends the signal: the extreme in progress becomes a turn without the hysteresis, if it
+    // stands `prominence` clear of the last one. Live, only once the set is over, until
+    // then the angle may still go further
+    pub fn flush(&mut self) -> Option<Turn> {
+        let anchor = self.anchor?;
+        let ((angle, timestamp), extreme) = match anchor.extreme {
+            Extreme::Valley => (self.high?, Extreme::Peak),
+            Extreme::Peak => (self.low?, Extreme::Valley),
+        };
+        if (angle - anchor.angle).abs() < self.prominence {
+            return None;
+        }
+
+        let turn = Turn { extreme, angle, timestamp };
+        self.anchor = Some(turn);
+        self.low = Some((angle, timestamp));
+        self.high = Some((angle, timestamp));
+        Some(turn)
+    }
+
+    pub fn last_turn(&self) -> Option<Turn> {
+        self.anchor
+    }
+
+    pub fn reset(&mut self) {
+        self.anchor = None;
+        self.low = None;
+        self.high = None;
+    }
+}
+
+// One rep found by recount, times in seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepSegment {
+    pub number: u32,
+    pub start: f32,
+    pub turn: f32, // The opposite extreme, e.g. the bottom of a squat
+    pub end: f32,
+    pub min_angle: f32,
+    pub max_angle: f32,
+}
+
+// A recorded set counted again offline, next to what was counted live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recount {
+    pub live: u32,
+    pub recounted: u32,
+    pub reps: Vec<RepSegment>,
+}
+
+// Reps in a recorded (timestamp in seconds, angle) signal, each ending on an `end` extreme
+// (peaks for lockout-ended lifts). Unlike the live detector the last rep counts without
+// waiting for the angle to come back
+pub fn recount(samples: &[(f32, f32)], prominence: f32, hysteresis: f32, end: Extreme) -> Vec<RepSegment> {
+    let mut detector = PeakDetector::new(prominence, hysteresis);
+    let mut turns: Vec<Turn> = samples.iter().filter_map(|&(timestamp, angle)| detector.push(angle, timestamp)).collect();
+    turns.extend(detector.flush());
+
+    let mut reps = Vec::new();
+    let mut start = samples.first().map_or(0.0, |&(timestamp, _)| timestamp);
+    for pair in turns.windows(2) {
+        let (turn, last) = (pair[0], pair[1]);
+        if last.extreme != end || turn.extreme != end.opposite() {
+            continue;
+        }
+        reps.push(RepSegment {
+            number: reps.len() as u32 + 1,
+            start,
+            turn: turn.timestamp,
+            end: last.timestamp,
+            min_angle: turn.angle.min(last.angle),
+            max_angle: turn.angle.max(last.angle),
+        });
+        start = last.timestamp;
+    }
+    reps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (timestamp, angle) at 30 fps, a cosine between `low` and `high` with a 2 s period
+    // starting at `high`, plus alternating jitter
+    fn signal(low: f32, high: f32, seconds: f32, jitter: f32) -> Vec<(f32, f32)> {
+        (0..=(seconds * 30.0) as usize)
+            .map(|frame| {
+                let t = frame as f32 / 30.0;
+                let wave = 0.5 + 0.5 * (t / 2.0 * std::f32::consts::TAU).cos();
+                let noise = if frame % 2 == 0 { jitter } else { -jitter };
+                (t, low + (high - low) * wave + noise)
+            })
+            .collect()
+    }
+
+    fn turns(samples: &[(f32, f32)], prominence: f32) -> Vec<Turn> {
+        let mut detector = PeakDetector::new(prominence, DEFAULT_HYSTERESIS);
+        samples.iter().filter_map(|&(timestamp, angle)| detector.push(angle, timestamp)).collect()
+    }
+
+    #[test]
+    fn turns_alternate_through_jitter() {
+        let turns = turns(&signal(90.0, 170.0, 6.0, 3.0), 40.0);
+        let extremes: Vec<Extreme> = turns.iter().map(|turn| turn.extreme).collect();
+        assert_eq!(
+            extremes,
+            [Extreme::Peak, Extreme::Valley, Extreme::Peak, Extreme::Valley, Extreme::Peak, Extreme::Valley]
+        );
+        // Each turn is the extreme itself, confirmed later once the angle came back
+        for turn in &turns {
+            let expected = if turn.extreme == Extreme::Peak { 170.0 } else { 90.0 };
+            assert!((turn.angle - expected).abs() <= 3.0, "{:?}", turn);
+            assert!((turn.timestamp % 1.0).min(1.0 - turn.timestamp % 1.0) < 0.1, "{:?}", turn);
+        }
+    }
+
+    #[test]
+    fn jitter_and_shallow_bumps_are_not_turns() {
+        assert!(turns(&signal(118.0, 122.0, 6.0, 3.0), 40.0).is_empty());
+        // 30 degrees of travel against a prominence of 40
+        assert!(turns(&signal(130.0, 160.0, 6.0, 0.0), 40.0).is_empty());
+    }
+
+    #[test]
+    fn the_anchor_follows_a_deeper_extreme() {
+        let mut detector = PeakDetector::new(20.0, 5.0);
+        let samples = [170.0, 140.0, 120.0, 140.0, 100.0, 90.0, 110.0, 130.0, 120.0];
+        let turns: Vec<Turn> =
+            samples.iter().enumerate().filter_map(|(i, &angle)| detector.push(angle, i as f32)).collect();
+        assert_eq!(turns.len(), 3);
+        assert_eq!((turns[1].extreme, turns[1].angle), (Extreme::Valley, 120.0));
+        assert_eq!((turns[2].extreme, turns[2].angle, turns[2].timestamp), (Extreme::Peak, 130.0, 7.0));
+        // Dropping to 90 after the valley at 120 was confirmed moved the bottom there, so
+        // the climb to 130 stands 40 clear of it
+        detector.reset();
+        assert_eq!(detector.last_turn(), None);
+    }
+
+    #[test]
+    fn recount_counts_the_last_rep_without_a_return() {
+        // Three reps of a squat ending on a lockout, the last one stopping at the top
+        let samples = signal(90.0, 170.0, 6.0, 2.0);
+        let reps = recount(&samples, 40.0, DEFAULT_HYSTERESIS, Extreme::Peak);
+        assert_eq!(reps.len(), 3);
+        assert_eq!(reps.iter().map(|rep| rep.number).collect::<Vec<_>>(), [1, 2, 3]);
+        for pair in reps.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        for rep in &reps {
+            assert!(rep.start < rep.turn && rep.turn < rep.end, "{:?}", rep);
+            assert!(rep.min_angle < 95.0 && rep.max_angle > 165.0, "{:?}", rep);
+        }
+
+        // Counted on valleys the same signal has three reps too, each ending at the bottom
+        let reps = recount(&samples, 40.0, DEFAULT_HYSTERESIS, Extreme::Valley);
+        assert_eq!(reps.len(), 3);
+        assert!(reps.iter().all(|rep| (rep.end % 2.0 - 1.0).abs() < 0.1), "{:?}", reps);
+    }
+}
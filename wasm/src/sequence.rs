@@ -0,0 +1,518 @@
+// wasm/src/sequence.rs
+// Multi-stage movements (burpee = squat -> plank -> push-up -> jump) and the step lists of
+// the exercise files (start -> lower). Each stage is a pose, either a rules.rs condition or
+// target angles with a tolerance; a rep counts once every stage has been reached in order.
+// The tracker only looks forward from the stage it expects, so poses the movement passes
+// through again on the way (the second squat of a burpee) are ignored, and a later stage
+// showing up first marks the ones in between as skipped
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::form::{FormIssue, Severity};
+use crate::logging;
+use crate::rep_counter;
+use crate::rules::{Condition, RuleContext};
+use crate::skeleton::Side;
+
+// A stage has to hold this long to count, so a single glitched frame can't skip ahead
+const MIN_STAGE_MS: f64 = 100.0;
+// An attempt that makes no progress for this long is dropped. Staying in the stage last
+// reached (resting at the top) doesn't count against it
+const STAGE_TIMEOUT_MS: f64 = 5000.0;
+
+// Names the exercise files give target angles, and what each measures. Joints are read
+// through the rep counter's aliases, so "knee" also finds "knee_flexion". Compound names
+// name the body segment moving at the joint measured
+const TARGET_NAMES: &[(&str, TargetAngle)] = &[
+    ("elbow", TargetAngle::Joint("elbow")),
+    ("shoulder", TargetAngle::Joint("shoulder")),
+    ("wrist", TargetAngle::Joint("wrist")),
+    ("hip", TargetAngle::Joint("hip")),
+    ("knee", TargetAngle::Joint("knee")),
+    ("ankle", TargetAngle::Joint("ankle")),
+    ("shoulderElbow", TargetAngle::Joint("shoulder")), // Upper arm against the torso
+    ("elbowWrist", TargetAngle::Joint("elbow")),       // Forearm against the upper arm
+    ("hipKnee", TargetAngle::Joint("hip")),            // Thigh against the torso
+    ("shoulderHip", TargetAngle::Joint("shoulder")),   // Torso against the upper arm, 180 in a plank
+    ("spineHip", TargetAngle::Joint("torso")),         // Lean of the back over the hips
+    ("torsoRotation", TargetAngle::Joint("torso_rotation")),
+    ("hipDip", TargetAngle::Joint("torso_lateral")), // Hips dropping to one side under the head
+    ("frontKnee", TargetAngle::Leading("knee")),
+    ("rearKnee", TargetAngle::Trailing("knee")),
+];
+
+// Exercise-file names with a meaning of their own, see TARGET_NAMES. Definitions may also
+// target the angles they define
+pub fn is_target_name(name: &str) -> bool {
+    TARGET_NAMES.iter().any(|(known, _)| *known == name)
+}
+
+// Angle keys a target name reads in `angles`, one per side for the names of a leading
+// or trailing limb. Empty when nothing there measures it
+pub fn target_keys(name: &str, angles: &HashMap<String, f32>) -> Vec<String> {
+    match TARGET_NAMES.iter().find(|(known, _)| *known == name).map(|&(_, angle)| angle) {
+        Some(TargetAngle::Joint(joint)) => rep_counter::joint_angle_key(joint, angles).into_iter().collect(),
+        Some(TargetAngle::Leading(joint) | TargetAngle::Trailing(joint)) => Side::BOTH
+            .into_iter()
+            .filter_map(|side| rep_counter::side_joint_angle_key(joint, side, angles))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TargetAngle {
+    Joint(&'static str),
+    // Per side, for poses with one side ahead of the other (the front knee of a split
+    // squat). Either side may lead
+    Leading(&'static str),
+    Trailing(&'static str),
+}
+
+impl TargetAngle {
+    fn read(self, angles: &HashMap<String, f32>, leading: Side) -> Option<f32> {
+        let trailing = match leading {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        };
+        match self {
+            TargetAngle::Joint(joint) => rep_counter::joint_angle(joint, angles),
+            TargetAngle::Leading(joint) => rep_counter::side_joint_angle(joint, leading, angles),
+            TargetAngle::Trailing(joint) => rep_counter::side_joint_angle(joint, trailing, angles),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Target {
+    name: String,
+    angle: Option<TargetAngle>, // None reads `name` as an angle key, for definitions' own angles
+    value: f32,
+}
+
+impl Target {
+    fn read(&self, angles: &HashMap<String, f32>, leading: Side) -> Option<f32> {
+        match self.angle {
+            Some(angle) => angle.read(angles, leading),
+            None => angles.get(&self.name).copied(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pose {
+    Condition(Condition),
+    Targets { targets: Vec<Target>, tolerance: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Stage {
+    name: String,
+    pose: Pose,
+    dwell_ms: f64,
+    cue: String, // What to do to reach the stage, shown when it is skipped
+}
+
+impl Stage {
+    pub fn parse(name: &str, condition: &str, cue: &str) -> Result<Stage, String> {
+        Ok(Stage {
+            name: name.to_string(),
+            pose: Pose::Condition(Condition::parse(condition)?),
+            dwell_ms: MIN_STAGE_MS,
+            cue: cue.to_string(),
+        })
+    }
+
+    // Reached when every target is within `tolerance` degrees. A target that can't be
+    // measured this frame keeps the stage from matching
+    pub fn targets(name: &str, targets: &HashMap<String, f32>, tolerance: f32, cue: &str) -> Stage {
+        let mut targets: Vec<Target> = targets
+            .iter()
+            .map(|(name, &value)| Target {
+                name: name.clone(),
+                angle: TARGET_NAMES.iter().find(|(known, _)| known == name).map(|&(_, angle)| angle),
+                value,
+            })
+            .collect();
+        targets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Stage {
+            name: name.to_string(),
+            pose: Pose::Targets { targets, tolerance },
+            dwell_ms: MIN_STAGE_MS,
+            cue: cue.to_string(),
+        }
+    }
+
+    // How long the pose has to hold before the stage counts, in milliseconds
+    pub fn dwell(mut self, dwell_ms: f64) -> Self {
+        self.dwell_ms = dwell_ms;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn matches(&self, context: &RuleContext) -> bool {
+        match &self.pose {
+            Pose::Condition(condition) => condition.holds(context) == Some(true),
+            Pose::Targets { targets, tolerance } => Side::BOTH.into_iter().any(|leading| {
+                targets.iter().all(|target| {
+                    target.read(context.angles, leading).is_some_and(|angle| (angle - target.value).abs() <= *tolerance)
+                })
+            }),
+        }
+    }
+
+    // Targets `angles` has no value for, e.g. one the exercise's analyzer never measures
+    pub fn unmeasured(&self, angles: &HashMap<String, f32>) -> Vec<&str> {
+        match &self.pose {
+            Pose::Condition(_) => Vec::new(),
+            Pose::Targets { targets, .. } => targets
+                .iter()
+                .filter(|target| Side::BOTH.into_iter().all(|leading| target.read(angles, leading).is_none()))
+                .map(|target| target.name.as_str())
+                .collect(),
+        }
+    }
+
+    fn label(&self) -> String {
+        self.name.replace('_', " ")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SequenceUpdate {
+    pub completed: bool, // Every stage reached in order, count the rep
+    pub issues: Vec<FormIssue>,
+}
+
+// Where the lifter is in the sequence, for step indicators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageStatus {
+    pub index: Option<usize>, // Stage last reached, None before the first one or after a timeout
+    pub name: Option<String>,
+    pub stage_count: usize,
+    pub completed: u32, // Full sequences this set
+}
+
+#[derive(Debug)]
+pub struct StageSequence {
+    stages: Vec<Stage>,
+    // Cycles end back on the first stage (start -> lower -> start), which also begins the
+    // next rep. Otherwise a rep ends on the last stage
+    cyclic: bool,
+    next: usize,                     // Stage expected next, 0 while waiting for a rep to start
+    reached: Option<usize>,          // Stage last reached
+    skipped: Vec<usize>,             // Stages skipped in the attempt in progress
+    candidate: Option<(usize, f64)>, // Stage the pose matches and since when, in milliseconds
+    last_progress: f64,
+    completed: u32,
+}
+
+impl StageSequence {
+    pub fn new(stages: Vec<Stage>) -> Self {
+        StageSequence {
+            stages,
+            cyclic: false,
+            next: 0,
+            reached: None,
+            skipped: Vec::new(),
+            candidate: None,
+            last_progress: 0.0,
+            completed: 0,
+        }
+    }
+
+    pub fn cycle(stages: Vec<Stage>) -> Self {
+        StageSequence { cyclic: true, ..StageSequence::new(stages) }
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn status(&self) -> StageStatus {
+        StageStatus {
+            index: self.reached,
+            name: self.reached.map(|index| self.stages[index].name.clone()),
+            stage_count: self.stages.len(),
+            completed: self.completed,
+        }
+    }
+
+    pub fn update(&mut self, context: &RuleContext) -> SequenceUpdate {
+        let mut update = SequenceUpdate::default();
+        let timestamp = context.timestamp;
+
+        if self.reached.is_some_and(|index| self.stages[index].matches(context)) {
+            self.last_progress = timestamp;
+        }
+        if self.next > 0 && timestamp - self.last_progress > STAGE_TIMEOUT_MS {
+            let stage = &self.stages[self.next % self.stages.len()];
+            let message = format!("Rep not finished, {}", stage.cue);
+            update.issues.push(FormIssue::new("incomplete_rep", Severity::Info, message));
+            self.restart();
+            self.reached = None;
+        }
+
+        // A rep only starts at the first stage and a cycle closes there, in between any
+        // stage ahead can match
+        let closing = self.next == self.stages.len();
+        let matched = if self.next == 0 || closing {
+            self.stages.first().filter(|stage| stage.matches(context)).map(|_| 0)
+        } else {
+            (self.next..self.stages.len()).find(|&index| self.stages[index].matches(context))
+        };
+        let Some(index) = matched else {
+            self.candidate = None;
+            return update;
+        };
+        let since = match self.candidate {
+            Some((candidate, since)) if candidate == index => since,
+            _ => self.candidate.insert((index, timestamp)).1,
+        };
+        if timestamp - since < self.stages[index].dwell_ms {
+            return update;
+        }
+        self.candidate = None;
+        self.reached = Some(index);
+        self.last_progress = timestamp;
+
+        if closing {
+            self.finish(&mut update);
+            self.next = 1;
+            return update;
+        }
+
+        for skipped in self.next..index {
+            let stage = &self.stages[skipped];
+            update.issues.push(FormIssue::new(format!("skipped_{}", stage.name), Severity::Warning, stage.cue.as_str()));
+            self.skipped.push(skipped);
+        }
+
+        if index + 1 < self.stages.len() {
+            self.next = index + 1;
+        } else if self.cyclic {
+            self.next = self.stages.len();
+        } else {
+            self.finish(&mut update);
+        }
+        update
+    }
+
+    pub fn reset(&mut self) {
+        self.restart();
+        self.reached = None;
+        self.last_progress = 0.0;
+        self.completed = 0;
+    }
+
+    // The attempt is over either way, it counts if nothing was skipped
+    fn finish(&mut self, update: &mut SequenceUpdate) {
+        if self.skipped.is_empty() {
+            update.completed = true;
+            self.completed += 1;
+        } else {
+            let skipped: Vec<String> = self.skipped.iter().map(|&index| self.stages[index].label()).collect();
+            let message = format!("Rep not counted, skipped the {}", skipped.join(" and the "));
+            update.issues.push(FormIssue::new("incomplete_rep", Severity::Warning, message));
+        }
+        self.restart();
+    }
+
+    fn restart(&mut self) {
+        self.next = 0;
+        self.skipped.clear();
+        self.candidate = None;
+    }
+}
+
+// Built-in stage tables: (exercise id, stage name, condition, cue), in order. A broken row
+// is logged and the exercise left without stages, counting from a partial sequence would
+// be wrong
+pub fn from_table(table: &[(&str, &str, &str, &str)], exercise_id: &str) -> Vec<Stage> {
+    table
+        .iter()
+        .filter(|(id, _, _, _)| *id == exercise_id)
+        .map(|(_, name, condition, cue)| {
+            Stage::parse(name, condition, cue).map_err(|e| format!("Stage '{}' for {}: {}", name, exercise_id, e))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| {
+            logging::error("sequence", &e);
+            Vec::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rep_counter::{MovementPhase, RepPosition};
+
+    // Feeds one pose for `ms` at 30 fps starting at `*clock`, merging the updates
+    fn hold(sequence: &mut StageSequence, pose: &[(&str, f32)], ms: f64, clock: &mut f64) -> SequenceUpdate {
+        let angles: HashMap<String, f32> = pose.iter().map(|&(key, value)| (key.to_string(), value)).collect();
+        let mut merged = SequenceUpdate::default();
+        let end = *clock + ms;
+        while *clock < end {
+            let context = RuleContext {
+                angles: &angles,
+                position: RepPosition { phase: MovementPhase::None, rom: None },
+                reps: 0,
+                phase_ms: 0.0,
+                timestamp: *clock,
+            };
+            let update = sequence.update(&context);
+            merged.completed |= update.completed;
+            merged.issues.extend(update.issues);
+            *clock += 1000.0 / 30.0;
+        }
+        merged
+    }
+
+    fn codes(update: &SequenceUpdate) -> Vec<&str> {
+        update.issues.iter().map(|issue| issue.code.as_str()).collect()
+    }
+
+    // squat -> plank -> jump, told apart by knee and hip
+    fn burpee() -> StageSequence {
+        StageSequence::new(vec![
+            Stage::parse("squat", "knee < 100", "Squat down").unwrap(),
+            Stage::parse("plank", "knee > 160 and hip > 160", "Kick back to a plank").unwrap(),
+            Stage::parse("jump", "knee > 160 and hip < 120", "Jump up").unwrap(),
+        ])
+    }
+
+    const SQUAT: [(&str, f32); 2] = [("knee", 80.0), ("hip", 70.0)];
+    const PLANK: [(&str, f32); 2] = [("knee", 175.0), ("hip", 175.0)];
+    const JUMP: [(&str, f32); 2] = [("knee", 175.0), ("hip", 100.0)];
+    const BETWEEN: [(&str, f32); 2] = [("knee", 130.0), ("hip", 140.0)];
+
+    #[test]
+    fn counts_stages_reached_in_order() {
+        let (mut sequence, mut clock) = (burpee(), 0.0);
+        assert!(!hold(&mut sequence, &SQUAT, 300.0, &mut clock).completed);
+        assert_eq!(sequence.status().name.as_deref(), Some("squat"));
+        assert!(!hold(&mut sequence, &PLANK, 300.0, &mut clock).completed);
+        // The second squat on the way up is passed over, only the jump is looked for
+        assert!(!hold(&mut sequence, &SQUAT, 300.0, &mut clock).completed);
+        assert_eq!(sequence.status().name.as_deref(), Some("plank"));
+        let update = hold(&mut sequence, &JUMP, 300.0, &mut clock);
+        assert!(update.completed && update.issues.is_empty());
+        assert_eq!(sequence.status().completed, 1);
+    }
+
+    #[test]
+    fn a_pose_has_to_dwell() {
+        let (mut sequence, mut clock) = (burpee(), 0.0);
+        hold(&mut sequence, &SQUAT, 50.0, &mut clock);
+        hold(&mut sequence, &BETWEEN, 100.0, &mut clock);
+        assert_eq!(sequence.status().index, None);
+
+        let mut slow = StageSequence::new(vec![
+            Stage::parse("squat", "knee < 100", "Squat down").unwrap().dwell(500.0),
+            Stage::parse("stand", "knee > 160", "Stand up").unwrap(),
+        ]);
+        hold(&mut slow, &SQUAT, 400.0, &mut clock);
+        assert_eq!(slow.status().index, None);
+        hold(&mut slow, &SQUAT, 200.0, &mut clock);
+        assert_eq!(slow.status().index, Some(0));
+    }
+
+    #[test]
+    fn skipping_a_stage_loses_the_rep() {
+        let (mut sequence, mut clock) = (burpee(), 0.0);
+        hold(&mut sequence, &SQUAT, 300.0, &mut clock);
+        let update = hold(&mut sequence, &JUMP, 300.0, &mut clock);
+        assert!(!update.completed);
+        assert_eq!(codes(&update), ["skipped_plank", "incomplete_rep"]);
+        assert!(update.issues[1].message.contains("plank"));
+        assert_eq!(sequence.status().completed, 0);
+    }
+
+    #[test]
+    fn stalled_attempts_time_out() {
+        let (mut sequence, mut clock) = (burpee(), 0.0);
+        hold(&mut sequence, &SQUAT, 300.0, &mut clock);
+        let update = hold(&mut sequence, &BETWEEN, STAGE_TIMEOUT_MS + 200.0, &mut clock);
+        assert_eq!(codes(&update), ["incomplete_rep"]);
+        assert!(update.issues[0].message.contains("Kick back"));
+        assert_eq!(sequence.status().index, None);
+    }
+
+    #[test]
+    fn cycles_close_on_the_first_stage() {
+        let targets = |knee: f32| HashMap::from([("knee".to_string(), knee)]);
+        let mut sequence = StageSequence::cycle(vec![
+            Stage::targets("start", &targets(170.0), 15.0, "Stand tall"),
+            Stage::targets("lower", &targets(90.0), 15.0, "Lower down"),
+        ]);
+        let mut clock = 0.0;
+        let mut reps = 0;
+        for _ in 0..3 {
+            reps += hold(&mut sequence, &[("knee", 172.0)], 300.0, &mut clock).completed as u32;
+            reps += hold(&mut sequence, &[("knee", 95.0)], 300.0, &mut clock).completed as u32;
+        }
+        reps += hold(&mut sequence, &[("knee", 165.0)], 300.0, &mut clock).completed as u32;
+        assert_eq!((reps, sequence.status().completed), (3, 3));
+    }
+
+    fn context(angles: &HashMap<String, f32>) -> RuleContext<'_> {
+        RuleContext {
+            angles,
+            position: RepPosition { phase: MovementPhase::None, rom: None },
+            reps: 0,
+            phase_ms: 0.0,
+            timestamp: 0.0,
+        }
+    }
+
+    fn angles(pairs: &[(&str, f32)]) -> HashMap<String, f32> {
+        pairs.iter().map(|&(key, value)| (key.to_string(), value)).collect()
+    }
+
+    fn targets(pairs: &[(&str, f32)]) -> Stage {
+        Stage::targets("pose", &angles(pairs), 10.0, "Get into position")
+    }
+
+    #[test]
+    fn target_names_read_the_angles_they_name() {
+        // Back analyzer keys: the upper arm at the shoulder, the forearm at the elbow
+        let stage = targets(&[("shoulderElbow", 90.0), ("elbowWrist", 170.0)]);
+        assert!(stage.matches(&context(&angles(&[("shoulder_flexion", 95.0), ("elbow", 165.0)]))));
+        assert!(!stage.matches(&context(&angles(&[("shoulder_flexion", 170.0), ("elbow", 95.0)]))));
+
+        let stage = targets(&[("hipDip", 20.0)]);
+        assert!(stage.matches(&context(&angles(&[("torso_lateral", 25.0)]))));
+    }
+
+    #[test]
+    fn either_side_can_lead() {
+        let stage = targets(&[("frontKnee", 90.0), ("rearKnee", 120.0)]);
+        let lunge = |left: f32, right: f32| angles(&[("left_knee_flexion", left), ("right_knee_flexion", right)]);
+        assert!(stage.matches(&context(&lunge(92.0, 118.0))));
+        assert!(stage.matches(&context(&lunge(118.0, 92.0))));
+        assert!(!stage.matches(&context(&lunge(92.0, 92.0))));
+    }
+
+    #[test]
+    fn every_target_has_to_be_measured() {
+        let stage = targets(&[("knee", 90.0), ("elbow", 90.0)]);
+        let knee_only = angles(&[("knee_flexion", 90.0)]);
+        assert!(!stage.matches(&context(&knee_only)));
+        assert_eq!(stage.unmeasured(&knee_only), ["elbow"]);
+        assert!(targets(&[("frontKnee", 90.0)]).unmeasured(&angles(&[("left_knee_flexion", 90.0)])).is_empty());
+    }
+
+    #[test]
+    fn a_broken_table_row_drops_every_stage() {
+        let table = [
+            ("burpees", "squat", "knee < 100", "Squat down"),
+            ("burpees", "plank", "knee >", "Kick back"),
+        ];
+        assert!(from_table(&table, "burpees").is_empty());
+        assert_eq!(from_table(&table[..1], "burpees").len(), 1);
+    }
+}
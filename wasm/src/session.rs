@@ -0,0 +1,221 @@
+// wasm/src/session.rs
+// Sets as the engine sees them: the reps reported between start_set and end_set. Fatigue is
+// judged by velocity loss, how much slower the latest rep moved than the fastest one of the
+// set, the usual velocity-based training measure
+use serde::{Deserialize, Serialize};
+
+use crate::logging;
+use crate::rep_counter::RepReport;
+
+// Velocity loss (0-1) that ends a set. The heavy compound lifts stop earlier, their form
+// goes well before the lifter runs out of reps
+const VELOCITY_LOSS_THRESHOLDS: &[(&str, f32)] = &[
+    ("squat", 0.2),
+    ("front-squats", 0.2),
+    ("goblet-squats", 0.2),
+    ("deadlifts", 0.2),
+    ("romanian-deadlifts", 0.2),
+    ("stiff-leg-deadlifts", 0.2),
+    ("barbell-hip-thrust", 0.25),
+    ("bench-press-barbell", 0.25),
+    ("bench-press-dumbbell", 0.25),
+    ("incline-bench-press", 0.25),
+    ("decline-bench-press", 0.25),
+    ("close-grip-bench-press", 0.25),
+    ("military-press", 0.25),
+    ("overhead-press", 0.25),
+    ("barbell-rows", 0.25),
+    ("pull-ups", 0.3),
+    ("chin-up-pull-ups", 0.3),
+];
+
+// Below this loss the trend is noise, too flat to project reps in reserve from
+const MIN_PROJECTED_LOSS: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SetConfig {
+    pub velocity_loss_threshold: f32,
+    pub failure_velocity_loss: f32, // Loss expected on the last rep the lifter could complete
+    pub min_reps: u32,              // Reps before the set can be called, the first one sets the pace
+}
+
+impl Default for SetConfig {
+    fn default() -> Self {
+        SetConfig {
+            velocity_loss_threshold: 0.3,
+            failure_velocity_loss: 0.5,
+            min_reps: 2,
+        }
+    }
+}
+
+impl SetConfig {
+    pub fn for_exercise(exercise_id: &str) -> Self {
+        let mut config = SetConfig::default();
+        if let Some(&(_, threshold)) = VELOCITY_LOSS_THRESHOLDS.iter().find(|(id, _)| *id == exercise_id) {
+            config.velocity_loss_threshold = threshold;
+        }
+        config
+    }
+}
+
+// State of the running set after the latest rep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetStatus {
+    pub set_number: u32,
+    pub reps: u32,
+    pub velocity_loss: f32,           // 0-1
+    pub reps_in_reserve: Option<f32>, // None until the velocity trend is clear enough to project
+    pub stop_set: bool,               // Velocity loss crossed the exercise's threshold
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetSummary {
+    pub set_number: u32,
+    pub reps: Vec<RepReport>,
+    pub duration: f64, // Seconds
+    pub average_quality: f32,
+    pub best_velocity: f32, // Degrees per second
+    pub velocity_loss: f32,
+    pub reps_in_reserve: Option<f32>,
+    pub stopped_for_fatigue: bool,
+}
+
+#[derive(Debug)]
+struct ActiveSet {
+    number: u32,
+    started_at: f64,
+    reps: Vec<RepReport>,
+    best_velocity: f32,
+    last_velocity: f32,
+}
+
+#[derive(Debug)]
+pub struct SetSession {
+    config: SetConfig,
+    current: Option<ActiveSet>,
+    completed: Vec<SetSummary>,
+}
+
+impl SetSession {
+    pub fn new(config: SetConfig) -> Self {
+        SetSession {
+            config,
+            current: None,
+            completed: Vec::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: SetConfig) {
+        self.config = config;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn completed_sets(&self) -> &[SetSummary] {
+        &self.completed
+    }
+
+    // Timestamps in seconds. A set still running is ended first. Returns the new set's number
+    pub fn start_set(&mut self, timestamp: f64) -> u32 {
+        self.end_set(timestamp);
+        let number = self.completed.len() as u32 + 1;
+        self.current = Some(ActiveSet {
+            number,
+            started_at: timestamp,
+            reps: Vec::new(),
+            best_velocity: 0.0,
+            last_velocity: 0.0,
+        });
+        logging::info("set", &format!("Set {} started", number));
+        number
+    }
+
+    // Reps outside a set are ignored
+    pub fn add_rep(&mut self, rep: &RepReport) -> Option<SetStatus> {
+        let set = self.current.as_mut()?;
+        let velocity = rep_velocity(rep);
+        set.reps.push(rep.clone());
+        set.best_velocity = set.best_velocity.max(velocity);
+        set.last_velocity = velocity;
+
+        let status = self.status()?;
+        if status.stop_set {
+            logging::info("set", &format!(
+                "Velocity loss {:.0}% after {} reps, stop the set",
+                status.velocity_loss * 100.0, status.reps
+            ));
+        }
+        Some(status)
+    }
+
+    pub fn status(&self) -> Option<SetStatus> {
+        let set = self.current.as_ref()?;
+        let reps = set.reps.len() as u32;
+        let velocity_loss = velocity_loss(set);
+
+        Some(SetStatus {
+            set_number: set.number,
+            reps,
+            velocity_loss,
+            reps_in_reserve: self.reps_in_reserve(reps, velocity_loss),
+            stop_set: reps >= self.config.min_reps && velocity_loss >= self.config.velocity_loss_threshold,
+        })
+    }
+
+    pub fn end_set(&mut self, timestamp: f64) -> Option<SetSummary> {
+        let status = self.status()?;
+        let set = self.current.take()?;
+
+        let average_quality = if set.reps.is_empty() {
+            0.0
+        } else {
+            set.reps.iter().map(|rep| rep.quality).sum::<f32>() / set.reps.len() as f32
+        };
+        let summary = SetSummary {
+            set_number: set.number,
+            duration: timestamp - set.started_at,
+            average_quality,
+            best_velocity: set.best_velocity,
+            velocity_loss: status.velocity_loss,
+            reps_in_reserve: status.reps_in_reserve,
+            stopped_for_fatigue: status.stop_set,
+            reps: set.reps,
+        };
+        logging::info("set", &format!("Set {} ended after {} reps", summary.set_number, summary.reps.len()));
+
+        self.completed.push(summary.clone());
+        Some(summary)
+    }
+
+    // Velocity loss grows roughly linearly with the share of possible reps done, so the
+    // loss so far against the loss expected at failure projects the total
+    fn reps_in_reserve(&self, reps: u32, velocity_loss: f32) -> Option<f32> {
+        if reps < self.config.min_reps || velocity_loss < MIN_PROJECTED_LOSS {
+            return None;
+        }
+        let projected = reps as f32 * self.config.failure_velocity_loss / velocity_loss;
+        Some((projected - reps as f32).max(0.0))
+    }
+}
+
+// Mean concentric velocity of the primary joint, or its peak when the rep had no clear
+// concentric phase (isometric and eccentric-only patterns)
+fn rep_velocity(rep: &RepReport) -> f32 {
+    if rep.concentric_time > 0.0 {
+        (rep.max_angle - rep.min_angle) / rep.concentric_time
+    } else {
+        rep.peak_velocity
+    }
+}
+
+fn velocity_loss(set: &ActiveSet) -> f32 {
+    if set.best_velocity > 0.0 {
+        ((set.best_velocity - set.last_velocity) / set.best_velocity).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,413 @@
+// wasm/src/skeleton.rs
+
+// Flat keypoint buffers come in two shapes, told apart by their length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypointFormat {
+    Xyc,  // x, y, confidence (MoveNet, 2D BlazePose)
+    Xyzv, // x, y, z, visibility (BlazePose GHUM with depth)
+}
+
+impl KeypointFormat {
+    pub fn values_per_keypoint(self) -> usize {
+        match self {
+            KeypointFormat::Xyc => 3,
+            KeypointFormat::Xyzv => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    pub z: Option<f32>, // Depth, only when the pose model provides it
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Landmark {
+    Nose,
+    LeftEyeInner,
+    LeftEye,
+    LeftEyeOuter,
+    RightEyeInner,
+    RightEye,
+    RightEyeOuter,
+    LeftEar,
+    RightEar,
+    MouthLeft,
+    MouthRight,
+    LeftShoulder,
+    RightShoulder,
+    LeftElbow,
+    RightElbow,
+    LeftWrist,
+    RightWrist,
+    LeftPinky,
+    RightPinky,
+    LeftIndex,
+    RightIndex,
+    LeftThumb,
+    RightThumb,
+    LeftHip,
+    RightHip,
+    LeftKnee,
+    RightKnee,
+    LeftAnkle,
+    RightAnkle,
+    LeftHeel,
+    RightHeel,
+    LeftFootIndex,
+    RightFootIndex,
+    // Not emitted by any model, derived from the shoulder/hip midpoints
+    Neck,
+    MidHip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub const BOTH: [Side; 2] = [Side::Left, Side::Right];
+
+    // Prefix used for per-side angle keys, e.g. "left_knee_flexion"
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+}
+
+// COCO keypoint order, shared by MoveNet
+const COCO_17: [Landmark; 17] = [
+    Landmark::Nose,
+    Landmark::LeftEye,
+    Landmark::RightEye,
+    Landmark::LeftEar,
+    Landmark::RightEar,
+    Landmark::LeftShoulder,
+    Landmark::RightShoulder,
+    Landmark::LeftElbow,
+    Landmark::RightElbow,
+    Landmark::LeftWrist,
+    Landmark::RightWrist,
+    Landmark::LeftHip,
+    Landmark::RightHip,
+    Landmark::LeftKnee,
+    Landmark::RightKnee,
+    Landmark::LeftAnkle,
+    Landmark::RightAnkle,
+];
+
+// BlazePose keypoint order
+const MEDIAPIPE_33: [Landmark; 33] = [
+    Landmark::Nose,
+    Landmark::LeftEyeInner,
+    Landmark::LeftEye,
+    Landmark::LeftEyeOuter,
+    Landmark::RightEyeInner,
+    Landmark::RightEye,
+    Landmark::RightEyeOuter,
+    Landmark::LeftEar,
+    Landmark::RightEar,
+    Landmark::MouthLeft,
+    Landmark::MouthRight,
+    Landmark::LeftShoulder,
+    Landmark::RightShoulder,
+    Landmark::LeftElbow,
+    Landmark::RightElbow,
+    Landmark::LeftWrist,
+    Landmark::RightWrist,
+    Landmark::LeftPinky,
+    Landmark::RightPinky,
+    Landmark::LeftIndex,
+    Landmark::RightIndex,
+    Landmark::LeftThumb,
+    Landmark::RightThumb,
+    Landmark::LeftHip,
+    Landmark::RightHip,
+    Landmark::LeftKnee,
+    Landmark::RightKnee,
+    Landmark::LeftAnkle,
+    Landmark::RightAnkle,
+    Landmark::LeftHeel,
+    Landmark::RightHeel,
+    Landmark::LeftFootIndex,
+    Landmark::RightFootIndex,
+];
+
+// Connections drawn for an overlay. Bones whose ends a layout doesn't provide
+// (hands and feet in COCO-17) simply come back invisible
+pub const BONES: [(Landmark, Landmark); 22] = [
+    // Head
+    (Landmark::Nose, Landmark::LeftEye),
+    (Landmark::Nose, Landmark::RightEye),
+    (Landmark::LeftEye, Landmark::LeftEar),
+    (Landmark::RightEye, Landmark::RightEar),
+    // Torso
+    (Landmark::LeftShoulder, Landmark::RightShoulder),
+    (Landmark::LeftShoulder, Landmark::LeftHip),
+    (Landmark::RightShoulder, Landmark::RightHip),
+    (Landmark::LeftHip, Landmark::RightHip),
+    // Arms
+    (Landmark::LeftShoulder, Landmark::LeftElbow),
+    (Landmark::LeftElbow, Landmark::LeftWrist),
+    (Landmark::LeftWrist, Landmark::LeftIndex),
+    (Landmark::RightShoulder, Landmark::RightElbow),
+    (Landmark::RightElbow, Landmark::RightWrist),
+    (Landmark::RightWrist, Landmark::RightIndex),
+    // Legs
+    (Landmark::LeftHip, Landmark::LeftKnee),
+    (Landmark::LeftKnee, Landmark::LeftAnkle),
+    (Landmark::LeftAnkle, Landmark::LeftHeel),
+    (Landmark::LeftHeel, Landmark::LeftFootIndex),
+    (Landmark::RightHip, Landmark::RightKnee),
+    (Landmark::RightKnee, Landmark::RightAnkle),
+    (Landmark::RightAnkle, Landmark::RightHeel),
+    (Landmark::RightHeel, Landmark::RightFootIndex),
+];
+
+impl Landmark {
+    // Snake case names, matching MoveNet keypoint names and the exercise JSON trackedJoints
+    pub fn name(self) -> &'static str {
+        match self {
+            Landmark::Nose => "nose",
+            Landmark::LeftEyeInner => "left_eye_inner",
+            Landmark::LeftEye => "left_eye",
+            Landmark::LeftEyeOuter => "left_eye_outer",
+            Landmark::RightEyeInner => "right_eye_inner",
+            Landmark::RightEye => "right_eye",
+            Landmark::RightEyeOuter => "right_eye_outer",
+            Landmark::LeftEar => "left_ear",
+            Landmark::RightEar => "right_ear",
+            Landmark::MouthLeft => "mouth_left",
+            Landmark::MouthRight => "mouth_right",
+            Landmark::LeftShoulder => "left_shoulder",
+            Landmark::RightShoulder => "right_shoulder",
+            Landmark::LeftElbow => "left_elbow",
+            Landmark::RightElbow => "right_elbow",
+            Landmark::LeftWrist => "left_wrist",
+            Landmark::RightWrist => "right_wrist",
+            Landmark::LeftPinky => "left_pinky",
+            Landmark::RightPinky => "right_pinky",
+            Landmark::LeftIndex => "left_index",
+            Landmark::RightIndex => "right_index",
+            Landmark::LeftThumb => "left_thumb",
+            Landmark::RightThumb => "right_thumb",
+            Landmark::LeftHip => "left_hip",
+            Landmark::RightHip => "right_hip",
+            Landmark::LeftKnee => "left_knee",
+            Landmark::RightKnee => "right_knee",
+            Landmark::LeftAnkle => "left_ankle",
+            Landmark::RightAnkle => "right_ankle",
+            Landmark::LeftHeel => "left_heel",
+            Landmark::RightHeel => "right_heel",
+            Landmark::LeftFootIndex => "left_foot_index",
+            Landmark::RightFootIndex => "right_foot_index",
+            Landmark::Neck => "neck",
+            Landmark::MidHip => "mid_hip",
+        }
+    }
+
+    // Swaps left and right landmarks, midline landmarks stay as they are
+    pub fn mirrored(self) -> Landmark {
+        match self {
+            Landmark::LeftEyeInner => Landmark::RightEyeInner,
+            Landmark::LeftEye => Landmark::RightEye,
+            Landmark::LeftEyeOuter => Landmark::RightEyeOuter,
+            Landmark::RightEyeInner => Landmark::LeftEyeInner,
+            Landmark::RightEye => Landmark::LeftEye,
+            Landmark::RightEyeOuter => Landmark::LeftEyeOuter,
+            Landmark::LeftEar => Landmark::RightEar,
+            Landmark::RightEar => Landmark::LeftEar,
+            Landmark::MouthLeft => Landmark::MouthRight,
+            Landmark::MouthRight => Landmark::MouthLeft,
+            Landmark::LeftShoulder => Landmark::RightShoulder,
+            Landmark::RightShoulder => Landmark::LeftShoulder,
+            Landmark::LeftElbow => Landmark::RightElbow,
+            Landmark::RightElbow => Landmark::LeftElbow,
+            Landmark::LeftWrist => Landmark::RightWrist,
+            Landmark::RightWrist => Landmark::LeftWrist,
+            Landmark::LeftPinky => Landmark::RightPinky,
+            Landmark::RightPinky => Landmark::LeftPinky,
+            Landmark::LeftIndex => Landmark::RightIndex,
+            Landmark::RightIndex => Landmark::LeftIndex,
+            Landmark::LeftThumb => Landmark::RightThumb,
+            Landmark::RightThumb => Landmark::LeftThumb,
+            Landmark::LeftHip => Landmark::RightHip,
+            Landmark::RightHip => Landmark::LeftHip,
+            Landmark::LeftKnee => Landmark::RightKnee,
+            Landmark::RightKnee => Landmark::LeftKnee,
+            Landmark::LeftAnkle => Landmark::RightAnkle,
+            Landmark::RightAnkle => Landmark::LeftAnkle,
+            Landmark::LeftHeel => Landmark::RightHeel,
+            Landmark::RightHeel => Landmark::LeftHeel,
+            Landmark::LeftFootIndex => Landmark::RightFootIndex,
+            Landmark::RightFootIndex => Landmark::LeftFootIndex,
+            Landmark::Nose | Landmark::Neck | Landmark::MidHip => self,
+        }
+    }
+
+    // Analyzers describe joints from the left side; this maps them onto the side being measured
+    pub fn on_side(self, side: Side) -> Landmark {
+        match side {
+            Side::Left => self,
+            Side::Right => self.mirrored(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Landmark> {
+        MEDIAPIPE_33
+            .iter()
+            .chain([Landmark::Neck, Landmark::MidHip].iter())
+            .copied()
+            .find(|landmark| landmark.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeypointLayout {
+    Coco17,
+    MediaPipe33,
+    #[default]
+    MoveNet, // MoveNet Lightning/Thunder, COCO ordering. usePoseDetection.js runs SinglePose Thunder
+}
+
+impl KeypointLayout {
+    pub fn from_name(name: &str) -> Option<KeypointLayout> {
+        match name.to_ascii_lowercase().as_str() {
+            "coco" | "coco17" | "coco-17" => Some(KeypointLayout::Coco17),
+            "mediapipe" | "mediapipe33" | "mediapipe-33" | "blazepose" => Some(KeypointLayout::MediaPipe33),
+            "movenet" => Some(KeypointLayout::MoveNet),
+            _ => None,
+        }
+    }
+
+    pub fn landmarks(self) -> &'static [Landmark] {
+        match self {
+            KeypointLayout::Coco17 | KeypointLayout::MoveNet => &COCO_17,
+            KeypointLayout::MediaPipe33 => &MEDIAPIPE_33,
+        }
+    }
+
+    pub fn keypoint_count(self) -> usize {
+        self.landmarks().len()
+    }
+
+    pub fn index_of(self, landmark: Landmark) -> Option<usize> {
+        self.landmarks().iter().position(|&l| l == landmark)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    layout: KeypointLayout,
+    format: KeypointFormat,
+    points: Vec<Keypoint>,
+}
+
+impl Skeleton {
+    pub fn from_flat(layout: KeypointLayout, flat_array: &[f32]) -> Result<Skeleton, String> {
+        let count = layout.keypoint_count();
+        let format = [KeypointFormat::Xyc, KeypointFormat::Xyzv]
+            .into_iter()
+            .find(|format| flat_array.len() == count * format.values_per_keypoint())
+            .ok_or_else(|| format!(
+                "Invalid keypoints array length for {:?}. Expected {} (x, y, confidence) or {} (x, y, z, visibility), got {}",
+                layout,
+                count * KeypointFormat::Xyc.values_per_keypoint(),
+                count * KeypointFormat::Xyzv.values_per_keypoint(),
+                flat_array.len()
+            ))?;
+
+        let points = flat_array
+            .chunks_exact(format.values_per_keypoint())
+            .map(|chunk| match format {
+                KeypointFormat::Xyc => Keypoint { x: chunk[0], y: chunk[1], z: None, confidence: chunk[2] },
+                KeypointFormat::Xyzv => Keypoint { x: chunk[0], y: chunk[1], z: Some(chunk[2]), confidence: chunk[3] },
+            })
+            .collect();
+
+        Ok(Skeleton { layout, format, points })
+    }
+
+    // Builds a skeleton from a per-landmark lookup, e.g. keypoints keyed by name.
+    // Landmarks the lookup doesn't find are stored with zero confidence
+    pub fn from_landmarks<F>(layout: KeypointLayout, lookup: F) -> Skeleton
+    where
+        F: Fn(Landmark) -> Option<Keypoint>,
+    {
+        let found: Vec<Option<Keypoint>> = layout.landmarks().iter().map(|&landmark| lookup(landmark)).collect();
+        let format = if found.iter().flatten().all(|point| point.z.is_some()) && found.iter().any(Option::is_some) {
+            KeypointFormat::Xyzv
+        } else {
+            KeypointFormat::Xyc
+        };
+        let points = found
+            .into_iter()
+            .map(|point| match (point, format) {
+                (Some(point), KeypointFormat::Xyc) => Keypoint { z: None, ..point },
+                (Some(point), KeypointFormat::Xyzv) => point,
+                (None, _) => Keypoint::default(),
+            })
+            .collect();
+
+        Skeleton { layout, format, points }
+    }
+
+    pub fn layout(&self) -> KeypointLayout {
+        self.layout
+    }
+
+    // Raw keypoints in layout order, for stages that rewrite them (e.g. smoothing)
+    pub fn points_mut(&mut self) -> &mut [Keypoint] {
+        &mut self.points
+    }
+
+    pub fn has_depth(&self) -> bool {
+        self.format == KeypointFormat::Xyzv
+    }
+
+    // Landmarks the layout doesn't provide come back with zero confidence,
+    // which the angle helpers already treat as "not visible"
+    pub fn point(&self, landmark: Landmark) -> Keypoint {
+        match landmark {
+            Landmark::Neck => self.midpoint(Landmark::LeftShoulder, Landmark::RightShoulder),
+            Landmark::MidHip => self.midpoint(Landmark::LeftHip, Landmark::RightHip),
+            _ => self
+                .layout
+                .index_of(landmark)
+                .and_then(|index| self.points.get(index).copied())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn confidence(&self, landmark: Landmark) -> f32 {
+        self.point(landmark).confidence
+    }
+
+    // Landmarks the detector supplied but isn't confident about this frame
+    pub fn occluded_landmarks(&self, min_confidence: f32) -> Vec<Landmark> {
+        self.layout
+            .landmarks()
+            .iter()
+            .zip(&self.points)
+            .filter(|(_, point)| point.confidence < min_confidence)
+            .map(|(&landmark, _)| landmark)
+            .collect()
+    }
+
+    fn midpoint(&self, a: Landmark, b: Landmark) -> Keypoint {
+        let (a, b) = (self.point(a), self.point(b));
+        Keypoint {
+            x: (a.x + b.x) / 2.0,
+            y: (a.y + b.y) / 2.0,
+            z: a.z.zip(b.z).map(|(az, bz)| (az + bz) / 2.0),
+            confidence: a.confidence.min(b.confidence),
+        }
+    }
+}
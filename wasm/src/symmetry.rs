@@ -0,0 +1,150 @@
+// wasm/src/symmetry.rs
+use std::collections::{HashMap, VecDeque};
+
+use crate::form::{FormIssue, Severity};
+use crate::skeleton::Side;
+
+const WINDOW_SECONDS: f32 = 3.0; // Roughly one to two reps
+const MIN_ROM_DEGREES: f32 = 10.0; // Below this the joint is effectively still
+const MIN_SAMPLES: usize = 10;
+
+// (left, right) angle per joint name
+type JointPairs = HashMap<String, (f32, f32)>;
+
+#[derive(Debug, Default)]
+pub struct SymmetryTracker {
+    // Per frame: timestamp and (left, right) angle for every joint seen on both sides
+    history: VecDeque<(f32, JointPairs)>,
+}
+
+#[derive(Debug)]
+struct JointRange {
+    min: f32,
+    max: f32,
+}
+
+impl JointRange {
+    fn new() -> Self {
+        JointRange { min: f32::MAX, max: f32::MIN }
+    }
+
+    fn include(&mut self, angle: f32) {
+        self.min = self.min.min(angle);
+        self.max = self.max.max(angle);
+    }
+
+    fn rom(&self) -> f32 {
+        (self.max - self.min).max(0.0)
+    }
+
+    // Where the angle sits within this range, 0.0 at the bottom and 1.0 at the top
+    fn normalize(&self, angle: f32) -> f32 {
+        if self.rom() > 0.0 { (angle - self.min) / self.rom() } else { 0.0 }
+    }
+}
+
+impl SymmetryTracker {
+    pub fn new() -> Self {
+        SymmetryTracker { history: VecDeque::new() }
+    }
+
+    pub fn update(&mut self, angles: &HashMap<String, f32>, timestamp: f32) {
+        let pairs = Self::joint_pairs(angles);
+        self.history.push_back((timestamp, pairs));
+
+        while let Some(&(oldest, _)) = self.history.front() {
+            if timestamp - oldest > WINDOW_SECONDS {
+                self.history.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Symmetry index per joint for the latest frame: |L - R| / mean(L, R) * 100,
+    // so 0 is perfectly symmetric
+    pub fn symmetry_index(&self) -> HashMap<String, f32> {
+        self.history
+            .back()
+            .map(|(_, pairs)| {
+                pairs
+                    .iter()
+                    .filter(|(_, &(left, right))| left + right > 0.0)
+                    .map(|(joint, &(left, right))| {
+                        (joint.clone(), (left - right).abs() / ((left + right) / 2.0) * 100.0)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Compares both sides over the recent window. Tolerance is a fraction of the
+    // larger ROM for range differences, and of the full range for timing lag
+    pub fn check_symmetry(&self, tolerance: f32) -> Vec<FormIssue> {
+        let mut errors = Vec::new();
+        if self.history.len() < MIN_SAMPLES {
+            return errors;
+        }
+
+        let mut ranges: HashMap<&str, (JointRange, JointRange)> = HashMap::new();
+        for (_, pairs) in &self.history {
+            for (joint, &(left, right)) in pairs {
+                let (left_range, right_range) = ranges
+                    .entry(joint.as_str())
+                    .or_insert_with(|| (JointRange::new(), JointRange::new()));
+                left_range.include(left);
+                right_range.include(right);
+            }
+        }
+
+        let mut joints: Vec<_> = ranges.keys().copied().collect();
+        joints.sort_unstable();
+
+        for joint in joints {
+            let (left_range, right_range) = &ranges[joint];
+            let (left_rom, right_rom) = (left_range.rom(), right_range.rom());
+            let larger_rom = left_rom.max(right_rom);
+            if larger_rom < MIN_ROM_DEGREES {
+                continue;
+            }
+
+            // ROM: one side travelling noticeably less than the other
+            if (left_rom - right_rom).abs() / larger_rom > tolerance {
+                let message = format!(
+                    "Uneven {} range (left {}°, right {}°)",
+                    joint.replace('_', " "),
+                    left_rom.round(),
+                    right_rom.round()
+                );
+                errors.push(FormIssue::new(format!("uneven_{}_range", joint), Severity::Warning, message).on_joint(joint));
+                continue;
+            }
+
+            // Timing: both sides cover the range but not at the same moment
+            let (lag_sum, samples) = self
+                .history
+                .iter()
+                .filter_map(|(_, pairs)| pairs.get(joint))
+                .fold((0.0, 0), |(sum, count), &(left, right)| {
+                    (sum + (left_range.normalize(left) - right_range.normalize(right)).abs(), count + 1)
+                });
+            if samples > 0 && lag_sum / samples as f32 > tolerance {
+                let message = format!("Left and right {} out of sync", joint.replace('_', " "));
+                errors.push(FormIssue::new(format!("{}_out_of_sync", joint), Severity::Warning, message).on_joint(joint));
+            }
+        }
+
+        errors
+    }
+
+    fn joint_pairs(angles: &HashMap<String, f32>) -> JointPairs {
+        angles
+            .iter()
+            .filter_map(|(key, &left)| {
+                let joint = key.strip_prefix(Side::Left.prefix())?.strip_prefix('_')?;
+                let right = *angles.get(&format!("{}_{}", Side::Right.prefix(), joint))?;
+                Some((joint.to_string(), (left, right)))
+            })
+            .collect()
+    }
+}
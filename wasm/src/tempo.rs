@@ -0,0 +1,176 @@
+// wasm/src/tempo.rs
+// Tempo prescriptions in the usual eccentric-pause-concentric-pause notation, e.g. "3-1-2-0"
+// or "31X0". Each part is in seconds, "X" asks for an explosive phase and leaves it unchecked.
+// The bottom pause follows the eccentric, the top pause the concentric, whichever end of the
+// joint's range that is for the exercise
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::form::{FormIssue, Severity};
+use crate::rep_counter::RepReport;
+
+// Allowed drift per phase: a fixed margin for detector and phase-detection lag, plus a share
+// of the target so long phases aren't held to the same absolute precision as short ones
+const TOLERANCE_SECONDS: f32 = 0.5;
+const TOLERANCE_SHARE: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tempo {
+    pub eccentric: Option<f32>, // None = explosive
+    pub bottom_pause: Option<f32>,
+    pub concentric: Option<f32>,
+    pub top_pause: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TempoPart {
+    Eccentric,
+    BottomPause,
+    Concentric,
+    TopPause,
+}
+
+impl TempoPart {
+    const ALL: [TempoPart; 4] = [TempoPart::Eccentric, TempoPart::BottomPause, TempoPart::Concentric, TempoPart::TopPause];
+
+    fn code(self) -> &'static str {
+        match self {
+            TempoPart::Eccentric => "eccentric",
+            TempoPart::BottomPause => "bottom_pause",
+            TempoPart::Concentric => "concentric",
+            TempoPart::TopPause => "top_pause",
+        }
+    }
+
+    fn actual(self, report: &RepReport) -> f32 {
+        match self {
+            TempoPart::Eccentric => report.eccentric_time,
+            TempoPart::BottomPause => report.bottom_pause_time,
+            TempoPart::Concentric => report.concentric_time,
+            TempoPart::TopPause => report.top_pause_time,
+        }
+    }
+
+    fn message(self, too_fast: bool, actual: f32, target: f32) -> String {
+        let what = match (self, too_fast) {
+            (TempoPart::Eccentric, true) => "Too fast on the way down",
+            (TempoPart::Eccentric, false) => "Too slow on the way down",
+            (TempoPart::Concentric, true) => "Too fast on the way up",
+            (TempoPart::Concentric, false) => "Too slow on the way up",
+            (TempoPart::BottomPause, true) => "Pause longer at the bottom",
+            (TempoPart::BottomPause, false) => "Pausing too long at the bottom",
+            (TempoPart::TopPause, true) => "Pause longer at the top",
+            (TempoPart::TopPause, false) => "Pausing too long at the top",
+        };
+        format!("{} ({:.1}s, aim for {}s)", what, actual, target)
+    }
+}
+
+impl Tempo {
+    pub fn parse(spec: &str) -> Result<Tempo, String> {
+        let spec = spec.trim();
+        let parts: Vec<&str> = if spec.contains('-') {
+            spec.split('-').map(str::trim).collect()
+        } else {
+            // Compact form, one character per part
+            spec.char_indices().map(|(i, c)| &spec[i..i + c.len_utf8()]).collect()
+        };
+        if parts.len() != 4 {
+            return Err(format!("Tempo '{}' needs 4 parts, eccentric-pause-concentric-pause", spec));
+        }
+
+        let mut seconds = [None; 4];
+        for (slot, part) in seconds.iter_mut().zip(&parts) {
+            *slot = match *part {
+                "X" | "x" => None,
+                text => Some(
+                    text.parse::<f32>()
+                        .ok()
+                        .filter(|value| *value >= 0.0)
+                        .ok_or_else(|| format!("Invalid tempo part '{}' in '{}'", text, spec))?,
+                ),
+            };
+        }
+
+        Ok(Tempo {
+            eccentric: seconds[0],
+            bottom_pause: seconds[1],
+            concentric: seconds[2],
+            top_pause: seconds[3],
+        })
+    }
+
+    fn target(&self, part: TempoPart) -> Option<f32> {
+        match part {
+            TempoPart::Eccentric => self.eccentric,
+            TempoPart::BottomPause => self.bottom_pause,
+            TempoPart::Concentric => self.concentric,
+            TempoPart::TopPause => self.top_pause,
+        }
+    }
+
+    // Issues for every prescribed phase of the rep that drifted past tolerance
+    pub fn check(&self, report: &RepReport) -> Vec<FormIssue> {
+        TempoPart::ALL
+            .iter()
+            .filter_map(|&part| {
+                let target = self.target(part)?;
+                let actual = part.actual(report);
+                let tolerance = TOLERANCE_SECONDS + TOLERANCE_SHARE * target;
+                let too_fast = actual < target - tolerance;
+                let too_slow = actual > target + tolerance;
+                (too_fast || too_slow).then(|| {
+                    let pace = if too_fast { "too_fast" } else { "too_slow" };
+                    FormIssue::new(format!("{}_{}", part.code(), pace), Severity::Info, part.message(too_fast, actual, target))
+                        .target((target - tolerance).max(0.0), target + tolerance)
+                })
+            })
+            .collect()
+    }
+
+    // 0-100, the average over prescribed phases of how close each came to its target.
+    // Drift inside the tolerance is free, beyond it a phase loses credit in proportion
+    // to its target (at least a second)
+    pub fn adherence(&self, report: &RepReport) -> f32 {
+        let scores: Vec<f32> = TempoPart::ALL
+            .iter()
+            .filter_map(|&part| {
+                let target = self.target(part)?;
+                let tolerance = TOLERANCE_SECONDS + TOLERANCE_SHARE * target;
+                let excess = ((part.actual(report) - target).abs() - tolerance).max(0.0);
+                Some(1.0 - (excess / target.max(1.0)).clamp(0.0, 1.0))
+            })
+            .collect();
+
+        if scores.is_empty() {
+            100.0
+        } else {
+            100.0 * scores.iter().sum::<f32>() / scores.len() as f32
+        }
+    }
+}
+
+impl fmt::Display for Tempo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = TempoPart::ALL
+            .iter()
+            .map(|&part| self.target(part).map_or_else(|| "X".to_string(), |seconds| seconds.to_string()))
+            .collect();
+        f.write_str(&parts.join("-"))
+    }
+}
+
+impl TryFrom<String> for Tempo {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, Self::Error> {
+        Tempo::parse(&spec)
+    }
+}
+
+impl From<Tempo> for String {
+    fn from(tempo: Tempo) -> Self {
+        tempo.to_string()
+    }
+}
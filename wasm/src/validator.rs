@@ -0,0 +1,118 @@
+// wasm/src/validator.rs
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+use crate::analyzer::ExerciseAnalyzer;
+use crate::definition::ExerciseSet;
+use crate::hold::HoldReport;
+use crate::recognition::Recognition;
+use crate::reference::ReferenceRep;
+use crate::rep_counter::RepReport;
+use crate::sequence::StageStatus;
+use crate::segmentation::{Recount, RepDetector};
+use crate::session::{SetStatus, SetSummary};
+use crate::tempo::Tempo;
+use crate::skeleton::{Keypoint, KeypointLayout, Skeleton};
+
+// One entry of the { left_shoulder: { x, y, score }, ... } object the JS validators take
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedKeypoint {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub z: Option<f32>,
+    #[serde(default, alias = "confidence", alias = "visibility")]
+    pub score: Option<f32>,
+}
+
+// Same shape as the JS ExerciseValidator so src/libs/wasm/adapter.js can swap either in
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationResult {
+    pub angles: HashMap<String, f32>,
+    pub errors: Vec<String>,
+    pub rep_detected: bool,
+    pub rep_count: u32,
+    pub rep_report: Option<RepReport>, // Set on the frame a rep completes
+    pub set_status: Option<SetStatus>,
+    pub hold: Option<HoldReport>, // Isometric exercises only
+    pub recognition: Option<Recognition>, // Once enabled with set_recognition
+    pub stage: Option<StageStatus>, // Step last reached, for StepIndicator
+}
+
+#[derive(Debug)]
+pub struct ExerciseValidator {
+    analyzer: ExerciseAnalyzer,
+}
+
+impl ExerciseValidator {
+    pub fn new(exercise_id: &str, layout: KeypointLayout) -> Self {
+        ExerciseValidator { analyzer: ExerciseAnalyzer::new(exercise_id, layout) }
+    }
+
+    pub fn load_definitions(&mut self, set: &ExerciseSet) -> Result<Vec<String>, String> {
+        self.analyzer.load_definitions(set)
+    }
+
+    pub fn set_tempo(&mut self, tempo: Option<Tempo>) {
+        self.analyzer.set_tempo(tempo);
+    }
+
+    pub fn set_recognition(&mut self, enabled: bool) {
+        self.analyzer.set_recognition(enabled);
+    }
+
+    pub fn set_rep_detector(&mut self, detector: Option<RepDetector>) {
+        self.analyzer.set_rep_detector(detector);
+    }
+
+    pub fn set_reference(&mut self, reference: Option<ReferenceRep>) -> Result<(), String> {
+        self.analyzer.set_reference(reference)
+    }
+
+    pub fn record_reference(&mut self) {
+        self.analyzer.record_reference();
+    }
+
+    pub fn reference(&self) -> Option<&ReferenceRep> {
+        self.analyzer.reference()
+    }
+
+    pub fn start_set(&mut self) -> u32 {
+        self.analyzer.start_set()
+    }
+
+    pub fn end_set(&mut self) -> Option<SetSummary> {
+        self.analyzer.end_set()
+    }
+
+    pub fn recount_set(&self) -> Option<Recount> {
+        self.analyzer.recount_set()
+    }
+
+    // Keypoints keyed by landmark name ("left_shoulder", ...), timestamp in milliseconds.
+    // Points without a score are taken as fully confident, missing landmarks as not visible
+    pub fn validate_pose(&mut self, keypoints: &HashMap<String, NamedKeypoint>, timestamp: f64) -> ValidationResult {
+        let skeleton = Skeleton::from_landmarks(self.analyzer.layout(), |landmark| {
+            keypoints.get(landmark.name()).map(|point| Keypoint {
+                x: point.x,
+                y: point.y,
+                z: point.z,
+                confidence: point.score.unwrap_or(1.0),
+            })
+        });
+
+        let analysis = self.analyzer.analyze(skeleton, timestamp);
+        ValidationResult {
+            angles: analysis.angles,
+            errors: analysis.form_errors,
+            rep_detected: analysis.rep_detected,
+            rep_count: analysis.rep_count,
+            rep_report: analysis.rep_report,
+            set_status: analysis.set_status,
+            hold: analysis.hold,
+            recognition: analysis.recognition,
+            stage: analysis.stage,
+        }
+    }
+}